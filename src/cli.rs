@@ -0,0 +1,250 @@
+use crate::config::{ Config, edge::Downstream, format::Format, restart::RestartPolicy };
+use crate::config::sinks::{ FileSinkConfig, SinkConfigs };
+use crate::config::sources::{ BinanceSpotSourceConfig, SourceConfigs };
+use crate::objects::Objects;
+use crate::utils::binary::{ self, HEADER_SIZE, Header };
+use crate::utils::{ clock::ClockKind, time::datetime_from_ts };
+
+use clap::{ Parser, Subcommand };
+use std::collections::BTreeMap;
+use std::io::{ BufRead, BufReader, Read };
+
+#[derive(Parser)]
+#[command(name = "main", version, about = "Runs, replays and records holocore pipelines")]
+pub struct Cli {
+    #[arg(long, global = true, help = "Log filter overriding the config and RUST_LOG, e.g. debug or holocore::processor=trace")]
+    pub log_level: Option<String>,
+    #[command(subcommand)]
+    pub command: Command
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    #[command(about = "Runs the pipeline of a config")]
+    Run {
+        config: String,
+        #[arg(long = "set", value_name = "KEY=VALUE", help = "Overrides a config value, e.g. sources.FileSource.filename=trades.json")]
+        sets: Vec<String>
+    },
+    #[command(about = "Checks a config without running it")]
+    Validate {
+        config: String,
+        #[arg(long = "set", value_name = "KEY=VALUE", help = "Overrides a config value, e.g. ctrl_port=9000")]
+        sets: Vec<String>
+    },
+    #[command(about = "Runs a config, its FileSource reading a recorded file")]
+    Replay {
+        config: String,
+        #[arg(long, help = "Recorded file, in the format given by --format")]
+        file: String,
+        #[arg(long, default_value = "json", help = "json or binary, overrides the format of the FileSource")]
+        format: Format,
+        #[arg(long, help = "Times faster than recorded, as fast as possible if unset")]
+        speed: Option<f64>,
+        #[arg(long, help = "Brings the recorded objects to the latest version of their type")]
+        upcast: bool,
+        #[arg(long, help = "FileSource to read the file, needed if the config has several")]
+        source: Option<String>,
+        #[arg(long = "set", value_name = "KEY=VALUE", help = "Overrides a config value")]
+        sets: Vec<String>
+    },
+    #[command(about = "Captures Binance streams to a file until interrupted")]
+    Record {
+        #[arg(long = "stream", required = true, value_name = "STREAM", help = "Binance stream, e.g. btcusdt@trade or ethusdt@depth")]
+        streams: Vec<String>,
+        #[arg(long, help = "Prefix of the file, suffixed with the start time")]
+        output: String,
+        #[arg(long, default_value = "json", help = "json or binary")]
+        format: Format,
+        #[arg(long, default_value_t = 18000)]
+        ctrl_port: u16,
+        #[arg(long, default_value = "wss://stream.binance.com:9443/stream?streams=")]
+        base_url: String,
+        #[arg(long, default_value = "https://api.binance.com/api/v3/depth?symbol=")]
+        snapshot_url: String
+    },
+    #[command(about = "Prints the statistics of a recorded file")]
+    Inspect {
+        file: String,
+        #[arg(long, default_value = "json", help = "json or binary")]
+        format: Format
+    }
+}
+
+// Points the FileSource called source, or the only one, at file written in format. Its upcast
+// setting is kept unless upcast is set.
+pub fn replay_config(mut cfg: Config, file: &str, format: Format, speed: Option<f64>, upcast: bool, source: Option<&str>) -> Result<Config, String> {
+    let mut candidates = cfg.sources.iter_mut()
+                                    .filter_map(|cfg| match cfg {
+                                        SourceConfigs::FileSource(cfg) => Some(cfg),
+                                        _ => None
+                                    })
+                                    .filter(|cfg| source.map(|source| cfg.name == source).unwrap_or(true))
+                                    .collect::<Vec<_>>();
+
+    match (candidates.len(), source) {
+        (1, _) => {
+            candidates[0].filename = String::from(file);
+            candidates[0].format = format;
+            candidates[0].speed = speed;
+            candidates[0].upcast |= upcast;
+        },
+        (0, Some(source)) => return Err(format!("Cannot find FileSource {}", source)),
+        (0, None) => return Err(String::from("Cannot find a FileSource to replay the file")),
+        (_, _) => return Err(String::from("Several FileSources can replay the file, pick one with --source"))
+    }
+
+    // The components follow the recorded time, not the wall clock
+    cfg.clock = ClockKind::Replay;
+    Ok(cfg)
+}
+
+// A BinanceSpotSource writing straight to a FileSink
+pub fn record_config(streams: &[String], output: &str, format: Format, ctrl_port: u16, base_url: &str, snapshot_url: &str) -> Config {
+    Config {
+        name: String::from("Recorder"),
+        version: 100,
+        log_level: String::from("info"),
+        workers: 1,
+        stack_size: 4,
+        ctrl_host: String::from("127.0.0.1"),
+        ctrl_port,
+        sources: vec![SourceConfigs::BinanceSpotSource(BinanceSpotSourceConfig {
+            name: String::from("BinanceSpotSource"),
+            restart: RestartPolicy::OnFailure { max_retries: 10, backoff: 1000, max_backoff: 60000 },
+            queue_size: 1024,
+            downstreams: vec![Downstream::Name(String::from("FileSink"))],
+            base_url: String::from(base_url),
+            snapshot_url: String::from(snapshot_url),
+            symbols: vec![streams.join("/")],
+            wakeup_interval: 3000,
+            ..Default::default()
+        })],
+        sinks: vec![SinkConfigs::FileSink(FileSinkConfig {
+            name: String::from("FileSink"),
+            filename: String::from(output),
+            format,
+            ..Default::default()
+        })],
+        ..Default::default()
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    objs: u64,
+    symbols: BTreeMap<String, u64>
+}
+
+fn datetime_from_nanos(nanos: i64) -> String {
+    datetime_from_ts((nanos.div_euclid(1_000_000_000), nanos.rem_euclid(1_000_000_000) as u32))
+}
+
+// Hands each object of a recorded file to f, None for those that cannot be decoded
+fn read_objects(path: &str, format: Format, mut f: impl FnMut(Option<Objects>)) -> std::io::Result<()> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    match format {
+        Format::Json => {
+            // Bytes, so that a line which is not UTF-8 counts as malformed
+            for line in reader.split(0xA) {
+                let line = line?;
+                if !line.trim_ascii().is_empty() {
+                    f(serde_json::from_slice::<Objects>(&line).ok());
+                }
+            }
+        },
+        Format::Binary => {
+            while !reader.fill_buf()?.is_empty() {
+                let mut header = [0u8; HEADER_SIZE];
+                reader.read_exact(&mut header)?;
+                let header = Header::parse(&header).map_err(|e| std::io::Error::other(e.to_string()))?;
+                let mut payload = vec![0u8; header.len];
+                reader.read_exact(&mut payload)?;
+                f(binary::decode(&header, &payload).ok());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Counts the objects of a recorded file by type and symbol
+pub fn inspect(path: &str, format: Format) -> std::io::Result<()> {
+    let mut types = BTreeMap::<&str, Stats>::new();
+    let (mut objs, mut malformed) = (0u64, 0u64);
+    let (mut first, mut last) = (i64::MAX, i64::MIN);
+
+    read_objects(path, format, |obj| {
+        match obj {
+            Some(obj) => {
+                objs += 1;
+                first = first.min(obj.get_timestamp());
+                last = last.max(obj.get_timestamp());
+                let stats = types.entry(obj.get_type_name()).or_default();
+                stats.objs += 1;
+                if let Some(symbol) = obj.get_symbol() {
+                    *stats.symbols.entry(String::from(symbol)).or_default() += 1;
+                }
+            },
+            None => malformed += 1
+        }
+    })?;
+
+    println!("File {}", path);
+    println!("  objects    {}", objs);
+    println!("  malformed  {}", malformed);
+    if objs > 0 {
+        println!("  from       {}", datetime_from_nanos(first));
+        println!("  to         {}", datetime_from_nanos(last));
+        println!("  span       {:.3} s", (last - first) as f64 / 1e9);
+    }
+    for (type_name, stats) in types {
+        println!("  {:<20} {}", type_name, stats.objs);
+        for (symbol, count) in stats.symbols {
+            println!("    {:<18} {}", symbol, count);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{ Cli, Command, replay_config };
+    use crate::config::{ Config, format::Format };
+    use crate::config::sources::{ FileSourceConfig, SourceConfigs };
+    use crate::utils::clock::ClockKind;
+
+    use clap::Parser;
+
+    #[test]
+    fn test_parse() {
+        let cli = Cli::parse_from(["main", "--log-level", "debug", "replay", "dev.toml", "--file", "trades.bin", "--format", "binary", "--speed", "10", "--set", "ctrl_port=9000"]);
+        assert_eq!(cli.log_level.as_deref(), Some("debug"));
+        match cli.command {
+            Command::Replay { config, file, format, speed, upcast, source, sets } => {
+                assert_eq!((config.as_str(), file.as_str(), format, speed, upcast, source), ("dev.toml", "trades.bin", Format::Binary, Some(10.0), false, None));
+                assert_eq!(sets, vec!["ctrl_port=9000"]);
+            },
+            _ => panic!("Expected replay")
+        }
+
+        assert!(Cli::try_parse_from(["main", "record", "--output", "capture"]).is_err());
+    }
+
+    #[test]
+    fn test_replay_config() {
+        let file_source = |name: &str| SourceConfigs::FileSource(FileSourceConfig { name: String::from(name), ..Default::default() });
+        let cfg = Config { sources: vec![file_source("Trades"), file_source("Books")], ..Default::default() };
+
+        assert!(replay_config(Config::clone(&cfg), "trades.json", Format::Json, None, false, None).is_err());
+        assert!(replay_config(Config::clone(&cfg), "trades.json", Format::Json, None, false, Some("Klines")).is_err());
+
+        let cfg = replay_config(cfg, "trades.bin", Format::Binary, Some(2.0), true, Some("Trades")).unwrap();
+        match &cfg.sources[0] {
+            SourceConfigs::FileSource(cfg) => assert_eq!((cfg.filename.as_str(), cfg.format, cfg.speed, cfg.upcast), ("trades.bin", Format::Binary, Some(2.0), true)),
+            _ => panic!("Expected FileSource")
+        }
+        assert_eq!(cfg.clock, ClockKind::Replay);
+    }
+}
@@ -1,65 +1,61 @@
-use crate::config::Config;
+use crate::config::{ Config, edge::Downstream, pus::PUConfigs, sinks::SinkConfigs, sources::SourceConfigs };
+use crate::edge::Edge;
+use crate::metrics;
 use crate::objects::Objects;
+use crate::processor::Runtime;
+use crate::supervisor::SupervisorReport;
+use crate::trace::{ Percentiles, Segment };
+use crate::utils::{ clock::Clock, uuid::IdGenerator };
 
-use flume::Sender;
-use rocket::{ self, Build, Ignite, Rocket, http, serde::json::Json };
+use flume::{ Receiver, Sender };
+use rocket::{ self, Build, Ignite, Rocket, State, http, serde::json::Json };
 use serde::{ Deserialize, Serialize };
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::str::FromStr;
-
-#[macro_export]
-macro_rules! inject {
-    ($unlocked_component:ident, $txs:ident, $obj:ident, $disabled:ident) => {
-        let mut futures = $txs.iter()
-                              .enumerate()
-                              .filter(|&(idx, _)| !$disabled.contains(&idx))
-                              .map(|(_, tx)| tx.send_async($obj.clone()))
-                              .collect::<Vec<_>>();
-
-        while !futures.is_empty() {
-            match future::select_all(futures).await {
-                (Ok(_value), _index, remaining) => futures = remaining,
-                (Err(e), index, remaining) => {
-                    log::error!("{}: INJECT failed while sending at index {} - {}", $unlocked_component.get_name(), index, e);
-                    futures = remaining;
-                    $disabled.push(index);
-                    if $disabled.len() == $txs.len() {
-                        log::error!("{}: all the txs are disabled. Returning...", $unlocked_component.get_name());
-                        return;
-                    }
-                }
-            }
-        }
-    };
-}
+use std::sync::Arc;
+use uuid::Uuid;
 
 pub struct ControlState {
-    cfg: Config,
-    txs: HashMap<String, Sender<Controls>>
+    runtime: Arc<Runtime>
 }
 
 impl ControlState {
-    pub fn new(cfg: Config, txs: HashMap<String, Sender<Controls>>) -> Self {
+    pub fn new(runtime: Arc<Runtime>) -> Self {
         Self {
-            cfg,
-            txs
+            runtime
         }
     }
 
-    pub fn config(&self) -> &Config {
-        &self.cfg
+    pub fn config(&self) -> Config {
+        Config::clone(self.runtime.graph().get_config())
     }
 
-    pub fn get(&self, key: &str) -> Option<&Sender<Controls>> {
-        self.txs.get(key)
+    pub fn get(&self, key: &str) -> Option<Sender<Controls>> {
+        self.runtime.graph().get_ctrl_tx(key).cloned()
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.runtime.get_clock()
+    }
+
+    pub fn ids(&self, key: &str) -> Option<Arc<dyn IdGenerator>> {
+        self.runtime.graph().get_ids(key)
     }
 }
 
 #[derive(Clone, Deserialize, Serialize)]
 pub enum Controls {
     Inject(Objects),
-    SleepTime(u64)
+    SleepTime(u64),
+    Shutdown,
+    // Changes to the edges of a running component, never sent over the wire
+    #[serde(skip)]
+    Connect(Edge),
+    #[serde(skip)]
+    Disconnect(String),
+    #[serde(skip)]
+    Attach(Arc<Receiver<Objects>>)
 }
 
 #[rocket::get("/health")]
@@ -67,6 +63,85 @@ async fn health() -> (http::Status, String) {
     (http::Status::Ok, String::from("OK"))
 }
 
+#[rocket::get("/restarts")]
+async fn restarts(state: &State<ControlState>) -> Json<HashMap<String, SupervisorReport>> {
+    let graph = state.runtime.graph();
+    Json(graph.get_statuses().iter().map(|(name, status)| (String::clone(name), status.report())).collect())
+}
+
+// Objects dropped by each edge, by upstream and then downstream
+#[rocket::get("/drops")]
+async fn drops(state: &State<ControlState>) -> Json<HashMap<String, HashMap<String, u64>>> {
+    let mut drops = HashMap::<String, HashMap<String, u64>>::new();
+    for edge in state.runtime.graph().get_edges() {
+        drops.entry(String::from(edge.get_upstream()))
+             .or_default()
+             .insert(String::from(edge.get_downstream()), edge.get_dropped());
+    }
+
+    Json(drops)
+}
+
+#[rocket::get("/metrics")]
+async fn prometheus(state: &State<ControlState>) -> (http::ContentType, String) {
+    let graph = state.runtime.graph();
+    (http::ContentType::Plain, metrics::render(&graph.get_config().name, graph.get_metrics(), graph.get_statuses(), graph.get_edges()))
+}
+
+// Percentiles of every hop over the latest traced objects
+#[rocket::get("/traces")]
+async fn traces(state: &State<ControlState>) -> Json<HashMap<String, Percentiles>> {
+    Json(state.runtime.get_traces().percentiles())
+}
+
+#[rocket::get("/traces/<correlation_id>")]
+async fn trace(correlation_id: &str, state: &State<ControlState>) -> Result<Json<Vec<Vec<Segment>>>, http::Status> {
+    let correlation_id = Uuid::parse_str(correlation_id).map_err(|_| http::Status::BadRequest)?;
+    match state.runtime.get_traces().get(correlation_id) {
+        traces if traces.is_empty() => Err(http::Status::NotFound),
+        traces => Ok(Json(traces))
+    }
+}
+
+// Components added at runtime can be fed by running ones. Every upstream is written
+// like the downstream it gains, but named after the upstream.
+#[derive(Deserialize)]
+struct AddRequest<T> {
+    #[serde(default)]
+    upstreams: Vec<Downstream>,
+    #[serde(flatten)]
+    cfg: T
+}
+
+fn changed(result: Result<(), String>) -> (http::Status, String) {
+    match result {
+        Ok(()) => (http::Status::Ok, String::from("OK")),
+        Err(e) => (http::Status::BadRequest, e)
+    }
+}
+
+#[rocket::post("/sources", data="<cfg>")]
+async fn add_source(cfg: Json<SourceConfigs>, state: &State<ControlState>) -> (http::Status, String) {
+    changed(state.runtime.add_source(cfg.into_inner()).await)
+}
+
+#[rocket::post("/pus", data="<request>")]
+async fn add_pu(request: Json<AddRequest<PUConfigs>>, state: &State<ControlState>) -> (http::Status, String) {
+    let request = request.into_inner();
+    changed(state.runtime.add_pu(request.cfg, request.upstreams).await)
+}
+
+#[rocket::post("/sinks", data="<request>")]
+async fn add_sink(request: Json<AddRequest<SinkConfigs>>, state: &State<ControlState>) -> (http::Status, String) {
+    let request = request.into_inner();
+    changed(state.runtime.add_sink(request.cfg, request.upstreams).await)
+}
+
+#[rocket::delete("/components/<name>")]
+async fn remove(name: &str, state: &State<ControlState>) -> (http::Status, String) {
+    changed(state.runtime.remove(name).await)
+}
+
 #[derive(Deserialize)]
 struct ShutdownRequest<'r> {
     magic_code: &'r str
@@ -83,16 +158,22 @@ async fn shutdown(request: Json<ShutdownRequest<'_>>, shutdown: rocket::Shutdown
 }
 
 pub fn build_control(ctrl_state: ControlState) -> Rocket<Build> {
+    let cfg = ctrl_state.config();
     let mut control_config = rocket::config::Config::default();
-    match IpAddr::from_str(&ctrl_state.cfg.ctrl_host) {
+    match IpAddr::from_str(&cfg.ctrl_host) {
         Ok(ip) => control_config.address = ip,
-        Err(e) => log::error!("Cannot parse ip address {} - {}", &ctrl_state.cfg.ctrl_host, e)
+        Err(e) => log::error!("Cannot parse ip address {} - {}", &cfg.ctrl_host, e)
     };
-    control_config.port = ctrl_state.cfg.ctrl_port;
+    control_config.port = cfg.ctrl_port;
 
     rocket::custom(control_config)
         .manage(ctrl_state)
         .mount("/Processor", rocket::routes![health])
+        .mount("/Processor", rocket::routes![restarts])
+        .mount("/Processor", rocket::routes![drops])
+        .mount("/Processor", rocket::routes![traces, trace])
+        .mount("/Processor", rocket::routes![add_source, add_pu, add_sink, remove])
+        .mount("/", rocket::routes![prometheus])
         .mount("/Processor", rocket::routes![shutdown])
 }
 
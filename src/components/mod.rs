@@ -1,4 +1,5 @@
 pub mod control;
 pub mod processing_units;
+pub mod registry;
 pub mod sinks;
 pub mod sources;
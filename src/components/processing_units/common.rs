@@ -1,9 +1,9 @@
-use crate::objects::{market::Market, orderbook_update::OrderbookUpdateV1, orderbook::OrderbookStatus};
+use crate::objects::{market::Market, orderbook_update::OrderbookUpdateV2, orderbook::OrderbookStatus};
 
 use reqwest::Client;
 use std::collections::HashMap;
 
-pub async fn update_orderbook(market: &mut Market, obj: &OrderbookUpdateV1, name: &str, realtime: bool, obj_queues: &mut HashMap<String, Vec<OrderbookUpdateV1>>) {
+pub async fn update_orderbook(market: &mut Market, obj: &OrderbookUpdateV2, name: &str, realtime: bool, obj_queues: &mut HashMap<String, Vec<OrderbookUpdateV2>>) {
     match market.get_mut_book(&obj.symbol) {
         Some(book) => {
             log::trace!("{}: Updating orderbook {}", name, &obj.symbol);
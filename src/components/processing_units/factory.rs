@@ -1,20 +1,29 @@
-use crate::components::processing_units::{ PUs,
-                                           mock::MockPU,
+use crate::components::processing_units::{ mock::MockPU,
                                            order_manager::OrderManagerPU,
                                            orderbook_manager::OrderbookManagerPU,
                                            strategy::base::StrategyPU
                                          };
+use crate::components::registry::{ Registry, SharedPU };
 use crate::config::pus::PUConfigs;
+use crate::error::HolocoreError;
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-pub fn make(cfg: &PUConfigs) -> PUs {
+pub fn register(registry: &mut Registry) {
+    registry.register_pu("MockPU", make);
+    registry.register_pu("OrderManagerPU", make);
+    registry.register_pu("OrderbookManagerPU", make);
+    registry.register_pu("StrategyPU", make);
+}
+
+pub fn make(cfg: &PUConfigs) -> Result<SharedPU, HolocoreError> {
     match cfg {
-        PUConfigs::MockPU(cfg) => PUs::MockPU(Arc::new(Mutex::new(MockPU::new(cfg)))),
-        PUConfigs::OrderManagerPU(cfg) => PUs::OrderManagerPU(Arc::new(Mutex::new(OrderManagerPU::new(cfg)))),
-        PUConfigs::OrderbookManagerPU(cfg) => PUs::OrderbookManagerPU(Arc::new(Mutex::new(OrderbookManagerPU::new(cfg)))),
-        PUConfigs::StrategyPU(cfg) => PUs::StrategyPU(Arc::new(Mutex::new(StrategyPU::new(cfg)))),
-        _ => panic!("Cannot build PU {}", cfg.name())
+        PUConfigs::MockPU(cfg) => Ok(Arc::new(Mutex::new(MockPU::new(cfg)))),
+        PUConfigs::OrderManagerPU(cfg) => Ok(Arc::new(Mutex::new(OrderManagerPU::new(cfg)))),
+        PUConfigs::OrderbookManagerPU(cfg) => Ok(Arc::new(Mutex::new(OrderbookManagerPU::new(cfg)))),
+        PUConfigs::StrategyPU(cfg) => Ok(Arc::new(Mutex::new(StrategyPU::new(cfg)?))),
+        PUConfigs::Custom(cfg) => Err(HolocoreError::Config(format!("Cannot build PU {} - malformed {} config", cfg.name, cfg.type_name))),
+        _ => Err(HolocoreError::Config(format!("Cannot build PU {}", cfg.name())))
     }
 }
@@ -5,12 +5,92 @@ pub mod order_manager;
 pub mod orderbook_manager;
 pub mod strategy;
 
+use crate::error::HolocoreError;
+use crate::markets::Markets;
+use crate::objects::Objects;
+use crate::utils::{ clock::Clock, uuid::IdGenerator };
+
+use async_trait::async_trait;
+use rocket::{ Build, Rocket };
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
-pub enum PUs {
-    MockPU(Arc<Mutex<mock::MockPU>>),
-    OrderManagerPU(Arc<Mutex<order_manager::OrderManagerPU>>),
-    OrderbookManagerPU(Arc<Mutex<orderbook_manager::OrderbookManagerPU>>),
-    StrategyPU(Arc<Mutex<strategy::base::StrategyPU>>)
+// Objects emitted by a PU for one input. Objects pushed without a port go to the downstreams
+// not bound to any port, the others only to the downstreams bound to their port.
+#[derive(Debug, Default)]
+pub struct Outputs {
+    objs: Vec<(Option<String>, Objects)>
+}
+
+impl Outputs {
+    pub fn new() -> Self {
+        Self {
+            objs: vec![]
+        }
+    }
+
+    pub fn push(&mut self, obj: Objects) {
+        self.objs.push((None, obj));
+    }
+
+    pub fn push_to(&mut self, port: &str, obj: Objects) {
+        self.objs.push((Some(String::from(port)), obj));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.objs.len()
+    }
+
+    // Objects of every port
+    pub fn objs_mut(&mut self) -> impl Iterator<Item = &mut Objects> {
+        self.objs.iter_mut().map(|(_, obj)| obj)
+    }
+}
+
+impl From<Objects> for Outputs {
+    fn from(obj: Objects) -> Self {
+        Self {
+            objs: vec![(None, obj)]
+        }
+    }
+}
+
+impl IntoIterator for Outputs {
+    type Item = (Option<String>, Objects);
+    type IntoIter = std::vec::IntoIter<(Option<String>, Objects)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.objs.into_iter()
+    }
+}
+
+#[async_trait]
+pub trait ProcessingUnit: Send {
+    async fn init(&mut self) -> Result<(), HolocoreError>;
+
+    // Returns the objects to forward downstream, if any, and whether the PU has completed
+    async fn execute(&mut self, obj: Objects) -> Result<(Outputs, bool), HolocoreError>;
+
+    // Called once the inputs are drained, e.g. to cancel open orders
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str;
+
+    // Setters
+    // Clock and ID generator of the objects the component creates, set before the first init
+    fn set_clock(&mut self, _clock: Arc<dyn Clock>) {}
+    fn set_id_generator(&mut self, _ids: Arc<dyn IdGenerator>) {}
+    // Markets shared with the other PUs of the processor, set before the first init
+    fn set_markets(&mut self, _markets: Arc<Markets>) {}
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
 }
@@ -1,6 +1,9 @@
+use crate::components::processing_units::{ Outputs, ProcessingUnit };
 use crate::config::pus::OrderManagerPUConfig;
+use crate::error::HolocoreError;
 use crate::objects::Objects;
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket, http };
 
 pub struct OrderManagerPU {
@@ -18,11 +21,19 @@ impl OrderManagerPU {
         }
     }
 
-    pub async fn init(&mut self) -> Result<(), String> {
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+#[async_trait]
+impl ProcessingUnit for OrderManagerPU {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
         Ok(())
     }
 
-    pub async fn execute(&mut self, obj: Objects) -> Result<(Option<Objects>, bool), String> {
+    async fn execute(&mut self, obj: Objects) -> Result<(Outputs, bool), HolocoreError> {
         self.processed_objs += 1;
         log::info!("{} - {}: Processing message {} - {} - {} - {}",
                    self.name,
@@ -32,20 +43,16 @@ impl OrderManagerPU {
                    obj.get_sequence_number(),
                    obj.get_correlation_id());
 
-        Ok((Some(obj), false))
+        Ok((Outputs::from(obj), false))
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue_size
-    }
-
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl.mount(format!("/{}/", self.name), rocket::routes!(test))
     }
 }
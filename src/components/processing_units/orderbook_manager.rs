@@ -1,7 +1,10 @@
-use crate::components::processing_units::common::update_orderbook;
+use crate::components::processing_units::{ Outputs, ProcessingUnit, common::update_orderbook };
 use crate::config::pus::OrderbookManagerPUConfig;
-use crate::objects::{Objects, market::Market, orderbook_update::OrderbookUpdateV1};
+use crate::error::HolocoreError;
+use crate::markets::{ MarketWriter, Markets };
+use crate::objects::{Objects, market::Market, orderbook_update::OrderbookUpdateV2};
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket, http, State };
 use std::{sync::Arc, collections::HashMap};
 use tokio::sync::RwLock;
@@ -11,8 +14,10 @@ pub struct OrderbookManagerPU {
     queue_size: usize,
     processed_objs: u64,
     realtime: bool,
-    market: Arc<RwLock<Market>>,
-    obj_queues: HashMap<String, Vec<OrderbookUpdateV1>>
+    market_name: Option<String>,
+    markets: Arc<Markets>,
+    market: MarketWriter,
+    obj_queues: HashMap<String, Vec<OrderbookUpdateV2>>
 }
 
 impl OrderbookManagerPU {
@@ -22,22 +27,42 @@ impl OrderbookManagerPU {
             queue_size: cfg.queue_size,
             processed_objs: 0,
             realtime: cfg.realtime,
-            market: Arc::new(RwLock::new(Market::new("Market"))),
-            obj_queues: HashMap::<String, Vec<OrderbookUpdateV1>>::new()
+            market_name: cfg.market.clone(),
+            markets: Arc::new(Markets::default()),
+            market: MarketWriter::new("Market"),
+            obj_queues: HashMap::<String, Vec<OrderbookUpdateV2>>::new()
         }
     }
 
-    pub async fn init(&mut self) -> Result<(), String> {
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+#[async_trait]
+impl ProcessingUnit for OrderbookManagerPU {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        if let Some(name) = &self.market_name {
+            self.market = self.markets.writer(name, &self.name)?;
+            log::info!("{}: sharing market {}", self.name, name);
+        }
+
         Ok(())
     }
 
-    pub async fn execute(&mut self, mut obj: Objects) -> Result<(Option<Objects>, bool), String> {
+    async fn execute(&mut self, mut obj: Objects) -> Result<(Outputs, bool), HolocoreError> {
         match obj {
-            Objects::OrderbookUpdateV1(ref obj) => {
+            Objects::OrderbookUpdateV2(ref obj) => {
                 let mut market = self.market.write().await;
+                let existed = market.contains(&obj.symbol);
                 update_orderbook(&mut market, obj, &self.name, self.realtime, &mut self.obj_queues).await;
+                // Queued updates do not change the book
+                if existed {
+                    self.market.notify(&obj.symbol);
+                }
             },
-            Objects::OrderbookV1(ref mut new_book) => {
+            Objects::OrderbookV2(ref mut new_book) => {
                 log::info!("{}: got orderbook for {} - {}", self.name, &new_book.symbol, new_book.last_update_id);
                 if let Some(queue) = self.obj_queues.get_mut(&new_book.symbol) {
                     for update in queue.iter() {
@@ -51,26 +76,38 @@ impl OrderbookManagerPU {
                 let mut market = self.market.write().await;
                 market.clear_pending(&new_book.symbol);
                 market.set_book(new_book.clone());
+                self.market.notify(&new_book.symbol);
             },
             _ => ()
         };
 
         self.processed_objs += 1;
-        Ok((Some(obj), false))
+        Ok((Outputs::from(obj), false))
+    }
+
+    // Removed at runtime, so that a replacement can claim the market
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        if let Some(name) = &self.market_name {
+            self.markets.release(name, &self.name);
+            log::info!("{}: released market {}", self.name, name);
+        }
+
+        Ok(())
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue_size
+    // Setters
+    fn set_markets(&mut self, markets: Arc<Markets>) {
+        self.markets = markets;
     }
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
-        ctrl.manage(self.market.clone())
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl.manage(self.market.get_market())
             .mount(format!("/{}/", self.name), rocket::routes!(get_orderbook))
             .mount(format!("/{}/", self.name), rocket::routes!(delete_orderbook))
     }
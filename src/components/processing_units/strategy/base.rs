@@ -1,84 +1,131 @@
-use crate::components::processing_units::common::update_orderbook;
-use crate::components::processing_units::strategy;
+use crate::components::processing_units::{ Outputs, ProcessingUnit, common::update_orderbook, strategy };
 use crate::config::pus::StrategyPUConfig;
+use crate::error::HolocoreError;
+use crate::markets::{ MarketReader, Markets };
 use crate::objects::Objects;
-use crate::objects::orderbook_update::OrderbookUpdateV1;
+use crate::objects::orderbook_update::OrderbookUpdateV2;
 use crate::objects::market::Market;
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket };
-use std::collections::HashMap;
+use std::{ collections::HashMap, sync::Arc };
+
+// Port of the orders placed by the algorithm, the market data goes to the default one
+pub const ORDERS_PORT: &str = "orders";
 
 pub struct StrategyPU {
     name: String,
     queue_size: usize,
     processed_objs: u64,
     realtime: bool,
+    market_name: Option<String>,
+    markets: Arc<Markets>,
+    // Books written by an orderbook manager, the local market is built from the inputs otherwise
+    shared_market: Option<MarketReader>,
     market: Market,
-    obj_queues: HashMap<String, Vec<OrderbookUpdateV1>>,
+    obj_queues: HashMap<String, Vec<OrderbookUpdateV2>>,
     algorithm: strategy::Algorithms
 }
 
 impl StrategyPU {
-    pub fn new(cfg: &StrategyPUConfig) -> Self {
-        let algorithm = strategy::factory::make(&cfg.algorithm);
+    pub fn new(cfg: &StrategyPUConfig) -> Result<Self, HolocoreError> {
+        let algorithm = strategy::factory::make(&cfg.algorithm)?;
 
-        Self {
+        Ok(Self {
             name: String::from(&cfg.name),
             queue_size: cfg.queue_size,
             processed_objs: 0,
             realtime: cfg.realtime,
+            market_name: cfg.market.clone(),
+            markets: Arc::new(Markets::default()),
+            shared_market: None,
             market: Market::new("Market"),
-            obj_queues: HashMap::<String, Vec<OrderbookUpdateV1>>::new(),
+            obj_queues: HashMap::<String, Vec<OrderbookUpdateV2>>::new(),
             algorithm
-        }
+        })
     }
 
-    pub async fn init(&mut self) -> Result<(), String> {
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+#[async_trait]
+impl ProcessingUnit for StrategyPU {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        if let Some(name) = &self.market_name {
+            self.shared_market = Some(self.markets.reader(name));
+            log::info!("{}: reading market {}", self.name, name);
+        }
+
         Ok(())
     }
 
-    pub async fn execute(&mut self, mut obj: Objects) -> Result<(Option<Objects>, bool), String> {
-        match obj {
-            Objects::OrderbookUpdateV1(ref obj) => {
-                update_orderbook(&mut self.market, obj, &self.name, self.realtime, &mut self.obj_queues).await;
+    async fn execute(&mut self, mut obj: Objects) -> Result<(Outputs, bool), HolocoreError> {
+        // The algorithm runs on the books that changed, whether the market is shared or local
+        let result = match &mut self.shared_market {
+            Some(shared_market) => {
+                let changed = shared_market.drain_changes();
+                self.algorithm.process(&obj, &*shared_market.read().await, &changed)
             },
-            Objects::OrderbookV1(ref mut new_book) => {
-                log::info!("{}: got orderbook for {} - {}", self.name, &new_book.symbol, new_book.last_update_id);
-                if let Some(queue) = self.obj_queues.get_mut(&new_book.symbol) {
-                    for update in queue.iter() {
-                        log::debug!("{}: spooling object {} ({}) - {} - {}", self.name, update.get_id(), update.symbol, update.first_update_id, update.final_update_id);
-                        new_book.update(update)
-                    }
-                    queue.clear();
-                    log::info!("{}: orderbook {} has been updated", self.name, &new_book.symbol);
-                }
+            None => {
+                let changed = match obj {
+                    Objects::OrderbookUpdateV2(ref obj) => {
+                        let existed = self.market.contains(&obj.symbol);
+                        update_orderbook(&mut self.market, obj, &self.name, self.realtime, &mut self.obj_queues).await;
+                        // Queued updates do not change the book
+                        if existed { vec![String::clone(&obj.symbol)] } else { vec![] }
+                    },
+                    Objects::OrderbookV2(ref mut new_book) => {
+                        log::info!("{}: got orderbook for {} - {}", self.name, &new_book.symbol, new_book.last_update_id);
+                        if let Some(queue) = self.obj_queues.get_mut(&new_book.symbol) {
+                            for update in queue.iter() {
+                                log::debug!("{}: spooling object {} ({}) - {} - {}", self.name, update.get_id(), update.symbol, update.first_update_id, update.final_update_id);
+                                new_book.update(update)
+                            }
+                            queue.clear();
+                            log::info!("{}: orderbook {} has been updated", self.name, &new_book.symbol);
+                        }
 
-                self.market.clear_pending(&new_book.symbol);
-                self.market.set_book(new_book.clone());
-            },
-            _ => ()
+                        self.market.clear_pending(&new_book.symbol);
+                        self.market.set_book(new_book.clone());
+                        vec![String::clone(&new_book.symbol)]
+                    },
+                    _ => vec![]
+                };
+
+                self.algorithm.process(&obj, &self.market, &changed)
+            }
         };
 
-        match self.algorithm.process(&obj, &self.market) {
-            Ok(_obj) => (),
+        let mut outputs = Outputs::new();
+        match result {
+            Ok((orders, _completed)) => {
+                for order in orders {
+                    outputs.push_to(ORDERS_PORT, order);
+                }
+            },
             Err(e) => log::error!("{}: failed to process obj {} - {}", "TrendFollowing", obj.get_id(), e)
         };
 
         self.processed_objs += 1;
-        Ok((Some(obj), false))
+        outputs.push(obj);
+        Ok((outputs, false))
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue_size
+    // Setters
+    fn set_markets(&mut self, markets: Arc<Markets>) {
+        self.markets = markets;
     }
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl
     }
 }
@@ -1,9 +1,10 @@
 use crate::components::processing_units::strategy::Algorithms;
 use crate::components::processing_units::strategy::trend_following;
+use crate::error::HolocoreError;
 
-pub fn make(strategy: &str) -> Algorithms {
+pub fn make(strategy: &str) -> Result<Algorithms, HolocoreError> {
     match strategy {
-        "TrendFollowing" => Algorithms::TrendFollowing(trend_following::TrendFollowing::new()),
-        _ => panic!("Cannot build algorithm {}", strategy)
+        "TrendFollowing" => Ok(Algorithms::TrendFollowing(trend_following::TrendFollowing::new())),
+        _ => Err(HolocoreError::Config(format!("Cannot build algorithm {}", strategy)))
     }
 }
@@ -2,6 +2,7 @@ pub mod base;
 pub mod factory;
 pub mod trend_following;
 
+use crate::error::HolocoreError;
 use crate::objects::{Objects, market::Market};
 
 pub enum Algorithms {
@@ -9,9 +10,11 @@ pub enum Algorithms {
 }
 
 impl Algorithms {
-    pub fn process(&mut self, obj: &Objects, market: &Market) -> Result<(Option<Objects>, bool), String> {
+    // Returns the orders to place, if any, and whether the algorithm has completed.
+    // changed holds the symbols whose book changed since the previous call.
+    pub fn process(&mut self, obj: &Objects, market: &Market, changed: &[String]) -> Result<(Vec<Objects>, bool), HolocoreError> {
         match self {
-            Algorithms::TrendFollowing(algo) => algo.process(obj, market)
+            Algorithms::TrendFollowing(algo) => algo.process(obj, market, changed)
         }
     }
 }
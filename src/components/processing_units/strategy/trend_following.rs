@@ -1,4 +1,7 @@
-use crate::objects::{Objects, market::Market};
+use crate::error::HolocoreError;
+use crate::objects::{Objects, market::Market, numeric::Quantity};
+
+use std::str::FromStr;
 
 pub struct TrendFollowing {
 
@@ -11,29 +14,29 @@ impl TrendFollowing {
         }
     }
 
-    pub fn process(&mut self, obj: &Objects, market: &Market) -> Result<(Option<Objects>, bool), String> {
-        match obj {
-            Objects::OrderbookV1(_) => (),
-            Objects::OrderbookUpdateV1(obj) => {
-                if obj.symbol == "BTCUSDT" {
-                    if let Some(book) = market.get_book(&obj.symbol) {
-                        let spread = book.spread();
-                        log::info!("Book {} spread {}", obj.symbol, spread);
+    pub fn process(&mut self, _obj: &Objects, market: &Market, changed: &[String]) -> Result<(Vec<Objects>, bool), HolocoreError> {
+        for symbol in changed.iter().filter(|symbol| *symbol == "BTCUSDT") {
+            if let Some(book) = market.get_book(symbol) {
+                // None on an empty or overflowing book
+                if let Some(spread) = book.spread() {
+                    log::info!("Book {} spread {}", symbol, spread);
+                }
 
-                        let weighted_spread = book.weighted_spread(0.1);
-                        log::info!("Book {} weighted spread {}", obj.symbol, weighted_spread);
+                let volume = Quantity::from_str("0.1").unwrap_or_default();
+                if let Some(weighted_spread) = book.weighted_spread(volume) {
+                    log::info!("Book {} weighted spread {}", symbol, weighted_spread);
+                }
 
-                        let mid = book.mid();
-                        log::info!("Book {} mid {}", obj.symbol, mid);
+                if let Some(mid) = book.mid() {
+                    log::info!("Book {} mid {}", symbol, mid);
+                }
 
-                        let weighted_mid = book.weighted_mid();
-                        log::info!("Book {} weighted mid {}", obj.symbol, weighted_mid);
-                    }
+                if let Some(weighted_mid) = book.weighted_mid() {
+                    log::info!("Book {} weighted mid {}", symbol, weighted_mid);
                 }
-            },
-            _ => ()
-        };
+            }
+        }
 
-        Ok((None, false))
+        Ok((vec![], false))
     }
 }
@@ -0,0 +1,180 @@
+use crate::components::processing_units::{ self, ProcessingUnit };
+use crate::components::sinks::{ self, Sink };
+use crate::components::sources::{ self, Source };
+use crate::config::{ pus::PUConfigs, sinks::SinkConfigs, sources::SourceConfigs };
+use crate::error::HolocoreError;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub type SharedSource = Arc<Mutex<dyn Source>>;
+pub type SharedPU = Arc<Mutex<dyn ProcessingUnit>>;
+pub type SharedSink = Arc<Mutex<dyn Sink>>;
+
+pub type SourceFactory = Box<dyn Fn(&SourceConfigs) -> Result<SharedSource, HolocoreError> + Send + Sync>;
+pub type PUFactory = Box<dyn Fn(&PUConfigs) -> Result<SharedPU, HolocoreError> + Send + Sync>;
+pub type SinkFactory = Box<dyn Fn(&SinkConfigs) -> Result<SharedSink, HolocoreError> + Send + Sync>;
+
+// Maps the `type` field of a component config to the factory building it.
+// Downstream crates register their own component types on top of the built-in ones.
+pub struct Registry {
+    sources: HashMap<String, SourceFactory>,
+    pus: HashMap<String, PUFactory>,
+    sinks: HashMap<String, SinkFactory>
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        sources::factory::register(&mut registry);
+        processing_units::factory::register(&mut registry);
+        sinks::factory::register(&mut registry);
+        registry
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            sources: HashMap::<String, SourceFactory>::new(),
+            pus: HashMap::<String, PUFactory>::new(),
+            sinks: HashMap::<String, SinkFactory>::new()
+        }
+    }
+
+    // Registration
+    pub fn register_source<F>(&mut self, type_name: &str, factory: F)
+    where
+        F: Fn(&SourceConfigs) -> Result<SharedSource, HolocoreError> + Send + Sync + 'static
+    {
+        if self.sources.insert(String::from(type_name), Box::new(factory)).is_some() {
+            log::warn!("Registry: source type {} has been overridden", type_name);
+        }
+    }
+
+    pub fn register_pu<F>(&mut self, type_name: &str, factory: F)
+    where
+        F: Fn(&PUConfigs) -> Result<SharedPU, HolocoreError> + Send + Sync + 'static
+    {
+        if self.pus.insert(String::from(type_name), Box::new(factory)).is_some() {
+            log::warn!("Registry: PU type {} has been overridden", type_name);
+        }
+    }
+
+    pub fn register_sink<F>(&mut self, type_name: &str, factory: F)
+    where
+        F: Fn(&SinkConfigs) -> Result<SharedSink, HolocoreError> + Send + Sync + 'static
+    {
+        if self.sinks.insert(String::from(type_name), Box::new(factory)).is_some() {
+            log::warn!("Registry: sink type {} has been overridden", type_name);
+        }
+    }
+
+    // Lookups
+    pub fn contains_source(&self, type_name: &str) -> bool {
+        self.sources.contains_key(type_name)
+    }
+
+    pub fn contains_pu(&self, type_name: &str) -> bool {
+        self.pus.contains_key(type_name)
+    }
+
+    pub fn contains_sink(&self, type_name: &str) -> bool {
+        self.sinks.contains_key(type_name)
+    }
+
+    // Builders
+    pub fn make_source(&self, cfg: &SourceConfigs) -> Result<SharedSource, HolocoreError> {
+        match self.sources.get(cfg.type_name()) {
+            Some(factory) => factory(cfg),
+            None => Err(HolocoreError::Config(format!("Cannot build source {} - unknown type {}", cfg.name(), cfg.type_name())))
+        }
+    }
+
+    pub fn make_pu(&self, cfg: &PUConfigs) -> Result<SharedPU, HolocoreError> {
+        match self.pus.get(cfg.type_name()) {
+            Some(factory) => factory(cfg),
+            None => Err(HolocoreError::Config(format!("Cannot build PU {} - unknown type {}", cfg.name(), cfg.type_name())))
+        }
+    }
+
+    pub fn make_sink(&self, cfg: &SinkConfigs) -> Result<SharedSink, HolocoreError> {
+        match self.sinks.get(cfg.type_name()) {
+            Some(factory) => factory(cfg),
+            None => Err(HolocoreError::Config(format!("Cannot build sink {} - unknown type {}", cfg.name(), cfg.type_name())))
+        }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::registry::Registry;
+    use crate::components::sources::Source;
+    use crate::config::sources::SourceConfigs;
+    use crate::error::HolocoreError;
+    use crate::objects::Objects;
+
+    use async_trait::async_trait;
+    use serde::Deserialize;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    #[derive(Deserialize)]
+    struct DummySourceConfig {
+        name: String,
+        path: String
+    }
+
+    struct DummySource {
+        name: String
+    }
+
+    #[async_trait]
+    impl Source for DummySource {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
+            Ok((None, true))
+        }
+
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    #[test]
+    fn test_custom_source() {
+        let mut registry = Registry::new();
+        registry.register_source("DummySource", |cfg| match cfg {
+            SourceConfigs::Custom(cfg) => {
+                let cfg = cfg.params::<DummySourceConfig>()?;
+                assert_eq!(cfg.path, "/tmp/dummy");
+                Ok(Arc::new(Mutex::new(DummySource { name: cfg.name })))
+            },
+            _ => Err(HolocoreError::Config(String::from("Unexpected config")))
+        });
+
+        let cfg: SourceConfigs = serde_json::from_str(r#"{ "type": "DummySource", "name": "Dummy", "queue_size": 8, "downstreams": [], "path": "/tmp/dummy" }"#).unwrap();
+        assert_eq!(cfg.type_name(), "DummySource");
+        let source = registry.make_source(&cfg).unwrap();
+        assert_eq!(source.try_lock().unwrap().get_name(), "Dummy");
+
+        let cfg: SourceConfigs = serde_json::from_str(r#"{ "type": "UnknownSource", "name": "Unknown", "queue_size": 8, "downstreams": [] }"#).unwrap();
+        assert!(registry.make_source(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_builtin_source() {
+        let registry = Registry::new();
+        let cfg: SourceConfigs = serde_json::from_str(r#"{ "type": "MockSource", "name": "Mock", "queue_size": 8, "downstreams": [], "sleep_time": 10 }"#).unwrap();
+        assert!(matches!(cfg, SourceConfigs::MockSource(_)));
+        assert!(registry.make_source(&cfg).is_ok());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::components::sinks::binance::api::API;
+use crate::error::HolocoreError;
 use crate::utils::time;
 
 use hex::encode;
@@ -49,7 +50,7 @@ impl BinanceSpotClient {
         }
     }
 
-    pub async fn get_signed<T: DeserializeOwned>(&self, endpoint: API, request: Option<String>) -> Result<T, String> {
+    pub async fn get_signed<T: DeserializeOwned>(&self, endpoint: API, request: Option<String>) -> Result<T, HolocoreError> {
         let url = self.sign_request(endpoint, request);
         match self.inner
             .get(&url)
@@ -61,11 +62,11 @@ impl BinanceSpotClient {
                         Err(e) => Err(e)
                     }
                 },
-                Err(e) => Err(e.to_string())
+                Err(e) => Err(HolocoreError::Network(e.to_string()))
             }
     }
 
-    pub async fn post_signed<T: DeserializeOwned>(&self, endpoint: API, request: Option<String>) -> Result<T, String> {
+    pub async fn post_signed<T: DeserializeOwned>(&self, endpoint: API, request: Option<String>) -> Result<T, HolocoreError> {
         let url = self.sign_request(endpoint, request);
         log::debug!("post_signed: {}", url);
 
@@ -79,11 +80,11 @@ impl BinanceSpotClient {
                         Err(e) => Err(e)
                     }
                 },
-                Err(e) => Err(e.to_string())
+                Err(e) => Err(HolocoreError::Network(e.to_string()))
             }
     }
 
-    pub async fn delete_signed<T: DeserializeOwned>(&self, endpoint: API, request: Option<String>) -> Result<T, String> {
+    pub async fn delete_signed<T: DeserializeOwned>(&self, endpoint: API, request: Option<String>) -> Result<T, HolocoreError> {
         let url = self.sign_request(endpoint, request);
         log::debug!("delete_signed: {}", url);
 
@@ -97,7 +98,7 @@ impl BinanceSpotClient {
                         Err(e) => Err(e)
                     }
                 },
-                Err(e) => Err(e.to_string())
+                Err(e) => Err(HolocoreError::Network(e.to_string()))
             }
     }
 
@@ -155,7 +156,7 @@ impl BinanceSpotClient {
     //     self.handler(response)
     // }
 
-    async fn handler<T: DeserializeOwned>(&self, response: Response) -> Result<T, String> {
+    async fn handler<T: DeserializeOwned>(&self, response: Response) -> Result<T, HolocoreError> {
         let status = response.status();
 
         match status {
@@ -163,13 +164,19 @@ impl BinanceSpotClient {
                 Ok(json) => Ok(json),
                 Err(e) => {
                     log::error!("{}", e);
-                    Err(e.to_string())
+                    Err(HolocoreError::Malformed(e.to_string()))
                 }
             },
+            // The exchange refuses the request on a 4xx, a 5xx is worth retrying
             _ => {
-                match response.text().await {
-                    Ok(text) => Err(format!("Response {}: {}", status, text)),
-                    Err(e) => Err(format!("Response {} - {}: Cannot extract text response ", status, e))
+                let err_msg = match response.text().await {
+                    Ok(text) => format!("Response {}: {}", status, text),
+                    Err(e) => format!("Response {} - {}: Cannot extract text response ", status, e)
+                };
+                if status.is_client_error() {
+                    Err(HolocoreError::Rejected(err_msg))
+                } else {
+                    Err(HolocoreError::Network(err_msg))
                 }
             }
         }
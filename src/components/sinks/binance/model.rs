@@ -1,6 +1,6 @@
 use serde::{ Deserialize, Serialize };
 
-use crate::objects::new_order::{ NewOrderV1, OrderSide, OrderType };
+use crate::objects::new_order::{ NewOrderV2, OrderSide, OrderType };
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum BinanceOrderSide {
@@ -119,8 +119,8 @@ pub struct BinanceNewOrder {
     pub price: String
 }
 
-impl From<NewOrderV1> for BinanceNewOrder {
-    fn from(item: NewOrderV1) -> Self {
+impl From<NewOrderV2> for BinanceNewOrder {
+    fn from(item: NewOrderV2) -> Self {
         Self {
             symbol: item.symbol,
             order_side: BinanceOrderSide::from(item.order_side),
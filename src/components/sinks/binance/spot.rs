@@ -1,3 +1,4 @@
+use crate::components::sinks::Sink;
 use crate::components::sinks::binance::api::{ API, Spot };
 use crate::components::sinks::binance::client::BinanceSpotClient;
 use crate::components::sinks::binance::model::{ BinanceAccountInformation,
@@ -7,16 +8,22 @@ use crate::components::sinks::binance::model::{ BinanceAccountInformation,
                                                 BinanceOrder,
                                                 BinanceOrderCanceled };
 use crate::config::sinks::BinanceSpotSinkConfig;
-use crate::objects::{ Objects, new_order::NewOrderV1 };
+use crate::error::HolocoreError;
+use crate::objects::{ Objects, new_order::NewOrderV2, numeric::Precision };
 
+use async_trait::async_trait;
 use rocket::{ self, http, Build, Rocket, State };
 use rocket::serde::json::Json;
-use std::collections::BTreeMap;
+use std::collections::{ BTreeMap, HashMap };
+
+// Decimals of the orders per symbol
+struct Precisions(HashMap<String, Precision>);
 
 pub struct BinanceSpotSink {
     name: String,
     sent_objs: u64,
-    client: BinanceSpotClient
+    client: BinanceSpotClient,
+    precisions: HashMap<String, Precision>
 }
 
 impl BinanceSpotSink {
@@ -24,32 +31,37 @@ impl BinanceSpotSink {
         Self {
             name: String::from(&cfg.name),
             sent_objs: 0,
-            client: BinanceSpotClient::new(&cfg.base_url, &cfg.api_key, &cfg.secret_key, &cfg.user_agent, &cfg.content_type)
+            client: BinanceSpotClient::new(&cfg.base_url, cfg.api_key.expose(), cfg.secret_key.expose(), &cfg.user_agent, &cfg.content_type),
+            precisions: cfg.precisions.clone()
         }
     }
+}
 
-    pub async fn init(&mut self) -> Result<(), String> {
+#[async_trait]
+impl Sink for BinanceSpotSink {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
         Ok(())
     }
 
-    pub async fn send(&mut self, _obj: Objects) -> Result<(), String> {
+    async fn send(&mut self, _obj: Objects) -> Result<(), HolocoreError> {
         self.sent_objs += 1;
 
         // match obj {
-        //     _ => self.new_order("BTCUSDT", OrderSide::Buy, OrderType::Market, FixedU64::<32>::from_num(1), FixedU64::<32>::from_num(1)).await
+        //     _ => self.new_order("BTCUSDT", OrderSide::Buy, OrderType::Market, Quantity::from_int(1), Price::from_int(1)).await
         // }
 
         Ok(())
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl.manage(self.client.clone())
+            .manage(Precisions(self.precisions.clone()))
             .mount(format!("/{}/", self.name), rocket::routes!(get_account))
             .mount(format!("/{}/", self.name), rocket::routes!(get_balance))
             .mount(format!("/{}/", self.name), rocket::routes!(get_open_orders))
@@ -60,13 +72,13 @@ impl BinanceSpotSink {
 }
 
 // Helpers
-async fn account(client: &BinanceSpotClient) -> Result<BinanceAccountInformation, String> {
+async fn account(client: &BinanceSpotClient) -> Result<BinanceAccountInformation, HolocoreError> {
     let params = BTreeMap::<String, String>::new();
     let request = BinanceSpotClient::build_request(params, None);
     client.get_signed(API::Spot(Spot::Account), request).await
 }
 
-async fn balance(client: &BinanceSpotClient, symbol: &str) -> Result<BinanceBalance, String> {
+async fn balance(client: &BinanceSpotClient, symbol: &str) -> Result<BinanceBalance, HolocoreError> {
     match account(client).await {
         Ok(account) => {
             for balance in account.balances {
@@ -75,13 +87,13 @@ async fn balance(client: &BinanceSpotClient, symbol: &str) -> Result<BinanceBala
                 }
             }
 
-            Err(format!("Cannot find balance for asset {}", symbol))
+            Err(HolocoreError::Rejected(format!("Cannot find balance for asset {}", symbol)))
         },
         Err(e) => Err(e)
     }
 }
 
-async fn list_orders(client: &BinanceSpotClient, symbol: &str) -> Result<Vec<BinanceOrder>, String>
+async fn list_orders(client: &BinanceSpotClient, symbol: &str) -> Result<Vec<BinanceOrder>, HolocoreError>
 {
     let mut params = BTreeMap::<String, String>::new();
     params.insert("symbol".into(), symbol.into());
@@ -90,7 +102,7 @@ async fn list_orders(client: &BinanceSpotClient, symbol: &str) -> Result<Vec<Bin
     client.get_signed::<Vec<BinanceOrder>>(API::Spot(Spot::AllOrders), request).await
 }
 
-async fn list_open_orders(client: &BinanceSpotClient, symbol: &str) -> Result<Vec<BinanceOrder>, String>
+async fn list_open_orders(client: &BinanceSpotClient, symbol: &str) -> Result<Vec<BinanceOrder>, HolocoreError>
 {
     let mut params = BTreeMap::<String, String>::new();
     params.insert("symbol".into(), symbol.into());
@@ -99,7 +111,7 @@ async fn list_open_orders(client: &BinanceSpotClient, symbol: &str) -> Result<Ve
     client.get_signed::<Vec<BinanceOrder>>(API::Spot(Spot::OpenOrders), request).await
 }
 
-async fn new_order(client: &BinanceSpotClient, new_order: BinanceNewOrder) -> Result<BinanceAckOrder, String> {
+async fn new_order(client: &BinanceSpotClient, new_order: BinanceNewOrder) -> Result<BinanceAckOrder, HolocoreError> {
     let mut params = BTreeMap::<String, String>::new();
 
     params.insert(String::from("symbol"), new_order.symbol);
@@ -112,7 +124,7 @@ async fn new_order(client: &BinanceSpotClient, new_order: BinanceNewOrder) -> Re
     client.post_signed::<BinanceAckOrder>(API::Spot(Spot::Order), request).await
 }
 
-async fn cancel_order(client: &BinanceSpotClient, symbol: &str, order_id: i64) -> Result<BinanceOrderCanceled, String> {
+async fn cancel_order(client: &BinanceSpotClient, symbol: &str, order_id: i64) -> Result<BinanceOrderCanceled, HolocoreError> {
     let mut params = BTreeMap::<String, String>::new();
     params.insert(String::from("symbol"), symbol.to_string());
     params.insert(String::from("orderId"), order_id.to_string());
@@ -130,7 +142,7 @@ async fn get_account(state: &State<BinanceSpotClient>) -> (http::Status, String)
                 Err(e) => (http::Status::BadRequest, e.to_string())
             }
         },
-        Err(e) => (http::Status::BadRequest, e)
+        Err(e) => (http::Status::BadRequest, e.to_string())
     }
 }
 
@@ -143,7 +155,7 @@ async fn get_balance(symbol: &str, state: &State<BinanceSpotClient>) -> (http::S
                 Err(e) => (http::Status::BadRequest, e.to_string())
             }
         },
-        Err(e) => (http::Status::BadRequest, e)
+        Err(e) => (http::Status::BadRequest, e.to_string())
     }
 }
 
@@ -156,20 +168,27 @@ async fn get_open_orders(symbol: &str, state: &State<BinanceSpotClient>) -> (htt
                 Err(e) => (http::Status::BadRequest, e.to_string())
             }
         },
-        Err(e) => (http::Status::BadRequest, e)
+        Err(e) => (http::Status::BadRequest, e.to_string())
     }
 }
 
 #[rocket::post("/orders", format = "json", data = "<order>")]
-async fn post_new_order(order: Json<NewOrderV1>, state: &State<BinanceSpotClient>) -> (http::Status, String) {
-    match new_order(state, BinanceNewOrder::from(order.into_inner())).await {
+async fn post_new_order(order: Json<NewOrderV2>, state: &State<BinanceSpotClient>, precisions: &State<Precisions>) -> (http::Status, String) {
+    let mut order = order.into_inner();
+    if let Some(precision) = precisions.0.get(&order.symbol) {
+        if let Err(e) = order.set_precision(precision) {
+            return (http::Status::BadRequest, e.to_string());
+        }
+    }
+
+    match new_order(state, BinanceNewOrder::from(order)).await {
         Ok(ack) => {
             match serde_json::to_string(&ack) {
                 Ok(text) => (http::Status::Ok, text),
                 Err(e) => (http::Status::BadRequest, e.to_string())
             }
         },
-        Err(e) => (http::Status::BadRequest, e)
+        Err(e) => (http::Status::BadRequest, e.to_string())
     }
 }
 
@@ -182,7 +201,7 @@ async fn get_orders(symbol: &str, state: &State<BinanceSpotClient>) -> (http::St
                 Err(e) => (http::Status::BadRequest, e.to_string())
             }
         },
-        Err(e) => (http::Status::BadRequest, e)
+        Err(e) => (http::Status::BadRequest, e.to_string())
     }
 }
 
@@ -195,6 +214,6 @@ async fn delete_cancel_order(symbol: &str, order_id: i64, state: &State<BinanceS
                 Err(e) => (http::Status::BadRequest, e.to_string())
             }
         },
-        Err(e) => (http::Status::BadRequest, e)
+        Err(e) => (http::Status::BadRequest, e.to_string())
     }
 }
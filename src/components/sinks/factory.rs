@@ -1,14 +1,26 @@
-use crate::components::sinks::{ Sinks, binance::spot::BinanceSpotSink, file::FileSink, mock::MockSink };
+use crate::components::registry::{ Registry, SharedSink };
+use crate::components::sinks::{ binance::spot::BinanceSpotSink, file::FileSink, mock::MockSink, shm::ShmSink, tcp::TcpSink };
 use crate::config::sinks::SinkConfigs;
+use crate::error::HolocoreError;
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-pub fn make(cfg: &SinkConfigs) -> Sinks {
+pub fn register(registry: &mut Registry) {
+    registry.register_sink("BinanceSpotSink", make);
+    registry.register_sink("FileSink", make);
+    registry.register_sink("MockSink", make);
+    registry.register_sink("ShmSink", make);
+    registry.register_sink("TcpSink", make);
+}
+
+pub fn make(cfg: &SinkConfigs) -> Result<SharedSink, HolocoreError> {
     match cfg {
-        SinkConfigs::BinanceSpotSink(cfg) => Sinks::BinanceSpotSink(Arc::new(Mutex::new(BinanceSpotSink::new(cfg)))),
-        SinkConfigs::FileSink(cfg) => Sinks::FileSink(Arc::new(Mutex::new(FileSink::new(cfg)))),
-        SinkConfigs::MockSink(cfg) => Sinks::MockSink(Arc::new(Mutex::new(MockSink::new(cfg)))),
-        // _ => panic!("Cannot build sink {}", cfg.name())
+        SinkConfigs::BinanceSpotSink(cfg) => Ok(Arc::new(Mutex::new(BinanceSpotSink::new(cfg)))),
+        SinkConfigs::FileSink(cfg) => Ok(Arc::new(Mutex::new(FileSink::new(cfg)))),
+        SinkConfigs::MockSink(cfg) => Ok(Arc::new(Mutex::new(MockSink::new(cfg)))),
+        SinkConfigs::ShmSink(cfg) => Ok(Arc::new(Mutex::new(ShmSink::new(cfg)))),
+        SinkConfigs::TcpSink(cfg) => Ok(Arc::new(Mutex::new(TcpSink::new(cfg)))),
+        SinkConfigs::Custom(cfg) => Err(HolocoreError::Config(format!("Cannot build sink {} - malformed {} config", cfg.name, cfg.type_name)))
     }
 }
@@ -1,7 +1,11 @@
-use crate::config::sinks::FileSinkConfig;
+use crate::components::sinks::Sink;
+use crate::config::{ format::Format, sinks::FileSinkConfig };
+use crate::error::HolocoreError;
 use crate::objects::Objects;
+use crate::utils::binary;
 use crate::utils::time::{ datetime_from_ts, now };
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket };
 use tokio::{fs::File, io::AsyncWriteExt};
 
@@ -9,6 +13,7 @@ pub struct FileSink {
     name: String,
     sent_objs: u64,
     filename: String,
+    format: Format,
     fd: Option<File>
 }
 
@@ -18,60 +23,82 @@ impl FileSink {
             name: String::from(&cfg.name),
             sent_objs: 0,
             filename: format!("{}_{}", cfg.filename, datetime_from_ts(now())),
+            format: cfg.format,
             fd: None
         }
     }
+}
 
-    pub async fn init(&mut self) -> Result<(), String> {
-        self.fd = Some(File::create(&self.filename).await.expect("FileSink: cannot create file"));
+#[async_trait]
+impl Sink for FileSink {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        let fd = File::create(&self.filename).await.map_err(|e| HolocoreError::Io(format!("{}: cannot create file {} - {}", self.name, self.filename, e)))?;
+        self.fd = Some(fd);
         Ok(())
     }
 
-    pub async fn send(&mut self, obj: Objects) -> Result<(), String> {
-        match serde_json::to_string(&obj) {
+    async fn send(&mut self, obj: Objects) -> Result<(), HolocoreError> {
+        let wire = match self.format {
+            // One object per line
+            Format::Json => serde_json::to_vec(&obj).map(|mut wire| {
+                                                        wire.push(0xA);
+                                                        wire
+                                                    })
+                                                    .map_err(|e| HolocoreError::Malformed(e.to_string())),
+            Format::Binary => binary::encode(&obj)
+        };
+
+        match wire {
             Ok(wire) => {
                 match self.fd.as_mut() {
                     Some(fd) => {
-                        match fd.write_all(wire.as_bytes()).await {
+                        match fd.write_all(&wire).await {
                             Ok(()) => {
-                                match fd.write_u8(0xA).await {
-                                    Ok(()) => {
-                                        self.sent_objs += 1;
-                                        Ok(())
-                                    },
-                                    Err(e) => {
-                                        log::error!("{}: failed to write newline", &self.name);
-                                        Err(e.to_string())
-                                    }
-                                }
+                                self.sent_objs += 1;
+                                Ok(())
                             },
                             Err(e) => {
                                 log::error!("{}: failed to write to file", &self.name);
-                                Err(e.to_string())
+                                Err(HolocoreError::Io(e.to_string()))
                             }
                         }
                     },
                     None => {
                         let err_msg = format!("{}: Cannot find the file descriptor", &self.name);
                         log::error!("{}", &err_msg);
-                        Err(err_msg)
+                        Err(HolocoreError::Internal(err_msg))
                     }
                 }
             },
             Err(e) => {
                 log::error!("{}: Cannot serialize object - {}", &self.name, e);
-                Err(e.to_string())
+                Err(e)
             }
         }
     }
 
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        match self.fd.as_mut() {
+            Some(fd) => {
+                if let Err(e) = fd.flush().await {
+                    log::error!("{}: failed to flush file {}", self.name, self.filename);
+                    return Err(HolocoreError::Io(e.to_string()));
+                }
+
+                log::info!("{}: flushed {} objects to {}", self.name, self.sent_objs, self.filename);
+                Ok(())
+            },
+            None => Ok(())
+        }
+    }
+
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl
     }
 }
@@ -1,7 +1,10 @@
+use crate::components::sinks::Sink;
 use crate::config::sinks::MockSinkConfig;
+use crate::error::HolocoreError;
 use crate::objects::Objects;
 use crate::utils::time::now_nanos;
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket, http };
 
 pub struct MockSink {
@@ -16,17 +19,20 @@ impl MockSink {
             sent_objs: 0
         }
     }
+}
 
-    pub async fn init(&mut self) -> Result<(), String> {
+#[async_trait]
+impl Sink for MockSink {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
         Ok(())
     }
 
-    pub async fn send(&mut self, obj: Objects) -> Result<(), String> {
+    async fn send(&mut self, obj: Objects) -> Result<(), HolocoreError> {
         self.sent_objs += 1;
         let now = now_nanos();
         log::debug!("{}: Received {} messages - msg id: {} - lag {} micros", self.name, self.sent_objs, obj.get_id(), (now - obj.get_timestamp()) / 1_000);
 
-        if let Objects::OrderbookUpdateV1(obj) = obj {
+        if let Objects::OrderbookUpdateV2(obj) = obj {
             let now_ms: u64 = now as u64 / 1_000;
             let timestamp: u64 = obj.get_timestamp() as u64 / 1_000;
             log::debug!("{}: lag from event {} - {}  = {} micros", self.name, now_ms, timestamp, now_ms - timestamp);
@@ -36,12 +42,12 @@ impl MockSink {
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl.mount(format!("/{}/", self.name), rocket::routes!(test))
     }
 }
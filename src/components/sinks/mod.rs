@@ -2,12 +2,37 @@ pub mod factory;
 pub mod binance;
 pub mod file;
 pub mod mock;
+pub mod shm;
+pub mod tcp;
 
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+use crate::utils::{ clock::Clock, uuid::IdGenerator };
+
+use async_trait::async_trait;
+use rocket::{ Build, Rocket };
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
-pub enum Sinks {
-    BinanceSpotSink(Arc<Mutex<binance::spot::BinanceSpotSink>>),
-    FileSink(Arc<Mutex<file::FileSink>>),
-    MockSink(Arc<Mutex<mock::MockSink>>)
+#[async_trait]
+pub trait Sink: Send {
+    async fn init(&mut self) -> Result<(), HolocoreError>;
+    async fn send(&mut self, obj: Objects) -> Result<(), HolocoreError>;
+
+    // Called once the inputs are drained, e.g. to flush buffered output
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str;
+
+    // Setters
+    // Clock and ID generator of the objects the component creates, set before the first init
+    fn set_clock(&mut self, _clock: Arc<dyn Clock>) {}
+    fn set_id_generator(&mut self, _ids: Arc<dyn IdGenerator>) {}
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
 }
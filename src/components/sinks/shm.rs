@@ -0,0 +1,125 @@
+use crate::components::sinks::Sink;
+use crate::config::sinks::ShmSinkConfig;
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+use crate::utils::binary;
+use crate::utils::shm::ShmWriter;
+
+use async_trait::async_trait;
+use rocket::{ self, Build, Rocket };
+
+// Holds the record of a full book, 1000 levels per side of 16 bytes each
+pub const MIN_SLOT_SIZE: u64 = 65536;
+
+// Publishes objects to the ShmSources of the processes on the same host
+pub struct ShmSink {
+    name: String,
+    path: String,
+    capacity: u64,
+    slot_size: u64,
+    sent_objs: u64,
+    writer: Option<ShmWriter>
+}
+
+impl ShmSink {
+    pub fn new(cfg: &ShmSinkConfig) -> Self {
+        Self {
+            name: String::from(&cfg.name),
+            path: String::from(&cfg.path),
+            capacity: cfg.capacity,
+            slot_size: cfg.slot_size,
+            sent_objs: 0,
+            writer: None
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for ShmSink {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        if self.slot_size < MIN_SLOT_SIZE {
+            let err_msg = format!("{}: slot size {} is below {}, which a full book needs", self.name, self.slot_size, MIN_SLOT_SIZE);
+            log::error!("{}", err_msg);
+            return Err(HolocoreError::Config(err_msg));
+        }
+
+        match ShmWriter::create(&self.path, self.capacity, self.slot_size) {
+            Ok(writer) => {
+                log::info!("{}: publishing to {}", self.name, self.path);
+                self.writer = Some(writer);
+                Ok(())
+            },
+            Err(e) => {
+                log::error!("{}: {}", self.name, e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn send(&mut self, obj: Objects) -> Result<(), HolocoreError> {
+        let payload = binary::encode(&obj).inspect_err(|e| log::error!("{}: {}", self.name, e))?;
+        match self.writer.as_mut() {
+            Some(writer) => {
+                if let Err(e) = writer.push(&payload) {
+                    log::error!("{}: {}", self.name, e);
+                    return Err(e);
+                }
+
+                self.sent_objs += 1;
+                Ok(())
+            },
+            None => Err(HolocoreError::Internal(format!("{}: writer is not set", self.name)))
+        }
+    }
+
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        log::info!("{}: published {} objects to {}", self.name, self.sent_objs, self.path);
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sinks::{ Sink, shm::{ MIN_SLOT_SIZE, ShmSink } };
+    use crate::components::sources::{ Source, shm::ShmSource };
+    use crate::components::sources::binance::model::{ BinanceAsks, BinanceBids, BinanceOrderBookEvent };
+    use crate::config::sinks::ShmSinkConfig;
+    use crate::config::sources::ShmSourceConfig;
+    use crate::objects::{ Objects, orderbook::OrderbookV2 };
+    use crate::utils::uuid;
+
+    #[tokio::test]
+    async fn test_full_book() {
+        let path = std::env::temp_dir().join("holocore_test_shm_sink");
+        let path = path.to_string_lossy().into_owned();
+        let mut sink = ShmSink::new(&ShmSinkConfig { name: String::from("ShmSink"), path: String::clone(&path), capacity: 4, slot_size: MIN_SLOT_SIZE / 2, ..Default::default() });
+        assert!(sink.init().await.is_err());
+
+        let mut sink = ShmSink::new(&ShmSinkConfig { name: String::from("ShmSink"), path: String::clone(&path), capacity: 4, slot_size: MIN_SLOT_SIZE, ..Default::default() });
+        let mut source = ShmSource::new(&ShmSourceConfig { name: String::from("ShmSource"), path, poll_interval: 10, ..Default::default() });
+        sink.init().await.unwrap();
+        source.init().await.unwrap();
+
+        // The deepest snapshot Binance sends
+        let event = BinanceOrderBookEvent {
+            last_update_id: 7,
+            bids: (1..=1000).map(|idx| BinanceBids { price: format!("{}.25", 50000 - idx), qty: String::from("1.5") }).collect(),
+            asks: (1..=1000).map(|idx| BinanceAsks { price: format!("{}.25", 50000 + idx), qty: String::from("0.125") }).collect()
+        };
+        sink.send(Objects::OrderbookV2(OrderbookV2::from_binance(uuid::uuid(), 42, 3, uuid::uuid(), "BinanceSpotSource", "Binance", "Spot", "BTCUSDT", event))).await.unwrap();
+        match source.recv().await.unwrap() {
+            (Some(Objects::OrderbookV2(book)), false) => assert_eq!((book.best_bid().0.to_string(), book.best_ask().0.to_string()), (String::from("49999.25"), String::from("50001.25"))),
+            (obj, _) => panic!("Expected OrderbookV2, got {:?}", obj.map(|obj| obj.get_type_name()))
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::components::sinks::Sink;
+use crate::config::sinks::TcpSinkConfig;
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+use crate::utils::{ frame, uuid::uuid };
+
+use async_trait::async_trait;
+use flume::{ Sender, TrySendError, bounded };
+use rocket::{ self, Build, Rocket };
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+use tokio::io::{ AsyncReadExt, AsyncWriteExt };
+use tokio::net::{ TcpListener, TcpStream };
+use tokio::task::JoinHandle;
+use tokio::time::{ Instant, timeout, timeout_at };
+use uuid::Uuid;
+
+// Frames queued for a subscriber before it is considered too slow and dropped
+const SUBSCRIBER_QUEUE_SIZE: usize = 1024;
+
+// Time given to a subscriber to answer the handshake, in milliseconds
+const HANDSHAKE_TIMEOUT: u64 = 5000;
+
+// Time given to the subscribers to receive the queued frames on shutdown, in milliseconds
+const FLUSH_TIMEOUT: u64 = 2000;
+
+type Frame = Arc<Vec<u8>>;
+
+// Sent frames, kept so that reconnecting subscribers can resume, and the live subscribers.
+// Both are behind the same lock, so that a subscriber misses nothing between the replay
+// and the live frames.
+struct Hub {
+    sequence: u64,
+    replay_size: usize,
+    replay: VecDeque<(u64, Frame)>,
+    subscribers: Vec<(SocketAddr, Sender<Frame>)>,
+    writers: Vec<JoinHandle<()>>,
+    closed: bool
+}
+
+pub struct TcpSink {
+    name: String,
+    address: String,
+    // Tells subscribers whether the sequence numbers they hold come from this run
+    session: Uuid,
+    hub: Arc<Mutex<Hub>>,
+    listener: Option<JoinHandle<()>>
+}
+
+impl TcpSink {
+    pub fn new(cfg: &TcpSinkConfig) -> Self {
+        Self {
+            name: String::from(&cfg.name),
+            address: String::from(&cfg.address),
+            session: uuid(),
+            hub: Arc::new(Mutex::new(Hub {
+                sequence: 0,
+                replay_size: cfg.replay_size,
+                replay: VecDeque::with_capacity(cfg.replay_size),
+                subscribers: vec![],
+                writers: vec![],
+                closed: false
+            })),
+            listener: None
+        }
+    }
+}
+
+// Handshake: the sink sends its session, the subscriber answers with the last sequence
+// number it received from that session, or 0 to start from the live frames
+async fn subscribe(name: &str, mut stream: TcpStream, peer: SocketAddr, session: Uuid, hub: &Mutex<Hub>) -> Result<(), String> {
+    stream.set_nodelay(true).map_err(|e| e.to_string())?;
+    stream.write_all(session.as_bytes()).await.map_err(|e| e.to_string())?;
+    let last = match timeout(Duration::from_millis(HANDSHAKE_TIMEOUT), stream.read_u64()).await {
+        Ok(result) => result.map_err(|e| e.to_string())?,
+        Err(_) => return Err(String::from("handshake timed out"))
+    };
+
+    let (tx, rx) = bounded::<Frame>(SUBSCRIBER_QUEUE_SIZE);
+    let replay = match hub.lock() {
+        Ok(mut hub) => {
+            if hub.closed {
+                return Err(String::from("sink is shutting down"));
+            }
+
+            let replay = if last == 0 {
+                vec![]
+            } else {
+                if hub.replay.front().map(|(sequence, _)| *sequence > last + 1).unwrap_or(hub.sequence > last) {
+                    log::warn!("{}: {} resumes after {}, older frames are lost", name, peer, last);
+                }
+                hub.replay.iter().filter(|(sequence, _)| *sequence > last).map(|(_, frame)| Arc::clone(frame)).collect()
+            };
+            hub.subscribers.push((peer, tx));
+            replay
+        },
+        Err(_) => return Err(String::from("hub lock is poisoned"))
+    };
+
+    log::info!("{}: {} subscribed, replaying {} frames", name, peer, replay.len());
+    for frame in replay {
+        stream.write_all(&frame).await.map_err(|e| e.to_string())?;
+    }
+    // Ends once the subscriber is dropped or the sink shuts down
+    while let Ok(frame) = rx.recv_async().await {
+        stream.write_all(&frame).await.map_err(|e| e.to_string())?;
+    }
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+#[async_trait]
+impl Sink for TcpSink {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        // The supervisor calls init again after a restart, the previous listener holds the port
+        // until its task is gone. The replay buffer is kept.
+        if let Some(listener) = self.listener.take() {
+            listener.abort();
+            let _ = listener.await;
+        }
+
+        let listener = match TcpListener::bind(&self.address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                let err_msg = format!("{}: cannot listen on {} - {}", self.name, self.address, e);
+                log::error!("{}", err_msg);
+                return Err(HolocoreError::Network(err_msg));
+            }
+        };
+
+        if let Ok(mut hub) = self.hub.lock() {
+            hub.closed = false;
+        }
+
+        log::info!("{}: listening on {}", self.name, self.address);
+        let (name, session, hub) = (String::clone(&self.name), self.session, Arc::clone(&self.hub));
+        self.listener = Some(tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let (writer_name, writer_hub) = (String::clone(&name), Arc::clone(&hub));
+                        let writer = tokio::spawn(async move {
+                            if let Err(e) = subscribe(&writer_name, stream, peer, session, &writer_hub).await {
+                                log::warn!("{}: {} disconnected - {}", writer_name, peer, e);
+                            }
+                        });
+                        if let Ok(mut hub) = hub.lock() {
+                            hub.writers.retain(|writer| !writer.is_finished());
+                            hub.writers.push(writer);
+                        }
+                    },
+                    Err(e) => log::error!("{}: cannot accept subscriber - {}", name, e)
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    async fn send(&mut self, obj: Objects) -> Result<(), HolocoreError> {
+        match self.hub.lock() {
+            Ok(mut hub) => {
+                let sequence = hub.sequence + 1;
+                let frame = match frame::encode(sequence, &obj) {
+                    Ok(frame) => Arc::new(frame),
+                    Err(e) => {
+                        log::error!("{}: {}", self.name, e);
+                        return Err(e);
+                    }
+                };
+                hub.sequence = sequence;
+
+                if hub.replay_size > 0 {
+                    if hub.replay.len() == hub.replay_size {
+                        hub.replay.pop_front();
+                    }
+                    hub.replay.push_back((sequence, Arc::clone(&frame)));
+                }
+
+                // A slow subscriber must not hold the others back, it resumes from the replay buffer
+                let name = &self.name;
+                hub.subscribers.retain(|(peer, tx)| match tx.try_send(Arc::clone(&frame)) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        log::warn!("{}: {} is too slow, dropping it", name, peer);
+                        false
+                    },
+                    Err(TrySendError::Disconnected(_)) => false
+                });
+                Ok(())
+            },
+            Err(_) => Err(HolocoreError::Internal(format!("{}: hub lock is poisoned", self.name)))
+        }
+    }
+
+    // Stops accepting subscribers and lets the connected ones receive the queued frames, a
+    // subscriber which stopped reading is cut off after FLUSH_TIMEOUT
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        if let Some(listener) = self.listener.take() {
+            listener.abort();
+            let _ = listener.await;
+        }
+
+        let writers = match self.hub.lock() {
+            Ok(mut hub) => {
+                hub.closed = true;
+                hub.subscribers.clear();
+                std::mem::take(&mut hub.writers)
+            },
+            Err(_) => vec![]
+        };
+        let deadline = Instant::now() + Duration::from_millis(FLUSH_TIMEOUT);
+        for mut writer in writers {
+            if timeout_at(deadline, &mut writer).await.is_err() {
+                log::warn!("{}: a subscriber has not received the queued frames in {} ms, closing it", self.name, FLUSH_TIMEOUT);
+                writer.abort();
+            }
+        }
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sinks::{ Sink, tcp::TcpSink };
+    use crate::components::sources::{ Source, tcp::TcpSource };
+    use crate::config::sinks::TcpSinkConfig;
+    use crate::config::sources::TcpSourceConfig;
+    use crate::objects::{ Objects, mock::MockV1 };
+    use crate::utils::uuid;
+
+    use std::time::Duration;
+
+    fn mock(sequence_number: u64) -> Objects {
+        Objects::MockV1(MockV1::new(uuid::uuid(), 0, sequence_number, uuid::uuid()))
+    }
+
+    #[tokio::test]
+    async fn test_resume() {
+        let address = String::from("127.0.0.1:18641");
+        let mut sink = TcpSink::new(&TcpSinkConfig { name: String::from("TcpSink"), address: String::clone(&address), replay_size: 16, ..Default::default() });
+        let mut source = TcpSource::new(&TcpSourceConfig { name: String::from("TcpSource"), address, reconnect_backoff: 200, max_reconnect_backoff: 200, ..Default::default() });
+        sink.init().await.unwrap();
+        source.init().await.unwrap();
+
+        let (tx, rx) = flume::unbounded();
+        tokio::spawn(async move {
+            while let Ok((Some(Objects::MockV1(obj)), _)) = source.recv().await {
+                let _ = tx.send_async(obj.get_sequence_number()).await;
+            }
+        });
+
+        while sink.hub.lock().unwrap().subscribers.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        sink.send(mock(1)).await.unwrap();
+        sink.send(mock(2)).await.unwrap();
+        assert_eq!(rx.recv_async().await.unwrap(), 1);
+        assert_eq!(rx.recv_async().await.unwrap(), 2);
+
+        // Objects sent while the source reconnects are replayed
+        sink.hub.lock().unwrap().subscribers.clear();
+        sink.send(mock(3)).await.unwrap();
+        sink.send(mock(4)).await.unwrap();
+        assert_eq!(rx.recv_async().await.unwrap(), 3);
+        assert_eq!(rx.recv_async().await.unwrap(), 4);
+
+        sink.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart() {
+        let mut sink = TcpSink::new(&TcpSinkConfig { name: String::from("TcpSink"), address: String::from("127.0.0.1:18642"), ..Default::default() });
+        sink.init().await.unwrap();
+
+        // The previous listener releases the port
+        sink.init().await.unwrap();
+        assert!(tokio::net::TcpStream::connect("127.0.0.1:18642").await.is_ok());
+
+        sink.shutdown().await.unwrap();
+    }
+}
@@ -1,5 +1,39 @@
 use serde::{ Deserialize, Serialize };
 
+// Aggregate trade
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BinanceAggTradeEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "a")]
+    pub agg_trade_id: u64,
+
+    #[serde(rename = "p")]
+    pub price: String,
+
+    #[serde(rename = "q")]
+    pub qty: String,
+
+    #[serde(rename = "f")]
+    pub first_trade_id: u64,
+
+    #[serde(rename = "l")]
+    pub last_trade_id: u64,
+
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool
+}
+
 // Asks
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BinanceAsks {
@@ -14,6 +48,28 @@ pub struct BinanceBids {
     pub qty: String,
 }
 
+// Book ticker, the only event without an event type
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BinanceBookTickerEvent {
+    #[serde(rename = "u")]
+    pub update_id: u64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "b")]
+    pub bid_price: String,
+
+    #[serde(rename = "B")]
+    pub bid_qty: String,
+
+    #[serde(rename = "a")]
+    pub ask_price: String,
+
+    #[serde(rename = "A")]
+    pub ask_qty: String
+}
+
 // Kline
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BinanceKline {
@@ -122,6 +178,74 @@ pub struct BinanceOrderbookUpdateEvent {
     pub asks: Vec<BinanceAsks>,
 }
 
+// 24h ticker, the mini ticker only sends the fields without a default
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BinanceTickerEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    #[serde(rename = "E")]
+    pub event_time: u64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "o")]
+    pub open: String,
+
+    #[serde(rename = "h")]
+    pub high: String,
+
+    #[serde(rename = "l")]
+    pub low: String,
+
+    #[serde(rename = "c")]
+    pub close: String,
+
+    #[serde(rename = "v")]
+    pub volume: String,
+
+    #[serde(rename = "q")]
+    pub quote_volume: String,
+
+    #[serde(rename = "p", default)]
+    pub price_change: String,
+
+    #[serde(rename = "w", default)]
+    pub weighted_average_price: String,
+
+    #[serde(rename = "Q", default)]
+    pub last_qty: String,
+
+    #[serde(rename = "b", default)]
+    pub bid_price: String,
+
+    #[serde(rename = "B", default)]
+    pub bid_qty: String,
+
+    #[serde(rename = "a", default)]
+    pub ask_price: String,
+
+    #[serde(rename = "A", default)]
+    pub ask_qty: String,
+
+    #[serde(rename = "O", default)]
+    pub open_time: u64,
+
+    #[serde(rename = "C", default)]
+    pub close_time: u64,
+
+    // -1 without trades
+    #[serde(rename = "F", default)]
+    pub first_trade_id: i64,
+
+    #[serde(rename = "L", default)]
+    pub last_trade_id: i64,
+
+    #[serde(rename = "n", default)]
+    pub number_of_trades: u64
+}
+
 // Trade
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BinanceTradeEvent {
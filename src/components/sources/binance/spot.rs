@@ -1,10 +1,26 @@
+use crate::components::sources::Source;
 use crate::components::control::{ ControlState, Controls };
-use crate::components::sources::binance::model::{ BinanceKlineEvent, BinanceOrderBookEvent, BinanceOrderbookUpdateEvent, BinanceTradeEvent };
+use crate::components::sources::binance::model::{ BinanceAggTradeEvent,
+                                                  BinanceBookTickerEvent,
+                                                  BinanceKlineEvent,
+                                                  BinanceOrderBookEvent,
+                                                  BinanceOrderbookUpdateEvent,
+                                                  BinanceTickerEvent,
+                                                  BinanceTradeEvent };
 use crate::config::sources::{BinanceSpotSourceConfig, SourceConfigs};
-use crate::objects::{ Objects, kline::KlineV1, orderbook::OrderbookV1, orderbook_update::OrderbookUpdateV1, trade::TradeV1 };
+use crate::error::HolocoreError;
+use crate::objects::{ Objects,
+                      agg_trade::AggTradeV1,
+                      book_ticker::BookTickerV1,
+                      kline::{ KlineV1, KlineV2 },
+                      orderbook::OrderbookV2,
+                      orderbook_update::OrderbookUpdateV2,
+                      ticker::TickerV1,
+                      trade::{ TradeV1, TradeV2 } };
 use crate::utils::http::get_name_from_uri;
-use crate::utils::{ time, uuid };
+use crate::utils::{ clock::{ Clock, WallClock }, uuid::{ IdGenerator, RandomIdGenerator } };
 
+use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest;
 use rocket::{ self, http, Build, Rocket, State };
@@ -24,7 +40,9 @@ pub struct BinanceSpotSource {
     wakeup_interval: u64,
     base_url: String,
     symbols: Vec<String>,
-    socket: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>
+    socket: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>
 }
 
 impl BinanceSpotSource {
@@ -43,11 +61,21 @@ impl BinanceSpotSource {
             wakeup_interval: cfg.wakeup_interval,
             base_url: String::from(&cfg.base_url),
             symbols,
-            socket: None
+            socket: None,
+            clock: Arc::new(WallClock),
+            ids: Arc::new(RandomIdGenerator)
         }
     }
 
-    pub async fn init(&mut self) -> Result<(), String> {
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+#[async_trait]
+impl Source for BinanceSpotSource {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
         let mut complete_url = String::from(&self.base_url);
         for sym in &self.symbols {
             complete_url += sym;
@@ -56,7 +84,7 @@ impl BinanceSpotSource {
         log::info!("{}: connecting to {}", self.name, complete_url);
         let url: Url = match Url::parse(&complete_url) {
             Ok(url) => url,
-            Err(e) => return Err(format!("{}: cannot parse url {} - {}", self.name, complete_url, e))
+            Err(e) => return Err(HolocoreError::Config(format!("{}: cannot parse url {} - {}", self.name, complete_url, e)))
         };
 
         match connect_async(url).await {
@@ -69,12 +97,12 @@ impl BinanceSpotSource {
             Err(e) => {
                 let err_msg = format!("{} Error during handshake - {}", self.name, e);
                 log::error!("{}", &err_msg);
-                Err(err_msg)
+                Err(HolocoreError::Network(err_msg))
             }
         }
     }
 
-    pub async fn recv(&mut self) -> Result<(Option<Objects>, bool), String> {
+    async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
         if !self.active.load(Ordering::Acquire) {
             return Ok((None, true));
         }
@@ -86,7 +114,7 @@ impl BinanceSpotSource {
                         match data {
                             Some(data) => match data {
                                 Ok(data) => {
-                                    let wire_timestamp = time::now_nanos();
+                                    let wire_timestamp = self.clock.now_nanos();
                                     self.last_timestamp.store(wire_timestamp, Ordering::Release);
 
                                     match data.into_text() {
@@ -94,101 +122,157 @@ impl BinanceSpotSource {
                                             match serde_json::from_str::<serde_json::Value>(&msg) {
                                                 Ok(event) => {
                                                     self.sequence_number += 1;
-                                                    match event["data"]["e"].as_str() {
+                                                    // Book tickers are the only events without a type, their stream tells them apart
+                                                    let event_type = match event["data"]["e"].as_str() {
+                                                        Some(event_type) => Some(event_type),
+                                                        None => event["stream"].as_str().filter(|stream| stream.ends_with("@bookTicker")).map(|_| "bookTicker")
+                                                    };
+
+                                                    match event_type {
                                                         Some("depthUpdate") => {
                                                             match serde_json::from_value::<BinanceOrderbookUpdateEvent>(event["data"].to_owned()) {
                                                                 Ok(binance_event) => {
-                                                                    let obj = OrderbookUpdateV1::from_binance(uuid::uuid(),
+                                                                    let obj = OrderbookUpdateV2::from_binance(self.ids.next_id(),
                                                                                                               wire_timestamp,
                                                                                                               self.sequence_number,
-                                                                                                              uuid::uuid(),
+                                                                                                              self.ids.next_id(),
                                                                                                               &self.name,
                                                                                                               "Binance",
                                                                                                               "Spot",
                                                                                                               binance_event);
-                                                                    Ok((Some(Objects::OrderbookUpdateV1(obj)), false))
+                                                                    Ok((Some(Objects::OrderbookUpdateV2(obj)), false))
                                                                 },
-                                                                Err(e) => Err(format!("{}: cannot decode orderbook update - {}", self.name, e))
+                                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode orderbook update - {}", self.name, e)))
                                                             }
                                                         },
                                                         Some("kline") => {
                                                             match serde_json::from_value::<BinanceKlineEvent>(event["data"].to_owned()) {
                                                                 Ok(binance_event) => {
-                                                                    let obj = KlineV1::from_binance(uuid::uuid(),
+                                                                    let obj = KlineV1::from_binance(self.ids.next_id(),
                                                                                                     wire_timestamp,
                                                                                                     self.sequence_number,
-                                                                                                    uuid::uuid(),
+                                                                                                    self.ids.next_id(),
                                                                                                     &self.name,
                                                                                                     "Binance",
                                                                                                     "Spot",
                                                                                                     binance_event);
-                                                                    Ok((Some(Objects::KlineV1(obj)), false))
+                                                                    Ok((Some(Objects::KlineV2(KlineV2::from(obj))), false))
                                                                 },
-                                                                Err(e) => Err(format!("{}: cannot decode kline - {}", self.name, e))
+                                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode kline - {}", self.name, e)))
                                                             }
                                                         },
                                                         Some("trade") => {
                                                             match serde_json::from_value::<BinanceTradeEvent>(event["data"].to_owned()) {
                                                                 Ok(binance_event) => {
-                                                                    let obj = TradeV1::from_binance(uuid::uuid(),
+                                                                    let obj = TradeV1::from_binance(self.ids.next_id(),
                                                                                                     wire_timestamp,
                                                                                                     self.sequence_number,
-                                                                                                    uuid::uuid(),
+                                                                                                    self.ids.next_id(),
                                                                                                     &self.name,
                                                                                                     "Binance",
                                                                                                     "Spot",
                                                                                                     binance_event);
-                                                                    Ok((Some(Objects::TradeV1(obj)), false))
+                                                                    Ok((Some(Objects::TradeV2(TradeV2::from(obj))), false))
+                                                                },
+                                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode trade - {}", self.name, e)))
+                                                            }
+                                                        },
+                                                        Some("aggTrade") => {
+                                                            match serde_json::from_value::<BinanceAggTradeEvent>(event["data"].to_owned()) {
+                                                                Ok(binance_event) => {
+                                                                    let obj = AggTradeV1::from_binance(self.ids.next_id(),
+                                                                                                       wire_timestamp,
+                                                                                                       self.sequence_number,
+                                                                                                       self.ids.next_id(),
+                                                                                                       &self.name,
+                                                                                                       "Spot",
+                                                                                                       binance_event);
+                                                                    Ok((Some(Objects::AggTradeV1(obj)), false))
+                                                                },
+                                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode aggregate trade - {}", self.name, e)))
+                                                            }
+                                                        },
+                                                        Some("bookTicker") => {
+                                                            match serde_json::from_value::<BinanceBookTickerEvent>(event["data"].to_owned()) {
+                                                                Ok(binance_event) => {
+                                                                    let obj = BookTickerV1::from_binance(self.ids.next_id(),
+                                                                                                         wire_timestamp,
+                                                                                                         self.sequence_number,
+                                                                                                         self.ids.next_id(),
+                                                                                                         &self.name,
+                                                                                                         "Spot",
+                                                                                                         binance_event);
+                                                                    Ok((Some(Objects::BookTickerV1(obj)), false))
+                                                                },
+                                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode book ticker - {}", self.name, e)))
+                                                            }
+                                                        },
+                                                        Some("24hrTicker") | Some("24hrMiniTicker") => {
+                                                            match serde_json::from_value::<BinanceTickerEvent>(event["data"].to_owned()) {
+                                                                Ok(binance_event) => {
+                                                                    let obj = TickerV1::from_binance(self.ids.next_id(),
+                                                                                                     wire_timestamp,
+                                                                                                     self.sequence_number,
+                                                                                                     self.ids.next_id(),
+                                                                                                     &self.name,
+                                                                                                     "Spot",
+                                                                                                     binance_event);
+                                                                    Ok((Some(Objects::TickerV1(obj)), false))
                                                                 },
-                                                                Err(e) => Err(format!("{}: cannot decode trade - {}", self.name, e))
+                                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode ticker - {}", self.name, e)))
                                                             }
                                                         },
-                                                        _ => {
+                                                        event_type => {
+                                                            log::trace!("{}: skipping event of type {:?}", self.name, event_type);
                                                             Ok((None, false))
                                                         }
                                                     }
                                                 },
-                                                Err(e) => Err(format!("{}: cannot decode json - {}", self.name, e))
+                                                Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode json - {}", self.name, e)))
                                             }
                                         },
-                                        Err(e) => Err(format!("{}: cannot decode data as text - {}", self.name, e))
+                                        Err(e) => Err(HolocoreError::Malformed(format!("{}: cannot decode data as text - {}", self.name, e)))
                                     }
                                 },
-                                Err(e) => Err(format!("{}: websocket has returned an error - {}", self.name, e))
+                                Err(e) => Err(HolocoreError::Network(format!("{}: websocket has returned an error - {}", self.name, e)))
                             },
-                            None => Err(format!("{}: websocket has returned no data", self.name))
+                            None => Err(HolocoreError::Network(format!("{}: websocket has returned no data", self.name)))
                         }
                     },
                     Err(e) => {
                         self.active.store(false, Ordering::Release);
                         let err_msg = format!("{}: websocket has not received any data in the last {} ms - {}", self.name, self.wakeup_interval, e);
                         log::error!("{}", &err_msg);
-                        Err(err_msg)
+                        Err(HolocoreError::Network(err_msg))
                     }
                 }
             },
             None => {
                 let err_msg = format!("{}: Socket is uninitialized", self.name);
                 log::error!("{}", &err_msg);
-                Err(err_msg)
+                Err(HolocoreError::Internal(err_msg))
             }
         }
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue_size
-}
-
     // Setters
-    pub fn set_sleep_time(&mut self, _sleep_time: u64) {}
+    fn set_sleep_time(&mut self, _sleep_time: u64) {}
+
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    fn set_id_generator(&mut self, ids: Arc<dyn IdGenerator>) {
+        self.ids = ids;
+    }
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl.mount(format!("/{}/", self.name), rocket::routes!(orderbook))
     }
 }
@@ -199,7 +283,7 @@ async fn orderbook(symbol: &str, uri: &http::uri::Origin<'_>, state: &State<Cont
     let snapshot_url = match state.config().sources.iter().find(|src| src.name() == name) {
         Some(cfg) => {
             if let SourceConfigs::BinanceSpotSource(cfg) = cfg {
-                &cfg.snapshot_url
+                String::clone(&cfg.snapshot_url)
             } else {
                 panic!("BinanceSource has got a wrong config format");
             }
@@ -210,6 +294,15 @@ async fn orderbook(symbol: &str, uri: &http::uri::Origin<'_>, state: &State<Cont
         }
     };
 
+    // Shared with the source, so that the snapshot follows its seed like the rest of its objects
+    let ids = match state.ids(&name) {
+        Some(ids) => ids,
+        None => {
+            log::error!("{}: cannot find its ID generator", name);
+            return http::Status::InternalServerError;
+        }
+    };
+
     let complete_url = format!("{}{}&limit=1000", snapshot_url, symbol);
 
     let client = reqwest::Client::new();
@@ -220,10 +313,10 @@ async fn orderbook(symbol: &str, uri: &http::uri::Origin<'_>, state: &State<Cont
                     Ok(data) => {
                         match serde_json::from_str::<BinanceOrderBookEvent>(&data) {
                             Ok(binance_event) => {
-                                let orderbook = OrderbookV1::from_binance(uuid::uuid(),
-                                                                          time::now_nanos(),
+                                let orderbook = OrderbookV2::from_binance(ids.next_id(),
+                                                                          state.clock().now_nanos(),
                                                                           0,
-                                                                          uuid::uuid(),
+                                                                          ids.next_id(),
                                                                           &name,
                                                                           "Binance",
                                                                           "Spot",
@@ -232,7 +325,7 @@ async fn orderbook(symbol: &str, uri: &http::uri::Origin<'_>, state: &State<Cont
 
                                 match state.get(&name) {
                                     Some(tx) => {
-                                        match tx.send_async(Controls::Inject(Objects::OrderbookV1(orderbook))).await {
+                                        match tx.send_async(Controls::Inject(Objects::OrderbookV2(orderbook))).await {
                                             Ok(()) => http::Status::Ok,
                                             Err(e) => {
                                                 log::error!("Cannot send {} - {}", name, e);
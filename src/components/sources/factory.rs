@@ -1,15 +1,27 @@
-use crate::components::sources::{ Sources, file::FileSource, mock::MockSource };
+use crate::components::registry::{ Registry, SharedSource };
+use crate::components::sources::{ file::FileSource, mock::MockSource, shm::ShmSource, tcp::TcpSource };
 use crate::components::sources::binance::spot::BinanceSpotSource;
 use crate::config::sources::SourceConfigs;
+use crate::error::HolocoreError;
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-pub fn make(cfg: &SourceConfigs) -> Sources {
+pub fn register(registry: &mut Registry) {
+    registry.register_source("BinanceSpotSource", make);
+    registry.register_source("FileSource", make);
+    registry.register_source("MockSource", make);
+    registry.register_source("ShmSource", make);
+    registry.register_source("TcpSource", make);
+}
+
+pub fn make(cfg: &SourceConfigs) -> Result<SharedSource, HolocoreError> {
     match cfg {
-        SourceConfigs::BinanceSpotSource(cfg) => Sources::BinanceSpotSource(Arc::new(Mutex::new(BinanceSpotSource::new(cfg)))),
-        SourceConfigs::FileSource(cfg) => Sources::FileSource(Arc::new(Mutex::new(FileSource::new(cfg)))),
-        SourceConfigs::MockSource(cfg) => Sources::MockSource(Arc::new(Mutex::new(MockSource::new(cfg)))),
-        // _ => panic!("Cannot build source {}", cfg.name())
+        SourceConfigs::BinanceSpotSource(cfg) => Ok(Arc::new(Mutex::new(BinanceSpotSource::new(cfg)))),
+        SourceConfigs::FileSource(cfg) => Ok(Arc::new(Mutex::new(FileSource::new(cfg)))),
+        SourceConfigs::MockSource(cfg) => Ok(Arc::new(Mutex::new(MockSource::new(cfg)))),
+        SourceConfigs::ShmSource(cfg) => Ok(Arc::new(Mutex::new(ShmSource::new(cfg)))),
+        SourceConfigs::TcpSource(cfg) => Ok(Arc::new(Mutex::new(TcpSource::new(cfg)))),
+        SourceConfigs::Custom(cfg) => Err(HolocoreError::Config(format!("Cannot build source {} - malformed {} config", cfg.name, cfg.type_name)))
     }
 }
@@ -1,16 +1,30 @@
-use crate::config::sources::FileSourceConfig;
-use crate::objects::Objects;
+use crate::components::sources::Source;
+use crate::config::{ format::Format, sources::FileSourceConfig };
+use crate::error::HolocoreError;
+use crate::objects::{ Objects, schema };
+use crate::utils::binary::{ self, HEADER_SIZE, Header };
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket };
 use tokio::fs::File;
-use tokio::io::{ AsyncBufReadExt, BufReader };
+use tokio::io::{ AsyncBufRead, AsyncBufReadExt, BufReader };
+use tokio::time::{ Duration, Instant, sleep_until };
 
 pub struct FileSource {
     name: String,
     queue_size: usize,
     sequence_number: u64,
     filename: String,
-    reader: Option<BufReader<File>>
+    speed: Option<f64>,
+    format: Format,
+    upcast: bool,
+    // Timestamp of the first object and when it was emitted
+    start: Option<(i64, Instant)>,
+    reader: Option<BufReader<File>>,
+    // recv is cancelled whenever a control request arrives, so a line or record read in several
+    // calls is kept in buf and an object waiting for its replay time in pending
+    buf: Vec<u8>,
+    pending: Option<(Objects, Instant)>
 }
 
 impl FileSource {
@@ -20,62 +34,208 @@ impl FileSource {
             queue_size: cfg.queue_size,
             sequence_number: 0,
             filename: String::from(&cfg.filename),
-            reader: None
+            speed: cfg.speed,
+            format: cfg.format,
+            upcast: cfg.upcast,
+            start: None,
+            reader: None,
+            buf: vec![],
+            pending: None
         }
     }
 
-    pub async fn init(&mut self) -> Result<(), String> {
-        let fd = File::open(&self.filename).await.unwrap_or_else(|_| panic!("FileSource: cannot open file {}", &self.filename));
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+fn read_error(name: &str, filename: &str, e: std::io::Error) -> HolocoreError {
+    let err_msg = format!("{}: cannot read file {} - {}", name, filename, e);
+    log::error!("{}", &err_msg);
+    HolocoreError::Io(err_msg)
+}
+
+// Next object, None at the end of the file
+async fn read_line(name: &str, filename: &str, reader: &mut BufReader<File>, line: &mut Vec<u8>) -> Result<Option<Objects>, HolocoreError> {
+    match reader.read_until(b'\n', line).await {
+        Ok(0) if line.is_empty() => Ok(None),
+        Ok(_) => {
+            let obj = serde_json::from_slice::<Objects>(line);
+            line.clear();
+            match obj {
+                Ok(obj) => Ok(Some(obj)),
+                Err(e) => {
+                    log::error!("{}: Cannot deserialize object - {}", name, e);
+                    Err(HolocoreError::Malformed(e.to_string()))
+                }
+            }
+        },
+        Err(e) => Err(read_error(name, filename, e))
+    }
+}
+
+// Next object, None at the end of the file. A corrupt header or a record cut short also end it:
+// there is nothing to resync on, and reopening the file would replay what was already sent.
+// Bytes move from reader to record without awaiting, so a cancelled call resumes where it stopped.
+async fn read_record(name: &str, filename: &str, reader: &mut (impl AsyncBufRead + Unpin), record: &mut Vec<u8>) -> Result<Option<Objects>, HolocoreError> {
+    let header = loop {
+        let len = match record.first_chunk::<HEADER_SIZE>() {
+            Some(header) => match Header::parse(header) {
+                Ok(header) if record.len() == HEADER_SIZE + header.len => break header,
+                Ok(header) => HEADER_SIZE + header.len,
+                Err(e) => {
+                    log::error!("{}: cannot read further than a corrupt record in {} - {}", name, filename, e);
+                    record.clear();
+                    return Ok(None);
+                }
+            },
+            None => HEADER_SIZE
+        };
+
+        let available = reader.fill_buf().await.map_err(|e| read_error(name, filename, e))?;
+        if available.is_empty() {
+            if !record.is_empty() {
+                log::error!("{}: {} ends with a record cut short after {} bytes", name, filename, record.len());
+                record.clear();
+            }
+            return Ok(None);
+        }
+        let count = available.len().min(len - record.len());
+        record.extend_from_slice(&available[..count]);
+        reader.consume(count);
+    };
+
+    let obj = binary::decode(&header, &record[HEADER_SIZE..]);
+    record.clear();
+    match obj {
+        Ok(obj) => Ok(Some(obj)),
+        Err(e) => {
+            log::error!("{}: Cannot deserialize object - {}", name, e);
+            Err(e)
+        }
+    }
+}
+
+#[async_trait]
+impl Source for FileSource {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        let fd = File::open(&self.filename).await.map_err(|e| HolocoreError::Io(format!("{}: cannot open file {} - {}", self.name, self.filename, e)))?;
         self.reader = Some(BufReader::new(fd));
+        self.start = None;
+        self.buf.clear();
+        self.pending = None;
         Ok(())
     }
 
-    pub async fn recv(&mut self) -> Result<(Option<Objects>, bool), String> {
-        if let Some(ref mut reader) = self.reader {
-            let mut line = String::new();
-            match reader.read_line(&mut line).await {
-                Ok(done) => {
-                    if done == 0 {
-                        log::info!("{}: read {} messages", &self.name, self.sequence_number);
-                        return Ok((None, true));
-                    }
-
-                    match serde_json::from_str(&line) {
-                        Ok(obj) => {
-                            self.sequence_number += 1;
-                            return Ok((obj, false));
-                        },
-                        Err(e) => {
-                            log::error!("{}: Cannot deserialize object - {}", &self.name, e);
-                            return Err(e.to_string());
-                        }
-                    }
-                },
-                Err(e) => {
-                    let err_msg = format!("{}: cannot read file {} - {}", &self.name, &self.filename, e);
-                    log::error!("{}", &err_msg);
-                    return Err(err_msg);
+    async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
+        if self.pending.is_none() {
+            let reader = match self.reader.as_mut() {
+                Some(reader) => reader,
+                None => return Err(HolocoreError::Internal(format!("{}: reader is not set", self.name)))
+            };
+            let obj = match self.format {
+                Format::Json => read_line(&self.name, &self.filename, reader, &mut self.buf).await?,
+                Format::Binary => read_record(&self.name, &self.filename, reader, &mut self.buf).await?
+            };
+
+            let mut obj = match obj {
+                Some(obj) => obj,
+                None => {
+                    log::info!("{}: read {} messages", &self.name, self.sequence_number);
+                    return Ok((None, true));
                 }
+            };
+
+            self.sequence_number += 1;
+            if self.upcast {
+                obj = schema::upcast(obj);
             }
+            let speed = match self.speed {
+                Some(speed) => speed,
+                None => return Ok((Some(obj), false))
+            };
+            let (first, start) = *self.start.get_or_insert((obj.get_timestamp(), Instant::now()));
+            let offset = (obj.get_timestamp() - first).max(0) as f64 / speed;
+            self.pending = Some((obj, start + Duration::from_nanos(offset as u64)));
         }
 
-        Err(format!("{}: reader is not set", self.name))
+        if let Some((_, deadline)) = &self.pending {
+            sleep_until(*deadline).await;
+        }
+        Ok((self.pending.take().map(|(obj, _)| obj), false))
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue_size
-    }
-
     // Setters
-    pub fn set_sleep_time(&mut self, _sleep_time: u64) {}
+    fn set_sleep_time(&mut self, _sleep_time: u64) {}
 
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::{ Source, file::{ FileSource, read_record } };
+    use crate::config::sources::FileSourceConfig;
+    use crate::objects::{ Objects, mock::MockV1 };
+    use crate::utils::{ binary, uuid };
+
+    use std::time::Duration;
+    use tokio::io::{ AsyncWriteExt, BufReader };
+
+    #[tokio::test]
+    async fn test_cancelled_replay() {
+        // The second object is due 200ms after the first
+        let path = std::env::temp_dir().join(format!("holocore_replay_{}.json", std::process::id()));
+        let lines = [0, 200_000_000].iter().enumerate().map(|(idx, timestamp)| {
+            serde_json::to_string(&Objects::MockV1(MockV1::new(uuid::uuid(), *timestamp, idx as u64 + 1, uuid::uuid()))).unwrap()
+        }).collect::<Vec<_>>();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let mut source = FileSource::new(&FileSourceConfig { name: String::from("FileSource"), filename: path.to_string_lossy().into_owned(), speed: Some(1.0), ..Default::default() });
+        source.init().await.unwrap();
+        assert!(matches!(source.recv().await.unwrap(), (Some(Objects::MockV1(obj)), false) if obj.get_sequence_number() == 1));
+
+        // A control request cancels the wait, but not the object waited for
+        assert!(tokio::time::timeout(Duration::from_millis(20), source.recv()).await.is_err());
+        assert!(matches!(source.recv().await.unwrap(), (Some(Objects::MockV1(obj)), false) if obj.get_sequence_number() == 2));
+        assert!(matches!(source.recv().await.unwrap(), (None, true)));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_record() {
+        let record = binary::encode(&Objects::MockV1(MockV1::new(uuid::uuid(), 0, 1, uuid::uuid()))).unwrap();
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        let mut reader = BufReader::new(reader);
+        let mut buf = vec![];
+
+        // A read cancelled in the middle of a record resumes once the rest arrives
+        writer.write_all(&record[..record.len() / 2]).await.unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(20), read_record("FileSource", "duplex", &mut reader, &mut buf)).await.is_err());
+        writer.write_all(&record[record.len() / 2..]).await.unwrap();
+        let obj = read_record("FileSource", "duplex", &mut reader, &mut buf).await.unwrap();
+        assert!(matches!(obj, Some(Objects::MockV1(obj)) if obj.get_sequence_number() == 1));
+
+        // A corrupt header ends the file rather than failing, which would reopen it
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        let mut reader = BufReader::new(reader);
+        writer.write_all(&[0, 1, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]).await.unwrap();
+        writer.write_all(&record).await.unwrap();
+        assert!(matches!(read_record("FileSource", "duplex", &mut reader, &mut buf).await, Ok(None)));
+
+        // And so does a record cut short
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        let mut reader = BufReader::new(reader);
+        writer.write_all(&record[..record.len() - 1]).await.unwrap();
+        drop(writer);
+        assert!(matches!(read_record("FileSource", "duplex", &mut reader, &mut buf).await, Ok(None)));
+    }
+}
@@ -1,10 +1,14 @@
+use crate::components::sources::Source;
 use crate::components::control::{ControlState, Controls};
 use crate::config::sources::MockSourceConfig;
+use crate::error::HolocoreError;
 use crate::objects::{ Objects, mock::MockV1 };
-use crate::utils::{ http::get_name_from_uri, time, uuid };
+use crate::utils::{ clock::{ Clock, WallClock }, http::get_name_from_uri, time, uuid::{ self, IdGenerator, RandomIdGenerator } };
 
+use async_trait::async_trait;
 use rocket::{ self, Build, Rocket, http, State, serde::json::Json };
 use serde::Deserialize;
+use std::sync::Arc;
 use tokio::time::sleep;
 
 pub struct MockSource {
@@ -12,6 +16,8 @@ pub struct MockSource {
     queue_size: usize,
     sequence_number: u64,
     sleep_time: u64,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>
 }
 
 impl MockSource {
@@ -20,41 +26,55 @@ impl MockSource {
             name: String::from(&cfg.name),
             queue_size: cfg.queue_size,
             sequence_number: 0,
-            sleep_time: cfg.sleep_time
+            sleep_time: cfg.sleep_time,
+            clock: Arc::new(WallClock),
+            ids: Arc::new(RandomIdGenerator)
         }
     }
 
-    pub async fn init(&mut self) -> Result<(), String> {
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+#[async_trait]
+impl Source for MockSource {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
         Ok(())
     }
 
-    pub async fn recv(&mut self) -> Result<(Option<Objects>, bool), String> {
+    async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
         if self.sleep_time != 0 {
             sleep(std::time::Duration::from_millis(self.sleep_time)).await;
         }
 
         self.sequence_number += 1;
-        let obj = Objects::MockV1(MockV1::new(uuid::uuid(), time::now_nanos(), self.sequence_number, uuid::uuid()));
+        let obj = Objects::MockV1(MockV1::new(self.ids.next_id(), self.clock.now_nanos(), self.sequence_number, self.ids.next_id()));
         log::info!("{}: Received {} messages", self.name, self.sequence_number);
         Ok((Some(obj), false))
     }
 
     // Getters
-    pub fn get_name(&self) -> &str {
+    fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue_size
-    }
-
     // Setters
-    pub fn set_sleep_time(&mut self, sleep_time: u64) {
+    fn set_sleep_time(&mut self, sleep_time: u64) {
         self.sleep_time = sleep_time;
     }
 
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    fn set_id_generator(&mut self, ids: Arc<dyn IdGenerator>) {
+        self.ids = ids;
+    }
+
     // Endpoints
-    pub fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
         ctrl.mount(format!("/{}/", self.name), rocket::routes!(test))
             .mount(format!("/{}/", self.name), rocket::routes!(inject))
             .mount(format!("/{}/", self.name), rocket::routes!(sleep_time))
@@ -2,12 +2,41 @@ pub mod factory;
 pub mod binance;
 pub mod file;
 pub mod mock;
+pub mod shm;
+pub mod tcp;
 
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+use crate::utils::{ clock::Clock, uuid::IdGenerator };
+
+use async_trait::async_trait;
+use rocket::{ Build, Rocket };
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
-pub enum Sources {
-    BinanceSpotSource(Arc<Mutex<binance::spot::BinanceSpotSource>>),
-    FileSource(Arc<Mutex<file::FileSource>>),
-    MockSource(Arc<Mutex<mock::MockSource>>)
+#[async_trait]
+pub trait Source: Send {
+    async fn init(&mut self) -> Result<(), HolocoreError>;
+
+    // Returns the next object, if any, and whether the source has completed
+    async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError>;
+
+    // Called once the source has stopped, e.g. to close connections
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str;
+
+    // Setters
+    fn set_sleep_time(&mut self, _sleep_time: u64) {}
+
+    // Clock and ID generator of the objects the component creates, set before the first init
+    fn set_clock(&mut self, _clock: Arc<dyn Clock>) {}
+    fn set_id_generator(&mut self, _ids: Arc<dyn IdGenerator>) {}
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
 }
@@ -0,0 +1,137 @@
+use crate::components::sources::Source;
+use crate::config::sources::ShmSourceConfig;
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+use crate::utils::binary::{ self, HEADER_SIZE, Header };
+use crate::utils::shm::{ ShmRead, ShmReader };
+
+use async_trait::async_trait;
+use rocket::{ self, Build, Rocket };
+use std::time::{ Duration, Instant };
+use tokio::time::sleep;
+
+// Reads the objects published by a ShmSink of another process on the same host
+pub struct ShmSource {
+    name: String,
+    queue_size: usize,
+    path: String,
+    poll_interval: u64,
+    sequence_number: u64,
+    overruns: u64,
+    reader: Option<ShmReader>
+}
+
+impl ShmSource {
+    pub fn new(cfg: &ShmSourceConfig) -> Self {
+        Self {
+            name: String::from(&cfg.name),
+            queue_size: cfg.queue_size,
+            path: String::from(&cfg.path),
+            poll_interval: cfg.poll_interval,
+            sequence_number: 0,
+            overruns: 0,
+            reader: None
+        }
+    }
+
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+// Decodes a record published by a ShmSink
+fn decode(payload: &[u8]) -> Result<Objects, HolocoreError> {
+    let header = match payload.first_chunk::<HEADER_SIZE>() {
+        Some(header) => Header::parse(header)?,
+        None => return Err(HolocoreError::Malformed(format!("Record of {} bytes is shorter than its header", payload.len())))
+    };
+    if header.len != payload.len() - HEADER_SIZE {
+        return Err(HolocoreError::Malformed(format!("Record of {} bytes does not match its header of {} bytes", payload.len() - HEADER_SIZE, header.len)));
+    }
+
+    binary::decode(&header, &payload[HEADER_SIZE..])
+}
+
+#[async_trait]
+impl Source for ShmSource {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        match ShmReader::open(&self.path) {
+            Ok(reader) => {
+                log::info!("{}: reading from {}", self.name, self.path);
+                self.reader = Some(reader);
+                Ok(())
+            },
+            Err(e) => {
+                log::error!("{}: {}", self.name, e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Err(HolocoreError::Internal(format!("{}: reader is not set", self.name)))
+        };
+
+        loop {
+            match reader.read() {
+                Ok(ShmRead::Message(payload)) => {
+                    return match decode(&payload) {
+                        Ok(obj) => {
+                            self.sequence_number += 1;
+                            Ok((Some(obj), false))
+                        },
+                        Err(e) => {
+                            log::error!("{}: Cannot deserialize object - {}", self.name, e);
+                            Err(e)
+                        }
+                    };
+                },
+                Ok(ShmRead::Overrun(skipped)) => {
+                    self.overruns += skipped;
+                    log::warn!("{}: overrun by the writer of {}, skipped {} objects", self.name, self.path, skipped);
+                },
+                Ok(ShmRead::Restarted) => log::warn!("{}: the writer of {} restarted", self.name, self.path),
+                // The tokio timer has a resolution of 1ms, so shorter intervals yield until they
+                // elapse, at the cost of a busy worker. 0 keeps polling.
+                Ok(ShmRead::Empty) => {
+                    if self.poll_interval >= 1000 {
+                        sleep(Duration::from_micros(self.poll_interval)).await;
+                    } else {
+                        let deadline = Instant::now() + Duration::from_micros(self.poll_interval);
+                        loop {
+                            tokio::task::yield_now().await;
+                            if Instant::now() >= deadline {
+                                break;
+                            }
+                        }
+                    }
+                },
+                Err(e) => {
+                    log::error!("{}: {}", self.name, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        log::info!("{}: read {} objects from {}, skipped {}", self.name, self.sequence_number, self.path, self.overruns);
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // Setters
+    fn set_sleep_time(&mut self, _sleep_time: u64) {}
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
+}
@@ -0,0 +1,169 @@
+use crate::components::sources::Source;
+use crate::config::sources::TcpSourceConfig;
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+use crate::utils::frame;
+
+use async_trait::async_trait;
+use rocket::{ self, Build, Rocket };
+use std::time::Duration;
+use tokio::io::{ AsyncReadExt, AsyncWriteExt };
+use tokio::net::TcpStream;
+use tokio::time::{ Instant, sleep_until, timeout };
+use uuid::Uuid;
+
+// Time given to the sink to start the handshake, in milliseconds
+const HANDSHAKE_TIMEOUT: u64 = 5000;
+
+// Subscribes to a TcpSink. recv is cancelled whenever a control request arrives, so the
+// connection state lives here and a frame read in several calls is kept in buf.
+pub struct TcpSource {
+    name: String,
+    queue_size: usize,
+    address: String,
+    reconnect_backoff: u64,
+    max_reconnect_backoff: u64,
+    backoff: u64,
+    retry_at: Option<Instant>,
+    stream: Option<TcpStream>,
+    buf: Vec<u8>,
+    // Session of the sink and last sequence number received from it, to resume after a reconnection
+    session: Option<Uuid>,
+    sequence_number: u64
+}
+
+impl TcpSource {
+    pub fn new(cfg: &TcpSourceConfig) -> Self {
+        Self {
+            name: String::from(&cfg.name),
+            queue_size: cfg.queue_size,
+            address: String::from(&cfg.address),
+            reconnect_backoff: cfg.reconnect_backoff,
+            max_reconnect_backoff: cfg.max_reconnect_backoff,
+            backoff: cfg.reconnect_backoff,
+            retry_at: None,
+            stream: None,
+            buf: vec![],
+            session: None,
+            sequence_number: 0
+        }
+    }
+
+    async fn connect(&mut self) -> Result<TcpStream, HolocoreError> {
+        let mut stream = TcpStream::connect(&self.address).await.map_err(|e| HolocoreError::Network(e.to_string()))?;
+        stream.set_nodelay(true).map_err(|e| HolocoreError::Network(e.to_string()))?;
+
+        let mut session = [0u8; 16];
+        match timeout(Duration::from_millis(HANDSHAKE_TIMEOUT), stream.read_exact(&mut session)).await {
+            Ok(result) => result.map_err(|e| HolocoreError::Network(e.to_string()))?,
+            Err(_) => return Err(HolocoreError::Network(String::from("handshake timed out")))
+        };
+
+        let session = Uuid::from_bytes(session);
+        if self.session != Some(session) {
+            if self.session.is_some() {
+                log::warn!("{}: {} restarted, frames after {} are lost", self.name, self.address, self.sequence_number);
+            }
+            self.session = Some(session);
+            self.sequence_number = 0;
+        }
+
+        stream.write_u64(self.sequence_number).await.map_err(|e| HolocoreError::Network(e.to_string()))?;
+        log::info!("{}: connected to {}, resuming after {}", self.name, self.address, self.sequence_number);
+        Ok(stream)
+    }
+
+    fn disconnected(&mut self) {
+        self.stream = None;
+        self.buf.clear();
+        self.retry_at = Some(Instant::now() + Duration::from_millis(self.backoff));
+        self.backoff = (self.backoff * 2).min(self.max_reconnect_backoff);
+    }
+
+    // Getters
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size
+    }
+}
+
+#[async_trait]
+impl Source for TcpSource {
+    async fn init(&mut self) -> Result<(), HolocoreError> {
+        // The session is kept across restarts, so that the source resumes where it stopped
+        self.stream = None;
+        self.buf.clear();
+        self.retry_at = None;
+        self.backoff = self.reconnect_backoff;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
+        loop {
+            match frame::decode(&mut self.buf) {
+                Ok(Some((sequence, obj))) => {
+                    self.sequence_number = sequence;
+                    return Ok((Some(obj), false));
+                },
+                Ok(None) => {},
+                Err(e) => {
+                    log::error!("{}: {}", self.name, e);
+                    return Err(e);
+                }
+            }
+
+            match self.stream.as_mut() {
+                Some(stream) => {
+                    match stream.read_buf(&mut self.buf).await {
+                        Ok(0) => {
+                            log::warn!("{}: {} closed the connection", self.name, self.address);
+                            self.disconnected();
+                        },
+                        Ok(_) => {},
+                        Err(e) => {
+                            log::warn!("{}: connection to {} lost - {}", self.name, self.address, e);
+                            self.disconnected();
+                        }
+                    }
+                },
+                None => {
+                    if let Some(retry_at) = self.retry_at {
+                        sleep_until(retry_at).await;
+                    }
+
+                    match self.connect().await {
+                        Ok(stream) => {
+                            self.stream = Some(stream);
+                            self.retry_at = None;
+                            self.backoff = self.reconnect_backoff;
+                        },
+                        Err(e) => {
+                            log::warn!("{}: cannot connect to {} - {}", self.name, self.address, e);
+                            self.disconnected();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+        log::info!("{}: received up to {} from {}", self.name, self.sequence_number, self.address);
+        Ok(())
+    }
+
+    // Getters
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // Setters
+    fn set_sleep_time(&mut self, _sleep_time: u64) {}
+
+    // Endpoints
+    fn endpoints(&self, ctrl: Rocket<Build>) -> Rocket<Build> {
+        ctrl
+    }
+}
@@ -0,0 +1,130 @@
+use crate::objects::Objects;
+
+use serde::{ Deserialize, Serialize, de::IgnoredAny };
+use std::collections::BTreeMap;
+
+// What an edge does when its queue is full
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackPressure {
+    // Waits for the downstream to make room
+    #[default]
+    Block,
+    // Discards the object being sent
+    DropNewest,
+    // Discards the oldest queued object
+    DropOldest,
+    // Keeps only the latest queued object per type and symbol, objects without a symbol are all kept
+    ConflatePerSymbol
+}
+
+// Objects let through an edge, an empty list allows everything.
+// Symbols are compared ignoring case.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EdgeFilter {
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    #[serde(default)]
+    pub exclude_symbols: Vec<String>,
+    #[serde(default)]
+    pub sources: Vec<String>
+}
+
+impl EdgeFilter {
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.symbols.is_empty() && self.exclude_symbols.is_empty() && self.sources.is_empty()
+    }
+
+    pub fn accepts(&self, obj: &Objects) -> bool {
+        if !self.types.is_empty() && !self.types.iter().any(|name| name == obj.get_type_name()) {
+            return false;
+        }
+
+        let symbol = obj.get_symbol();
+        let matches = |symbols: &Vec<String>| symbol.map(|symbol| symbols.iter().any(|s| s.eq_ignore_ascii_case(symbol))).unwrap_or(false);
+        if !self.symbols.is_empty() && !matches(&self.symbols) {
+            return false;
+        }
+
+        if matches(&self.exclude_symbols) {
+            return false;
+        }
+
+        if !self.sources.is_empty() && !obj.get_source().map(|source| self.sources.iter().any(|s| s == source)).unwrap_or(false) {
+            return false;
+        }
+
+        true
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EdgeConfig {
+    pub name: String,
+    // Output port of the upstream PU feeding this edge, the default one if not set
+    #[serde(default)]
+    pub port: Option<String>,
+    #[serde(default)]
+    pub policy: BackPressure,
+    #[serde(flatten)]
+    pub filter: EdgeFilter,
+    // Keys left over by the fields above, e.g. a misspelled filter, which validation rejects.
+    // deny_unknown_fields does not work along with flatten.
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>
+}
+
+// A downstream is either a plain component name or a table with the edge settings
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Downstream {
+    Name(String),
+    Edge(EdgeConfig)
+}
+
+impl Downstream {
+    pub fn name(&self) -> &str {
+        match self {
+            Downstream::Name(name) => name,
+            Downstream::Edge(cfg) => &cfg.name
+        }
+    }
+
+    pub fn port(&self) -> Option<&str> {
+        match self {
+            Downstream::Name(_) => None,
+            Downstream::Edge(cfg) => cfg.port.as_deref()
+        }
+    }
+
+    pub fn filter(&self) -> Option<&EdgeFilter> {
+        match self {
+            Downstream::Name(_) => None,
+            Downstream::Edge(cfg) => Some(&cfg.filter)
+        }
+    }
+
+    pub fn unknown_keys(&self) -> Vec<&str> {
+        match self {
+            Downstream::Name(_) => vec![],
+            Downstream::Edge(cfg) => cfg.unknown.keys().map(String::as_str).collect()
+        }
+    }
+
+    pub fn policy(&self) -> BackPressure {
+        match self {
+            Downstream::Name(_) => BackPressure::default(),
+            Downstream::Edge(cfg) => cfg.policy
+        }
+    }
+
+    // Same edge towards another component
+    pub fn with_name(&self, name: &str) -> Downstream {
+        match self {
+            Downstream::Name(_) => Downstream::Name(String::from(name)),
+            Downstream::Edge(cfg) => Downstream::Edge(EdgeConfig { name: String::from(name), ..EdgeConfig::clone(cfg) })
+        }
+    }
+}
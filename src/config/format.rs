@@ -0,0 +1,25 @@
+use serde::{ Deserialize, Serialize };
+use std::str::FromStr;
+
+// Encoding of the objects a file component reads or writes
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    // One JSON object per line
+    #[default]
+    Json,
+    // Records of utils::binary, smaller and faster to parse
+    Binary
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "json" => Ok(Self::Json),
+            "binary" => Ok(Self::Binary),
+            _ => Err(format!("Unknown format {}, expected json or binary", text))
+        }
+    }
+}
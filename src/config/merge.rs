@@ -0,0 +1,17 @@
+use serde::{ Deserialize, Serialize };
+
+// Order in which a PU or a sink takes the objects of its inputs
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "kebab-case")]
+pub enum MergePolicy {
+    // As they arrive, whatever input they come from
+    #[default]
+    Arrival,
+    // By timestamp, each input being in timestamp order. An object is released once every input
+    // has one pending, or once it has waited lateness milliseconds. Backtests leave lateness unset,
+    // so that the order does not depend on the scheduling.
+    Timestamp {
+        #[serde(default)]
+        lateness: Option<u64>
+    }
+}
@@ -1,6 +1,18 @@
 pub mod sources;
 pub mod pus;
 pub mod sinks;
+pub mod edge;
+pub mod format;
+pub mod merge;
+pub mod overrides;
+pub mod placement;
+pub mod restart;
+pub mod secret;
+pub mod timer;
+pub mod validation;
+
+use crate::config::edge::Downstream;
+use crate::utils::clock::ClockKind;
 
 use serde::{Deserialize, Serialize};
 
@@ -13,9 +25,81 @@ pub struct Config {
     pub stack_size: usize,
     pub ctrl_host: String,
     pub ctrl_port: u16,
+    // Milliseconds given to the components to drain on shutdown
+    pub shutdown_timeout: Option<u64>,
+    // Replays follow the timestamps of the objects instead of the wall clock
+    #[serde(default)]
+    pub clock: ClockKind,
+    // Seeds the ID generators of the components, which are random otherwise
+    pub id_seed: Option<u64>,
     pub discovery_service: Option<String>,
     pub gateway: Option<String>,
     pub sources: Vec<sources::SourceConfigs>,
     pub pus: Option<Vec<pus::PUConfigs>>,
     pub sinks: Vec<sinks::SinkConfigs>,
 }
+
+impl Config {
+    // Queue size of the edges leaving the source or PU called name
+    pub fn queue_size(&self, name: &str) -> Option<usize> {
+        match self.sources.iter().find(|cfg| cfg.name() == name) {
+            Some(cfg) => Some(cfg.queue_size()),
+            None => self.pus.iter().flatten().find(|cfg| cfg.name() == name).map(|cfg| cfg.queue_size())
+        }
+    }
+
+    pub fn downstreams_mut(&mut self, name: &str) -> Option<&mut Vec<Downstream>> {
+        match self.sources.iter_mut().find(|cfg| cfg.name() == name) {
+            Some(cfg) => Some(cfg.downstreams_mut()),
+            None => self.pus.iter_mut().flatten().find(|cfg| cfg.name() == name).map(|cfg| cfg.downstreams_mut())
+        }
+    }
+
+    // Removes the component called name and every edge towards it. Returns false if there is no such component.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.sources.len() + self.pus.iter().flatten().count() + self.sinks.len();
+        self.sources.retain(|cfg| cfg.name() != name);
+        if let Some(pus) = &mut self.pus {
+            pus.retain(|cfg| cfg.name() != name);
+        }
+        self.sinks.retain(|cfg| cfg.name() != name);
+
+        for cfg in &mut self.sources {
+            cfg.downstreams_mut().retain(|downstream| downstream.name() != name);
+        }
+        for cfg in self.pus.iter_mut().flatten() {
+            cfg.downstreams_mut().retain(|downstream| downstream.name() != name);
+        }
+
+        count != self.sources.len() + self.pus.iter().flatten().count() + self.sinks.len()
+    }
+}
+
+// Reads a TOML config file as written, without any override
+pub fn load(path: &str) -> Result<Config, String> {
+    read(path)?.try_into::<Config>().map_err(|e| format!("Cannot parse config file {} - {}", path, e))
+}
+
+fn read(path: &str) -> Result<toml::Value, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => text.parse::<toml::Value>().map_err(|e| format!("Cannot parse config file {} - {}", path, e)),
+        Err(e) => Err(format!("Cannot read config file {} - {}", path, e))
+    }
+}
+
+// Reads a TOML config file, then sets the HOLOCORE_* env vars and the key=value overrides given
+// on the command line, in this order
+pub fn load_with_overrides(path: &str, sets: &[String]) -> Result<Config, String> {
+    let mut root = read(path)?;
+
+    for (key, raw) in overrides::from_env(std::env::vars()) {
+        overrides::apply(&mut root, &key, &raw)?;
+    }
+
+    for set in sets {
+        let (key, raw) = overrides::parse(set)?;
+        overrides::apply(&mut root, key, raw)?;
+    }
+
+    root.try_into::<Config>().map_err(|e| format!("Cannot parse config file {} - {}", path, e))
+}
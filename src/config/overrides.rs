@@ -0,0 +1,117 @@
+use toml::Value;
+
+// Prefix of the env vars overriding the config
+const ENV_PREFIX: &str = "HOLOCORE_";
+
+// Sets the value at key, a dotted path such as ctrl_port or sources.FileSource.filename.
+// Components are picked by name, ignoring the case, or by index. raw is parsed as a TOML value,
+// and taken as a string otherwise.
+pub fn apply(root: &mut Value, key: &str, raw: &str) -> Result<(), String> {
+    let value = match format!("value = {}", raw).parse::<Value>() {
+        Ok(Value::Table(mut table)) => table.remove("value").unwrap_or_else(|| Value::String(String::from(raw))),
+        _ => Value::String(String::from(raw))
+    };
+
+    let segments = key.split('.').collect::<Vec<_>>();
+    let (last, parents) = match segments.split_last() {
+        Some((last, parents)) if !last.is_empty() => (*last, parents),
+        _ => return Err(format!("Invalid override key {}", key))
+    };
+
+    let mut node = root;
+    for segment in parents {
+        node = match node {
+            Value::Table(table) => table.entry(String::from(*segment)).or_insert_with(|| Value::Table(Default::default())),
+            Value::Array(items) => {
+                let idx = match segment.parse::<usize>() {
+                    Ok(idx) => Some(idx),
+                    Err(_) => items.iter().position(|item| item.get("name").and_then(Value::as_str).is_some_and(|name| name.eq_ignore_ascii_case(segment)))
+                };
+                match idx.and_then(|idx| items.get_mut(idx)) {
+                    Some(item) => item,
+                    None => return Err(format!("Cannot find {} in {}", segment, key))
+                }
+            },
+            _ => return Err(format!("Cannot set {} - {} is not a table", key, segment))
+        };
+    }
+
+    match node {
+        Value::Table(table) => {
+            table.insert(String::from(last), value);
+            Ok(())
+        },
+        _ => Err(format!("Cannot set {} - its parent is not a table", key))
+    }
+}
+
+// Keys of the HOLOCORE_* env vars, sorted so that they apply in the same order every time.
+// Path segments are separated by a double underscore, e.g. HOLOCORE_SINKS__BINANCESPOTSINK__API_KEY.
+pub fn from_env(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let mut overrides = vars.filter_map(|(name, raw)| {
+                                name.strip_prefix(ENV_PREFIX).map(|key| (key.split("__").map(str::to_lowercase).collect::<Vec<_>>().join("."), raw))
+                            })
+                            .collect::<Vec<_>>();
+    overrides.sort();
+    overrides
+}
+
+// Parses a key=value override
+pub fn parse(text: &str) -> Result<(&str, &str), String> {
+    match text.split_once('=') {
+        Some((key, raw)) if !key.trim().is_empty() => Ok((key.trim(), raw.trim())),
+        _ => Err(format!("Invalid override {}, expected key=value", text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::overrides::{ apply, from_env };
+
+    use toml::Value;
+
+    #[test]
+    fn test_overrides() {
+        let mut root = r#"
+            ctrl_port = 8000
+
+            [[sources]]
+            type = "FileSource"
+            name = "Replay"
+            filename = "trades.json"
+        "#.parse::<Value>().unwrap();
+
+        apply(&mut root, "ctrl_port", "9000").unwrap();
+        apply(&mut root, "sources.Replay.filename", "books.json").unwrap();
+        apply(&mut root, "sources.0.speed", "2.5").unwrap();
+        apply(&mut root, "gateway", "MainGateway").unwrap();
+
+        assert_eq!(root["ctrl_port"].as_integer(), Some(9000));
+        assert_eq!(root["sources"][0]["filename"].as_str(), Some("books.json"));
+        assert_eq!(root["sources"][0]["speed"].as_float(), Some(2.5));
+        assert_eq!(root["gateway"].as_str(), Some("MainGateway"));
+        assert!(apply(&mut root, "sources.Live.filename", "trades.json").is_err());
+        assert!(apply(&mut root, "ctrl_port.host", "localhost").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = vec![
+            (String::from("HOLOCORE_SOURCES__REPLAY__SPEED"), String::from("4")),
+            (String::from("HOLOCORE_LOG_LEVEL"), String::from("debug")),
+            (String::from("RUST_LOG"), String::from("info"))
+        ];
+        let overrides = from_env(vars.into_iter());
+        assert_eq!(overrides, vec![
+            (String::from("log_level"), String::from("debug")),
+            (String::from("sources.replay.speed"), String::from("4"))
+        ]);
+
+        let mut root = "[[sources]]\nname = \"Replay\"".parse::<Value>().unwrap();
+        for (key, raw) in overrides {
+            apply(&mut root, &key, &raw).unwrap();
+        }
+        assert_eq!(root["log_level"].as_str(), Some("debug"));
+        assert_eq!(root["sources"][0]["speed"].as_integer(), Some(4));
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{ Deserialize, Serialize };
+
+// Where the task of a component runs, e.g. { runtime = "dedicated", core = 2 }
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "runtime", rename_all = "kebab-case")]
+pub enum Placement {
+    // On the multi-thread runtime of the processor, with the other components and the control API
+    #[default]
+    Shared,
+    // On a current-thread runtime of its own OS thread, pinned to core if set
+    Dedicated {
+        #[serde(default)]
+        core: Option<usize>
+    }
+}
@@ -1,4 +1,12 @@
-use serde::{Deserialize, Serialize};
+use crate::config::edge::Downstream;
+use crate::config::merge::MergePolicy;
+use crate::config::placement::Placement;
+use crate::config::restart::RestartPolicy;
+use crate::config::timer::TimerConfig;
+use crate::error::HolocoreError;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{ Map, Value };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -7,17 +15,31 @@ pub enum PUConfigs {
     MockPU(MockPUConfig),
     OrderManagerPU(OrderManagerPUConfig),
     OrderbookManagerPU(OrderbookManagerPUConfig),
-    StrategyPU(StrategyPUConfig)
+    StrategyPU(StrategyPUConfig),
+    #[serde(untagged)]
+    Custom(CustomPUConfig)
 }
 
 impl PUConfigs {
+    pub fn type_name(&self) -> &str {
+        match self {
+            PUConfigs::EnginePU(_) => "EnginePU",
+            PUConfigs::MockPU(_) => "MockPU",
+            PUConfigs::OrderManagerPU(_) => "OrderManagerPU",
+            PUConfigs::OrderbookManagerPU(_) => "OrderbookManagerPU",
+            PUConfigs::StrategyPU(_) => "StrategyPU",
+            PUConfigs::Custom(cfg) => &cfg.type_name
+        }
+    }
+
     pub fn name(&self) -> &str {
         match self {
             PUConfigs::EnginePU(cfg) => &cfg.name,
             PUConfigs::MockPU(cfg) => &cfg.name,
             PUConfigs::OrderManagerPU(cfg) => &cfg.name,
             PUConfigs::OrderbookManagerPU(cfg) => &cfg.name,
-            PUConfigs::StrategyPU(cfg) => &cfg.name
+            PUConfigs::StrategyPU(cfg) => &cfg.name,
+            PUConfigs::Custom(cfg) => &cfg.name
         }
     }
 
@@ -27,17 +49,74 @@ impl PUConfigs {
             PUConfigs::MockPU(cfg) => cfg.queue_size,
             PUConfigs::OrderManagerPU(cfg) => cfg.queue_size,
             PUConfigs::OrderbookManagerPU(cfg) => cfg.queue_size,
-            PUConfigs::StrategyPU(cfg) => cfg.queue_size
+            PUConfigs::StrategyPU(cfg) => cfg.queue_size,
+            PUConfigs::Custom(cfg) => cfg.queue_size
         }
     }
 
-    pub fn downstreams(&self) -> &Vec<String> {
+    pub fn downstreams(&self) -> &Vec<Downstream> {
         match self {
             PUConfigs::EnginePU(cfg) => &cfg.downstreams,
             PUConfigs::MockPU(cfg) => &cfg.downstreams,
             PUConfigs::OrderManagerPU(cfg) => &cfg.downstreams,
             PUConfigs::OrderbookManagerPU(cfg) => &cfg.downstreams,
-            PUConfigs::StrategyPU(cfg) => &cfg.downstreams
+            PUConfigs::StrategyPU(cfg) => &cfg.downstreams,
+            PUConfigs::Custom(cfg) => &cfg.downstreams
+        }
+    }
+
+    pub fn downstreams_mut(&mut self) -> &mut Vec<Downstream> {
+        match self {
+            PUConfigs::EnginePU(cfg) => &mut cfg.downstreams,
+            PUConfigs::MockPU(cfg) => &mut cfg.downstreams,
+            PUConfigs::OrderManagerPU(cfg) => &mut cfg.downstreams,
+            PUConfigs::OrderbookManagerPU(cfg) => &mut cfg.downstreams,
+            PUConfigs::StrategyPU(cfg) => &mut cfg.downstreams,
+            PUConfigs::Custom(cfg) => &mut cfg.downstreams
+        }
+    }
+
+    pub fn restart(&self) -> &RestartPolicy {
+        match self {
+            PUConfigs::EnginePU(cfg) => &cfg.restart,
+            PUConfigs::MockPU(cfg) => &cfg.restart,
+            PUConfigs::OrderManagerPU(cfg) => &cfg.restart,
+            PUConfigs::OrderbookManagerPU(cfg) => &cfg.restart,
+            PUConfigs::StrategyPU(cfg) => &cfg.restart,
+            PUConfigs::Custom(cfg) => &cfg.restart
+        }
+    }
+
+    pub fn placement(&self) -> &Placement {
+        match self {
+            PUConfigs::EnginePU(cfg) => &cfg.placement,
+            PUConfigs::MockPU(cfg) => &cfg.placement,
+            PUConfigs::OrderManagerPU(cfg) => &cfg.placement,
+            PUConfigs::OrderbookManagerPU(cfg) => &cfg.placement,
+            PUConfigs::StrategyPU(cfg) => &cfg.placement,
+            PUConfigs::Custom(cfg) => &cfg.placement
+        }
+    }
+
+    pub fn merge(&self) -> &MergePolicy {
+        match self {
+            PUConfigs::EnginePU(cfg) => &cfg.merge,
+            PUConfigs::MockPU(cfg) => &cfg.merge,
+            PUConfigs::OrderManagerPU(cfg) => &cfg.merge,
+            PUConfigs::OrderbookManagerPU(cfg) => &cfg.merge,
+            PUConfigs::StrategyPU(cfg) => &cfg.merge,
+            PUConfigs::Custom(cfg) => &cfg.merge
+        }
+    }
+
+    pub fn timers(&self) -> &Vec<TimerConfig> {
+        match self {
+            PUConfigs::EnginePU(cfg) => &cfg.timers,
+            PUConfigs::MockPU(cfg) => &cfg.timers,
+            PUConfigs::OrderManagerPU(cfg) => &cfg.timers,
+            PUConfigs::OrderbookManagerPU(cfg) => &cfg.timers,
+            PUConfigs::StrategyPU(cfg) => &cfg.timers,
+            PUConfigs::Custom(cfg) => &cfg.timers
         }
     }
 }
@@ -45,38 +124,114 @@ impl PUConfigs {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct EnginePUConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
     pub queue_size: usize,
-    pub downstreams: Vec<String>
+    pub downstreams: Vec<Downstream>
 }
 
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MockPUConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
     pub queue_size: usize,
-    pub downstreams: Vec<String>
+    pub downstreams: Vec<Downstream>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct OrderManagerPUConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
     pub queue_size: usize,
-    pub downstreams: Vec<String>
+    pub downstreams: Vec<Downstream>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct OrderbookManagerPUConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
     pub queue_size: usize,
-    pub downstreams: Vec<String>,
-    pub realtime: bool
+    pub downstreams: Vec<Downstream>,
+    pub realtime: bool,
+    // Shares the books under this name, they are private otherwise
+    #[serde(default)]
+    pub market: Option<String>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct StrategyPUConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
     pub queue_size: usize,
-    pub downstreams: Vec<String>,
+    pub downstreams: Vec<Downstream>,
     pub realtime: bool,
-    pub algorithm: String
+    pub algorithm: String,
+    // Reads the books of the shared market with this name instead of building its own
+    #[serde(default)]
+    pub market: Option<String>
+}
+
+// Config of a PU type registered outside holocore
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CustomPUConfig {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
+    pub queue_size: usize,
+    pub downstreams: Vec<Downstream>,
+    #[serde(flatten)]
+    pub params: Map<String, Value>
+}
+
+impl CustomPUConfig {
+    // Deserializes the whole entry into the component's own config struct
+    pub fn params<T: DeserializeOwned>(&self) -> Result<T, HolocoreError> {
+        match serde_json::to_value(self) {
+            Ok(value) => serde_json::from_value(value).map_err(|e| HolocoreError::Config(format!("{}: invalid {} config - {}", self.name, self.type_name, e))),
+            Err(e) => Err(HolocoreError::Config(format!("{}: cannot serialize config - {}", self.name, e)))
+        }
+    }
 }
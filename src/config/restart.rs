@@ -0,0 +1,33 @@
+use serde::{ Deserialize, Serialize };
+
+fn default_backoff() -> u64 {
+    100
+}
+
+fn default_max_backoff() -> u64 {
+    30000
+}
+
+// What the supervisor does once a component stops, backoffs are in milliseconds
+// and double after every consecutive restart, up to max_backoff
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    // Restarts after a failure or a completion
+    Always {
+        #[serde(default = "default_backoff")]
+        backoff: u64,
+        #[serde(default = "default_max_backoff")]
+        max_backoff: u64
+    },
+    // Restarts after a failure, giving up after max_retries consecutive ones
+    OnFailure {
+        max_retries: u32,
+        #[serde(default = "default_backoff")]
+        backoff: u64,
+        #[serde(default = "default_max_backoff")]
+        max_backoff: u64
+    }
+}
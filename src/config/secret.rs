@@ -0,0 +1,78 @@
+use serde::{ Deserialize, Deserializer, Serialize, Serializer, de::Error };
+use std::fmt;
+
+// Credential set inline, or read from a file with { file = "/run/secrets/api_key" }.
+// It is redacted wherever the config is printed or served.
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct Secret(String);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecretSource {
+    Value(String),
+    File { file: String }
+}
+
+impl Secret {
+    pub fn new(value: &str) -> Self {
+        Self(String::from(value))
+    }
+
+    // Getters
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secret(***)")
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("***")
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match SecretSource::deserialize(deserializer)? {
+            SecretSource::Value(value) => Ok(Self(value)),
+            // Files usually end with a newline that is not part of the secret
+            SecretSource::File { file } => match std::fs::read_to_string(&file) {
+                Ok(value) => Ok(Self(String::from(value.trim_end()))),
+                Err(e) => Err(D::Error::custom(format!("cannot read secret file {} - {}", file, e)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::secret::Secret;
+
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Keys {
+        inline: Secret,
+        from_file: Secret
+    }
+
+    #[test]
+    fn test_secret() {
+        let path = std::env::temp_dir().join(format!("holocore_secret_{}", std::process::id()));
+        std::fs::write(&path, "s3cr3t\n").unwrap();
+
+        let keys = toml::from_str::<Keys>(&format!("inline = \"k3y\"\nfrom_file = {{ file = \"{}\" }}", path.display())).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(keys.inline.expose(), "k3y");
+        assert_eq!(keys.from_file.expose(), "s3cr3t");
+        assert_eq!(serde_json::to_string(&keys.from_file).unwrap(), "\"***\"");
+        assert_eq!(format!("{:?}", keys.inline), "Secret(***)");
+        assert!(toml::from_str::<Keys>("inline = \"k3y\"\nfrom_file = { file = \"/nonexistent/secret\" }").is_err());
+    }
+}
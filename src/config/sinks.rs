@@ -1,19 +1,92 @@
-use serde::{Deserialize, Serialize};
+use crate::config::format::Format;
+use crate::config::merge::MergePolicy;
+use crate::config::placement::Placement;
+use crate::config::restart::RestartPolicy;
+use crate::config::secret::Secret;
+use crate::config::timer::TimerConfig;
+use crate::error::HolocoreError;
+use crate::objects::numeric::Precision;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{ Map, Value };
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum SinkConfigs {
     BinanceSpotSink(BinanceSpotSinkConfig),
     FileSink(FileSinkConfig),
-    MockSink(MockSinkConfig)
+    MockSink(MockSinkConfig),
+    ShmSink(ShmSinkConfig),
+    TcpSink(TcpSinkConfig),
+    #[serde(untagged)]
+    Custom(CustomSinkConfig)
 }
 
 impl SinkConfigs {
+    pub fn type_name(&self) -> &str {
+        match self {
+            SinkConfigs::BinanceSpotSink(_) => "BinanceSpotSink",
+            SinkConfigs::FileSink(_) => "FileSink",
+            SinkConfigs::MockSink(_) => "MockSink",
+            SinkConfigs::ShmSink(_) => "ShmSink",
+            SinkConfigs::TcpSink(_) => "TcpSink",
+            SinkConfigs::Custom(cfg) => &cfg.type_name
+        }
+    }
+
     pub fn name(&self) -> &str {
         match self {
             SinkConfigs::BinanceSpotSink(cfg) => &cfg.name,
             SinkConfigs::FileSink(cfg) => &cfg.name,
-            SinkConfigs::MockSink(cfg) => &cfg.name
+            SinkConfigs::MockSink(cfg) => &cfg.name,
+            SinkConfigs::ShmSink(cfg) => &cfg.name,
+            SinkConfigs::TcpSink(cfg) => &cfg.name,
+            SinkConfigs::Custom(cfg) => &cfg.name
+        }
+    }
+
+    pub fn restart(&self) -> &RestartPolicy {
+        match self {
+            SinkConfigs::BinanceSpotSink(cfg) => &cfg.restart,
+            SinkConfigs::FileSink(cfg) => &cfg.restart,
+            SinkConfigs::MockSink(cfg) => &cfg.restart,
+            SinkConfigs::ShmSink(cfg) => &cfg.restart,
+            SinkConfigs::TcpSink(cfg) => &cfg.restart,
+            SinkConfigs::Custom(cfg) => &cfg.restart
+        }
+    }
+
+    pub fn placement(&self) -> &Placement {
+        match self {
+            SinkConfigs::BinanceSpotSink(cfg) => &cfg.placement,
+            SinkConfigs::FileSink(cfg) => &cfg.placement,
+            SinkConfigs::MockSink(cfg) => &cfg.placement,
+            SinkConfigs::ShmSink(cfg) => &cfg.placement,
+            SinkConfigs::TcpSink(cfg) => &cfg.placement,
+            SinkConfigs::Custom(cfg) => &cfg.placement
+        }
+    }
+
+    pub fn merge(&self) -> &MergePolicy {
+        match self {
+            SinkConfigs::BinanceSpotSink(cfg) => &cfg.merge,
+            SinkConfigs::FileSink(cfg) => &cfg.merge,
+            SinkConfigs::MockSink(cfg) => &cfg.merge,
+            SinkConfigs::ShmSink(cfg) => &cfg.merge,
+            SinkConfigs::TcpSink(cfg) => &cfg.merge,
+            SinkConfigs::Custom(cfg) => &cfg.merge
+        }
+    }
+
+    pub fn timers(&self) -> &Vec<TimerConfig> {
+        match self {
+            SinkConfigs::BinanceSpotSink(cfg) => &cfg.timers,
+            SinkConfigs::FileSink(cfg) => &cfg.timers,
+            SinkConfigs::MockSink(cfg) => &cfg.timers,
+            SinkConfigs::ShmSink(cfg) => &cfg.timers,
+            SinkConfigs::TcpSink(cfg) => &cfg.timers,
+            SinkConfigs::Custom(cfg) => &cfg.timers
         }
     }
 }
@@ -21,20 +94,128 @@ impl SinkConfigs {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct BinanceSpotSinkConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
     pub base_url: String,
-    pub api_key: String,
-    pub secret_key: String,
+    pub api_key: Secret,
+    pub secret_key: Secret,
     pub user_agent: String,
-    pub content_type: String
+    pub content_type: String,
+    // Decimals of the orders sent per symbol, orders of other symbols are sent as they are
+    #[serde(default)]
+    pub precisions: HashMap<String, Precision>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MockSinkConfig {
-    pub name: String
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct FileSinkConfig {
     pub name: String,
-    pub filename: String
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
+    pub filename: String,
+    #[serde(default)]
+    pub format: Format
+}
+
+fn default_capacity() -> u64 {
+    1024
+}
+
+// Fits a full book
+fn default_slot_size() -> u64 {
+    65536
+}
+
+// Publishes objects to a ring buffer of capacity slots of slot_size bytes, mapped
+// from path, which is usually under /dev/shm
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ShmSinkConfig {
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
+    pub path: String,
+    #[serde(default = "default_capacity")]
+    pub capacity: u64,
+    #[serde(default = "default_slot_size")]
+    pub slot_size: u64
+}
+
+fn default_replay_size() -> usize {
+    4096
+}
+
+// Streams objects to the TcpSources connected to address, the last replay_size
+// ones are kept for the sources which reconnect
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TcpSinkConfig {
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
+    pub address: String,
+    #[serde(default = "default_replay_size")]
+    pub replay_size: usize
+}
+
+// Config of a sink type registered outside holocore
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CustomSinkConfig {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub merge: MergePolicy,
+    #[serde(default)]
+    pub timers: Vec<TimerConfig>,
+    #[serde(flatten)]
+    pub params: Map<String, Value>
+}
+
+impl CustomSinkConfig {
+    // Deserializes the whole entry into the component's own config struct
+    pub fn params<T: DeserializeOwned>(&self) -> Result<T, HolocoreError> {
+        match serde_json::to_value(self) {
+            Ok(value) => serde_json::from_value(value).map_err(|e| HolocoreError::Config(format!("{}: invalid {} config - {}", self.name, self.type_name, e))),
+            Err(e) => Err(HolocoreError::Config(format!("{}: cannot serialize config - {}", self.name, e)))
+        }
+    }
 }
@@ -1,19 +1,44 @@
-use serde::{Deserialize, Serialize};
+use crate::config::edge::Downstream;
+use crate::config::format::Format;
+use crate::config::placement::Placement;
+use crate::config::restart::RestartPolicy;
+use crate::error::HolocoreError;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{ Map, Value };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum SourceConfigs {
     BinanceSpotSource(BinanceSpotSourceConfig),
     FileSource(FileSourceConfig),
-    MockSource(MockSourceConfig)
+    MockSource(MockSourceConfig),
+    ShmSource(ShmSourceConfig),
+    TcpSource(TcpSourceConfig),
+    #[serde(untagged)]
+    Custom(CustomSourceConfig)
 }
 
 impl SourceConfigs {
+    pub fn type_name(&self) -> &str {
+        match self {
+            SourceConfigs::BinanceSpotSource(_) => "BinanceSpotSource",
+            SourceConfigs::FileSource(_) => "FileSource",
+            SourceConfigs::MockSource(_) => "MockSource",
+            SourceConfigs::ShmSource(_) => "ShmSource",
+            SourceConfigs::TcpSource(_) => "TcpSource",
+            SourceConfigs::Custom(cfg) => &cfg.type_name
+        }
+    }
+
     pub fn name(&self) -> &str {
         match self {
             SourceConfigs::BinanceSpotSource(cfg) => &cfg.name,
             SourceConfigs::FileSource(cfg) => &cfg.name,
-            SourceConfigs::MockSource(cfg) => &cfg.name
+            SourceConfigs::MockSource(cfg) => &cfg.name,
+            SourceConfigs::ShmSource(cfg) => &cfg.name,
+            SourceConfigs::TcpSource(cfg) => &cfg.name,
+            SourceConfigs::Custom(cfg) => &cfg.name
         }
     }
 
@@ -21,15 +46,66 @@ impl SourceConfigs {
         match self {
             SourceConfigs::BinanceSpotSource(cfg) => cfg.queue_size,
             SourceConfigs::FileSource(cfg) => cfg.queue_size,
-            SourceConfigs::MockSource(cfg) => cfg.queue_size
+            SourceConfigs::MockSource(cfg) => cfg.queue_size,
+            SourceConfigs::ShmSource(cfg) => cfg.queue_size,
+            SourceConfigs::TcpSource(cfg) => cfg.queue_size,
+            SourceConfigs::Custom(cfg) => cfg.queue_size
         }
     }
 
-    pub fn downstreams(&self) -> &Vec<String> {
+    pub fn downstreams(&self) -> &Vec<Downstream> {
         match self {
             SourceConfigs::BinanceSpotSource(cfg) => &cfg.downstreams,
             SourceConfigs::FileSource(cfg) => &cfg.downstreams,
-            SourceConfigs::MockSource(cfg) => &cfg.downstreams
+            SourceConfigs::MockSource(cfg) => &cfg.downstreams,
+            SourceConfigs::ShmSource(cfg) => &cfg.downstreams,
+            SourceConfigs::TcpSource(cfg) => &cfg.downstreams,
+            SourceConfigs::Custom(cfg) => &cfg.downstreams
+        }
+    }
+
+    pub fn downstreams_mut(&mut self) -> &mut Vec<Downstream> {
+        match self {
+            SourceConfigs::BinanceSpotSource(cfg) => &mut cfg.downstreams,
+            SourceConfigs::FileSource(cfg) => &mut cfg.downstreams,
+            SourceConfigs::MockSource(cfg) => &mut cfg.downstreams,
+            SourceConfigs::ShmSource(cfg) => &mut cfg.downstreams,
+            SourceConfigs::TcpSource(cfg) => &mut cfg.downstreams,
+            SourceConfigs::Custom(cfg) => &mut cfg.downstreams
+        }
+    }
+
+    pub fn restart(&self) -> &RestartPolicy {
+        match self {
+            SourceConfigs::BinanceSpotSource(cfg) => &cfg.restart,
+            SourceConfigs::FileSource(cfg) => &cfg.restart,
+            SourceConfigs::MockSource(cfg) => &cfg.restart,
+            SourceConfigs::ShmSource(cfg) => &cfg.restart,
+            SourceConfigs::TcpSource(cfg) => &cfg.restart,
+            SourceConfigs::Custom(cfg) => &cfg.restart
+        }
+    }
+
+    pub fn placement(&self) -> &Placement {
+        match self {
+            SourceConfigs::BinanceSpotSource(cfg) => &cfg.placement,
+            SourceConfigs::FileSource(cfg) => &cfg.placement,
+            SourceConfigs::MockSource(cfg) => &cfg.placement,
+            SourceConfigs::ShmSource(cfg) => &cfg.placement,
+            SourceConfigs::TcpSource(cfg) => &cfg.placement,
+            SourceConfigs::Custom(cfg) => &cfg.placement
+        }
+    }
+
+    // Whether the emitted objects carry the hops stamped along the pipeline
+    pub fn trace(&self) -> bool {
+        match self {
+            SourceConfigs::BinanceSpotSource(cfg) => cfg.trace,
+            SourceConfigs::FileSource(cfg) => cfg.trace,
+            SourceConfigs::MockSource(cfg) => cfg.trace,
+            SourceConfigs::ShmSource(cfg) => cfg.trace,
+            SourceConfigs::TcpSource(cfg) => cfg.trace,
+            SourceConfigs::Custom(cfg) => cfg.trace
         }
     }
 }
@@ -37,8 +113,14 @@ impl SourceConfigs {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct BinanceSpotSourceConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub trace: bool,
     pub queue_size: usize,
-    pub downstreams: Vec<String>,
+    pub downstreams: Vec<Downstream>,
     pub base_url: String,
     pub snapshot_url: String,
     pub symbols: Vec<String>,
@@ -48,15 +130,111 @@ pub struct BinanceSpotSourceConfig {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct FileSourceConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub trace: bool,
     pub queue_size: usize,
-    pub downstreams: Vec<String>,
-    pub filename: String
+    pub downstreams: Vec<Downstream>,
+    pub filename: String,
+    // Paces the objects by their timestamps, speed times faster than recorded. As fast as possible if unset.
+    #[serde(default)]
+    pub speed: Option<f64>,
+    #[serde(default)]
+    pub format: Format,
+    // Brings the recorded objects to the latest version of their type
+    #[serde(default)]
+    pub upcast: bool
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MockSourceConfig {
     pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub trace: bool,
     pub queue_size: usize,
-    pub downstreams: Vec<String>,
+    pub downstreams: Vec<Downstream>,
     pub sleep_time: u64
 }
+
+fn default_reconnect_backoff() -> u64 {
+    100
+}
+
+fn default_max_reconnect_backoff() -> u64 {
+    30000
+}
+
+fn default_poll_interval() -> u64 {
+    10
+}
+
+// Reads the ring buffer of a ShmSink, polling every poll_interval microseconds when it is empty
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ShmSourceConfig {
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub trace: bool,
+    pub queue_size: usize,
+    pub downstreams: Vec<Downstream>,
+    pub path: String,
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64
+}
+
+// Subscribes to a TcpSink of another processor, backoffs are in milliseconds
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TcpSourceConfig {
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub trace: bool,
+    pub queue_size: usize,
+    pub downstreams: Vec<Downstream>,
+    pub address: String,
+    #[serde(default = "default_reconnect_backoff")]
+    pub reconnect_backoff: u64,
+    #[serde(default = "default_max_reconnect_backoff")]
+    pub max_reconnect_backoff: u64
+}
+
+// Config of a source type registered outside holocore
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CustomSourceConfig {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub name: String,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub placement: Placement,
+    #[serde(default)]
+    pub trace: bool,
+    pub queue_size: usize,
+    pub downstreams: Vec<Downstream>,
+    #[serde(flatten)]
+    pub params: Map<String, Value>
+}
+
+impl CustomSourceConfig {
+    // Deserializes the whole entry into the component's own config struct
+    pub fn params<T: DeserializeOwned>(&self) -> Result<T, HolocoreError> {
+        match serde_json::to_value(self) {
+            Ok(value) => serde_json::from_value(value).map_err(|e| HolocoreError::Config(format!("{}: invalid {} config - {}", self.name, self.type_name, e))),
+            Err(e) => Err(HolocoreError::Config(format!("{}: cannot serialize config - {}", self.name, e)))
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{ Deserialize, Serialize };
+
+// Timer of a PU or a sink, e.g. { name = "snapshot", interval = 1000 } or
+// { name = "roll", cron = "0 0 0 * * *" }
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TimerConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub schedule: Schedule
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Schedule {
+    // Every that many milliseconds, starting one period after the component
+    Interval(u64),
+    // Cron expression in UTC, with seconds: sec min hour day-of-month month day-of-week [year]
+    Cron(String)
+}
@@ -0,0 +1,433 @@
+use crate::components::registry::Registry;
+use crate::config::Config;
+use crate::config::edge::{ Downstream, EdgeFilter };
+use crate::config::restart::RestartPolicy;
+use crate::config::timer::{ Schedule, TimerConfig };
+use crate::config::pus::{ CustomPUConfig, MockPUConfig, OrderManagerPUConfig, OrderbookManagerPUConfig, StrategyPUConfig, PUConfigs };
+use crate::config::sinks::{ BinanceSpotSinkConfig, CustomSinkConfig, FileSinkConfig, MockSinkConfig, ShmSinkConfig, SinkConfigs, TcpSinkConfig };
+use crate::config::sources::{ BinanceSpotSourceConfig, CustomSourceConfig, FileSourceConfig, MockSourceConfig, ShmSourceConfig, SourceConfigs, TcpSourceConfig };
+use crate::objects::{ numeric, schema };
+
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationError {
+    pub component: String,
+    pub field: String,
+    pub message: String
+}
+
+impl ValidationError {
+    fn new(component: &str, field: &str, message: String) -> Self {
+        Self {
+            component: String::from(component),
+            field: String::from(field),
+            message
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}: {}", self.component, self.field, self.message)
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Kind {
+    Source,
+    PU,
+    Sink
+}
+
+// Built-in types that fell back to a custom config because a field is missing or has the wrong type
+fn malformed_source(cfg: &CustomSourceConfig) -> Option<String> {
+    match cfg.type_name.as_str() {
+        "BinanceSpotSource" => cfg.params::<BinanceSpotSourceConfig>().err().map(|e| String::from(e.get_message())),
+        "FileSource" => cfg.params::<FileSourceConfig>().err().map(|e| String::from(e.get_message())),
+        "MockSource" => cfg.params::<MockSourceConfig>().err().map(|e| String::from(e.get_message())),
+        "ShmSource" => cfg.params::<ShmSourceConfig>().err().map(|e| String::from(e.get_message())),
+        "TcpSource" => cfg.params::<TcpSourceConfig>().err().map(|e| String::from(e.get_message())),
+        _ => None
+    }
+}
+
+fn malformed_pu(cfg: &CustomPUConfig) -> Option<String> {
+    match cfg.type_name.as_str() {
+        "MockPU" => cfg.params::<MockPUConfig>().err().map(|e| String::from(e.get_message())),
+        "OrderManagerPU" => cfg.params::<OrderManagerPUConfig>().err().map(|e| String::from(e.get_message())),
+        "OrderbookManagerPU" => cfg.params::<OrderbookManagerPUConfig>().err().map(|e| String::from(e.get_message())),
+        "StrategyPU" => cfg.params::<StrategyPUConfig>().err().map(|e| String::from(e.get_message())),
+        _ => None
+    }
+}
+
+fn malformed_sink(cfg: &CustomSinkConfig) -> Option<String> {
+    match cfg.type_name.as_str() {
+        "BinanceSpotSink" => cfg.params::<BinanceSpotSinkConfig>().err().map(|e| String::from(e.get_message())),
+        "FileSink" => cfg.params::<FileSinkConfig>().err().map(|e| String::from(e.get_message())),
+        "MockSink" => cfg.params::<MockSinkConfig>().err().map(|e| String::from(e.get_message())),
+        "ShmSink" => cfg.params::<ShmSinkConfig>().err().map(|e| String::from(e.get_message())),
+        "TcpSink" => cfg.params::<TcpSinkConfig>().err().map(|e| String::from(e.get_message())),
+        _ => None
+    }
+}
+
+// Reports every problem found in the topology, without building any component
+pub fn validate(cfg: &Config, registry: &Registry) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::<ValidationError>::new();
+    let mut kinds = HashMap::<&str, Kind>::new();
+    let mut edges = Vec::<(&str, &Vec<Downstream>)>::new();
+
+    let pus: &[PUConfigs] = match &cfg.pus {
+        Some(cfgs) => cfgs,
+        None => &[]
+    };
+
+    // Names and types
+    for cfg in &cfg.sources {
+        register_name(cfg.name(), Kind::Source, &mut kinds, &mut errors);
+        check_restart(cfg.name(), cfg.restart(), &mut errors);
+        if let SourceConfigs::Custom(custom) = cfg {
+            if let Some(e) = malformed_source(custom) {
+                errors.push(ValidationError::new(cfg.name(), "type", e));
+            }
+        }
+
+        if let SourceConfigs::FileSource(file) = cfg {
+            if file.speed.is_some_and(|speed| speed.is_nan() || speed <= 0.0) {
+                errors.push(ValidationError::new(cfg.name(), "speed", String::from("must be positive")));
+            }
+        }
+
+        if !registry.contains_source(cfg.type_name()) {
+            errors.push(ValidationError::new(cfg.name(), "type", format!("unknown source type {}", cfg.type_name())));
+        }
+    }
+
+    for cfg in pus {
+        register_name(cfg.name(), Kind::PU, &mut kinds, &mut errors);
+        check_restart(cfg.name(), cfg.restart(), &mut errors);
+        check_timers(cfg.name(), cfg.timers(), &mut errors);
+        if let PUConfigs::Custom(custom) = cfg {
+            if let Some(e) = malformed_pu(custom) {
+                errors.push(ValidationError::new(cfg.name(), "type", e));
+            }
+        }
+
+        if !registry.contains_pu(cfg.type_name()) {
+            errors.push(ValidationError::new(cfg.name(), "type", format!("unknown PU type {}", cfg.type_name())));
+        }
+    }
+
+    for cfg in &cfg.sinks {
+        register_name(cfg.name(), Kind::Sink, &mut kinds, &mut errors);
+        check_restart(cfg.name(), cfg.restart(), &mut errors);
+        check_timers(cfg.name(), cfg.timers(), &mut errors);
+        if let SinkConfigs::Custom(custom) = cfg {
+            if let Some(e) = malformed_sink(custom) {
+                errors.push(ValidationError::new(cfg.name(), "type", e));
+            }
+        }
+
+        if let SinkConfigs::BinanceSpotSink(binance) = cfg {
+            for (symbol, precision) in &binance.precisions {
+                if precision.price.max(precision.quantity) > numeric::MAX_DECIMALS {
+                    errors.push(ValidationError::new(cfg.name(), "precisions", format!("{} has more than {} decimals", symbol, numeric::MAX_DECIMALS)));
+                }
+            }
+        }
+
+        if !registry.contains_sink(cfg.type_name()) {
+            errors.push(ValidationError::new(cfg.name(), "type", format!("unknown sink type {}", cfg.type_name())));
+        }
+    }
+
+    // Markets, each one built by a single orderbook manager
+    let mut markets = HashMap::<&str, &str>::new();
+    for cfg in pus {
+        if let PUConfigs::OrderbookManagerPU(OrderbookManagerPUConfig { market: Some(market), .. }) = cfg {
+            if let Some(writer) = markets.insert(market, cfg.name()) {
+                errors.push(ValidationError::new(cfg.name(), "market", format!("market {} is already written by {}", market, writer)));
+            }
+        }
+    }
+
+    for cfg in pus {
+        if let PUConfigs::StrategyPU(StrategyPUConfig { market: Some(market), .. }) = cfg {
+            if !markets.contains_key(market.as_str()) {
+                errors.push(ValidationError::new(cfg.name(), "market", format!("no orderbook manager writes market {}", market)));
+            }
+        }
+    }
+
+    // Edges
+    for cfg in &cfg.sources {
+        edges.push((cfg.name(), cfg.downstreams()));
+        for downstream in cfg.downstreams() {
+            if let Some(port) = downstream.port() {
+                errors.push(ValidationError::new(cfg.name(), "downstreams", format!("port {} towards {} - only PUs have output ports", port, downstream.name())));
+            }
+        }
+    }
+
+    for cfg in pus {
+        edges.push((cfg.name(), cfg.downstreams()));
+    }
+
+    let mut inputs = HashMap::<&str, usize>::new();
+    let mut graph = HashMap::<&str, Vec<&str>>::new();
+    for (name, downstreams) in &edges {
+        if downstreams.is_empty() {
+            errors.push(ValidationError::new(name, "downstreams", String::from("output goes nowhere")));
+        }
+
+        for filter in downstreams.iter().filter_map(|downstream| downstream.filter()) {
+            check_filter(name, filter, &kinds, &mut errors);
+        }
+
+        for downstream in downstreams.iter() {
+            for key in downstream.unknown_keys() {
+                errors.push(ValidationError::new(name, "downstreams", format!("unknown key {} in the edge towards {}", key, downstream.name())));
+            }
+        }
+
+        // The same downstream can be reached through several ports
+        let mut seen = HashSet::<(&str, Option<&str>)>::new();
+        for (downstream, port) in downstreams.iter().map(|downstream| (downstream.name(), downstream.port())) {
+            if !seen.insert((downstream, port)) {
+                errors.push(ValidationError::new(name, "downstreams", format!("duplicate downstream {}", downstream)));
+                continue;
+            }
+
+            match kinds.get(downstream) {
+                Some(Kind::Source) => errors.push(ValidationError::new(name, "downstreams", format!("{} is a source and cannot receive objects", downstream))),
+                Some(_) => {
+                    *inputs.entry(downstream).or_insert(0) += 1;
+                    graph.entry(name).or_default().push(downstream);
+                },
+                None => errors.push(ValidationError::new(name, "downstreams", format!("unknown downstream {}", downstream)))
+            }
+        }
+    }
+
+    for cfg in pus {
+        if !inputs.contains_key(cfg.name()) {
+            errors.push(ValidationError::new(cfg.name(), "inputs", String::from("PU has no inputs")));
+        }
+    }
+
+    for cfg in &cfg.sinks {
+        if !inputs.contains_key(cfg.name()) {
+            errors.push(ValidationError::new(cfg.name(), "inputs", String::from("sink has no inputs")));
+        }
+    }
+
+    // Cycles, only PUs can be part of one
+    let mut visited = HashSet::<&str>::new();
+    for cfg in pus {
+        let mut path = Vec::<&str>::new();
+        find_cycle(cfg.name(), &graph, &mut visited, &mut path, &mut errors);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn register_name<'a>(name: &'a str, kind: Kind, kinds: &mut HashMap<&'a str, Kind>, errors: &mut Vec<ValidationError>) {
+    if name.is_empty() {
+        errors.push(ValidationError::new("<unnamed>", "name", String::from("component name cannot be empty")));
+    }
+
+    if kinds.insert(name, kind).is_some() {
+        errors.push(ValidationError::new(name, "name", format!("duplicate component name {}", name)));
+    }
+}
+
+fn check_restart(name: &str, policy: &RestartPolicy, errors: &mut Vec<ValidationError>) {
+    match policy {
+        RestartPolicy::Always { backoff, max_backoff } | RestartPolicy::OnFailure { backoff, max_backoff, .. } => {
+            if backoff > max_backoff {
+                errors.push(ValidationError::new(name, "restart", format!("backoff {} ms is greater than max_backoff {} ms", backoff, max_backoff)));
+            }
+        },
+        RestartPolicy::Never => ()
+    }
+}
+
+fn check_timers(name: &str, timers: &[TimerConfig], errors: &mut Vec<ValidationError>) {
+    let mut seen = HashSet::<&str>::new();
+    for timer in timers {
+        if !seen.insert(&timer.name) {
+            errors.push(ValidationError::new(name, "timers", format!("duplicate timer {}", timer.name)));
+        }
+
+        match &timer.schedule {
+            Schedule::Interval(0) => errors.push(ValidationError::new(name, "timers", format!("timer {} has an interval of 0 ms", timer.name))),
+            Schedule::Interval(_) => (),
+            Schedule::Cron(expression) => {
+                if let Err(e) = cron::Schedule::from_str(expression) {
+                    errors.push(ValidationError::new(name, "timers", format!("timer {} has an invalid cron expression {} - {}", timer.name, expression, e)));
+                }
+            }
+        }
+    }
+}
+
+fn check_filter(name: &str, filter: &EdgeFilter, kinds: &HashMap<&str, Kind>, errors: &mut Vec<ValidationError>) {
+    for type_name in &filter.types {
+        if schema::find_by_name(type_name).is_none() {
+            errors.push(ValidationError::new(name, "downstreams", format!("unknown object type {} in filter", type_name)));
+        }
+    }
+
+    for source in &filter.sources {
+        if kinds.get(source.as_str()) != Some(&Kind::Source) {
+            errors.push(ValidationError::new(name, "downstreams", format!("unknown source {} in filter", source)));
+        }
+    }
+}
+
+fn find_cycle<'a>(node: &'a str, graph: &HashMap<&'a str, Vec<&'a str>>, visited: &mut HashSet<&'a str>, path: &mut Vec<&'a str>, errors: &mut Vec<ValidationError>) {
+    if let Some(start) = path.iter().position(|&name| name == node) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(node);
+        errors.push(ValidationError::new(node, "downstreams", format!("cycle detected {}", cycle.join(" -> "))));
+        return;
+    }
+
+    if !visited.insert(node) {
+        return;
+    }
+
+    path.push(node);
+    if let Some(downstreams) = graph.get(node) {
+        for downstream in downstreams {
+            find_cycle(downstream, graph, visited, path, errors);
+        }
+    }
+    path.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::registry::Registry;
+    use crate::config::{ self, Config, validation::validate };
+
+    fn parse(text: &str) -> Config {
+        toml::from_str(text).expect("Cannot parse config")
+    }
+
+    const HEADER: &str = r#"
+        name = "Test"
+        version = 1
+        log_level = "info"
+        workers = 1
+        stack_size = 4
+        ctrl_host = "127.0.0.1"
+        ctrl_port = 8000
+    "#;
+
+    #[test]
+    fn test_validate_topology() {
+        let cfg = parse(&format!(r#"{}
+            [[sources]]
+            type = "MockSource"
+            name = "Source"
+            queue_size = 8
+            downstreams = [ {{ name = "PU1", port = "orders" }}, {{ name = "Missing", policy = "drop-oldest" }} ]
+            sleep_time = 0
+
+            [[pus]]
+            type = "MockPU"
+            name = "PU1"
+            queue_size = 8
+            downstreams = [ {{ name = "PU2", types = [ "TradeV1", "Trades" ], sources = [ "Source", "PU3" ], symbol = [ "BTCUSDT" ] }} ]
+
+            [[pus]]
+            type = "MockPU"
+            name = "PU2"
+            queue_size = 8
+            downstreams = [ "PU1" ]
+
+            [[pus]]
+            type = "MockPU"
+            name = "PU3"
+            queue_size = 8
+            downstreams = []
+            timers = [ {{ name = "snapshot", interval = 0 }}, {{ name = "roll", cron = "0 0 0 * * *" }}, {{ name = "roll", cron = "midnight" }} ]
+
+            [[pus]]
+            type = "OrderbookManagerPU"
+            name = "Books1"
+            queue_size = 8
+            downstreams = [ "PU2" ]
+            realtime = false
+            market = "Books"
+
+            [[pus]]
+            type = "OrderbookManagerPU"
+            name = "Books2"
+            queue_size = 8
+            downstreams = [ "PU2" ]
+            realtime = false
+            market = "Books"
+
+            [[pus]]
+            type = "StrategyPU"
+            name = "Strategy"
+            queue_size = 8
+            downstreams = [ "PU2", {{ name = "PU2", port = "orders" }}, {{ name = "PU2", port = "orders" }} ]
+            realtime = false
+            algorithm = "TrendFollowing"
+            market = "Klines"
+
+            [[sinks]]
+            type = "MockSink"
+            name = "Sink"
+            restart = {{ policy = "always", backoff = 500, max_backoff = 100 }}
+
+            [[sinks]]
+            type = "FileSink"
+            name = "Sink"
+        "#, HEADER));
+
+        let errors = validate(&cfg, &Registry::new()).unwrap_err();
+        let messages = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert!(messages.contains(&String::from("Source.downstreams: unknown downstream Missing")));
+        assert!(messages.contains(&String::from("Source.downstreams: port orders towards PU1 - only PUs have output ports")));
+        assert!(messages.contains(&String::from("PU3.downstreams: output goes nowhere")));
+        assert!(messages.contains(&String::from("PU1.downstreams: unknown object type Trades in filter")));
+        assert!(messages.contains(&String::from("PU1.downstreams: unknown source PU3 in filter")));
+        assert!(messages.contains(&String::from("PU1.downstreams: unknown key symbol in the edge towards PU2")));
+        assert!(messages.contains(&String::from("PU3.inputs: PU has no inputs")));
+        assert!(messages.contains(&String::from("Sink.name: duplicate component name Sink")));
+        assert!(messages.contains(&String::from("Sink.inputs: sink has no inputs")));
+        assert!(messages.contains(&String::from("PU1.downstreams: cycle detected PU1 -> PU2 -> PU1")));
+        assert!(messages.contains(&String::from("Sink.restart: backoff 500 ms is greater than max_backoff 100 ms")));
+        assert!(messages.contains(&String::from("PU3.timers: timer snapshot has an interval of 0 ms")));
+        assert!(messages.contains(&String::from("PU3.timers: duplicate timer roll")));
+        assert!(messages.iter().any(|message| message.starts_with("PU3.timers: timer roll has an invalid cron expression midnight")));
+        assert!(messages.contains(&String::from("Books2.market: market Books is already written by Books1")));
+        assert!(messages.contains(&String::from("Strategy.market: no orderbook manager writes market Klines")));
+        assert_eq!(messages.iter().filter(|message| message.starts_with("Strategy.downstreams: duplicate downstream PU2")).count(), 1);
+        assert!(errors.iter().any(|e| e.component == "Sink" && e.field == "type"));
+    }
+
+    #[test]
+    fn test_validate_static_data() {
+        let pattern = format!("{}/static_data/prod", env!("CARGO_MANIFEST_DIR"));
+        for entry in std::fs::read_dir(pattern).expect("Cannot list static_data/prod") {
+            let path = entry.expect("Cannot read entry").path();
+            let cfg = config::load(path.to_str().unwrap()).expect("Cannot load config");
+            if let Err(errors) = validate(&cfg, &Registry::new()) {
+                panic!("{:?}: {:?}", path, errors);
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::config::edge::{ BackPressure, Downstream, EdgeFilter };
+use crate::objects::Objects;
+
+use flume::{ Receiver, SendError, Sender, TrySendError, bounded };
+use std::sync::{ Arc, Weak, atomic::{ AtomicU64, Ordering } };
+
+// Sending half of a connection between two components, applying the back-pressure policy.
+// The receiving half is shared with the edge, so that queued objects can be discarded
+// without keeping the downstream alive.
+#[derive(Clone)]
+pub struct Edge {
+    downstream: String,
+    port: Option<String>,
+    policy: BackPressure,
+    filter: Option<EdgeFilter>,
+    tx: Sender<Objects>,
+    rx: Weak<Receiver<Objects>>,
+    dropped: Arc<AtomicU64>
+}
+
+// Read-only view of an edge for the control API, which does not keep the channel alive
+#[derive(Clone)]
+pub struct EdgeProbe {
+    upstream: String,
+    downstream: String,
+    dropped: Arc<AtomicU64>,
+    rx: Weak<Receiver<Objects>>
+}
+
+impl EdgeProbe {
+    // Getters
+    pub fn get_upstream(&self) -> &str {
+        &self.upstream
+    }
+
+    pub fn get_downstream(&self) -> &str {
+        &self.downstream
+    }
+
+    pub fn get_dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.rx.upgrade().map(|rx| rx.len()).unwrap_or(0)
+    }
+}
+
+pub fn edge(cfg: &Downstream, queue_size: usize) -> (Edge, Arc<Receiver<Objects>>) {
+    let (tx, rx) = bounded(queue_size);
+    let rx = Arc::new(rx);
+
+    let edge = Edge {
+        downstream: String::from(cfg.name()),
+        port: cfg.port().map(String::from),
+        policy: cfg.policy(),
+        filter: cfg.filter().filter(|filter| !filter.is_empty()).cloned(),
+        tx,
+        rx: Arc::downgrade(&rx),
+        dropped: Arc::new(AtomicU64::new(0))
+    };
+
+    (edge, rx)
+}
+
+impl Edge {
+    pub async fn send(&self, obj: Objects) -> Result<(), SendError<Objects>> {
+        match self.policy {
+            BackPressure::Block => self.tx.send_async(obj).await,
+            BackPressure::DropNewest => match self.tx.try_send(obj) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                },
+                Err(TrySendError::Disconnected(obj)) => Err(SendError(obj))
+            },
+            BackPressure::DropOldest => {
+                let mut obj = obj;
+                loop {
+                    match self.tx.try_send(obj) {
+                        Ok(()) => return Ok(()),
+                        Err(TrySendError::Full(rejected)) => {
+                            let rx = match self.rx.upgrade() {
+                                Some(rx) => rx,
+                                None => return Err(SendError(rejected))
+                            };
+
+                            if rx.try_recv().is_ok() {
+                                self.dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                            obj = rejected;
+                        },
+                        Err(TrySendError::Disconnected(obj)) => return Err(SendError(obj))
+                    }
+                }
+            },
+            BackPressure::ConflatePerSymbol => match self.tx.try_send(obj) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(rejected)) => {
+                    let rx = match self.rx.upgrade() {
+                        Some(rx) => rx,
+                        None => return Err(SendError(rejected))
+                    };
+
+                    self.conflate(&rx, rejected);
+                    Ok(())
+                },
+                Err(TrySendError::Disconnected(obj)) => Err(SendError(obj))
+            }
+        }
+    }
+
+    // Takes the queued objects out, replaces every object with the latest one of the same
+    // type and symbol, and queues them again. Objects without a symbol, e.g. timers, are all
+    // kept. The oldest ones are dropped if the queue is still full.
+    fn conflate(&self, rx: &Receiver<Objects>, obj: Objects) {
+        let mut queued = Vec::<Objects>::new();
+        let mut total = 0;
+        for obj in rx.drain().chain(std::iter::once(obj)) {
+            total += 1;
+            let symbol = obj.get_symbol().filter(|symbol| !symbol.is_empty());
+            match symbol.and_then(|symbol| queued.iter().position(|queued| queued.get_type() == obj.get_type() && queued.get_symbol() == Some(symbol))) {
+                Some(idx) => queued[idx] = obj,
+                None => queued.push(obj)
+            }
+        }
+
+        let capacity = self.tx.capacity().unwrap_or(queued.len());
+        if queued.len() > capacity {
+            queued.drain(..queued.len() - capacity);
+        }
+
+        self.dropped.fetch_add((total - queued.len()) as u64, Ordering::Relaxed);
+        for obj in queued {
+            if self.tx.try_send(obj).is_err() {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    // Objects filtered out are not sent at all, so they never take queue space
+    pub fn accepts(&self, obj: &Objects) -> bool {
+        match &self.filter {
+            Some(filter) => filter.accepts(obj),
+            None => true
+        }
+    }
+
+    pub fn probe(&self, upstream: &str) -> EdgeProbe {
+        EdgeProbe {
+            upstream: String::from(upstream),
+            downstream: String::clone(&self.downstream),
+            dropped: self.dropped.clone(),
+            rx: self.rx.clone()
+        }
+    }
+
+    // Getters
+    pub fn get_downstream(&self) -> &str {
+        &self.downstream
+    }
+
+    pub fn get_port(&self) -> Option<&str> {
+        self.port.as_deref()
+    }
+
+    pub fn get_dropped(&self) -> Arc<AtomicU64> {
+        self.dropped.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::edge::{ BackPressure, Downstream, EdgeConfig };
+    use crate::edge::edge;
+    use crate::objects::{ Objects, mock::MockV1, trade::TradeV1 };
+    use crate::utils::uuid;
+
+    use std::sync::atomic::Ordering;
+
+    fn downstream(policy: BackPressure) -> Downstream {
+        Downstream::Edge(EdgeConfig { name: String::from("Sink"), policy, ..Default::default() })
+    }
+
+    fn mock(sequence_number: u64) -> Objects {
+        Objects::MockV1(MockV1::new(uuid::uuid(), 0, sequence_number, uuid::uuid()))
+    }
+
+    fn trade(symbol: &str, trade_id: u64) -> Objects {
+        let event = serde_json::from_value(serde_json::json!({
+            "e": "trade", "E": 0, "s": symbol, "t": trade_id, "p": "1.0", "q": "1.0", "b": 0, "a": 0, "T": 0, "m": false, "M": false
+        })).expect("Cannot build trade event");
+        Objects::TradeV1(TradeV1::from_binance(uuid::uuid(), 0, trade_id, uuid::uuid(), "Test", "Binance", "Spot", event))
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let (newest, rx) = edge(&downstream(BackPressure::DropNewest), 2);
+        for idx in 0..4 {
+            newest.send(mock(idx)).await.unwrap();
+        }
+        assert_eq!(rx.drain().map(|obj| obj.get_sequence_number()).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(newest.get_dropped().load(Ordering::Relaxed), 2);
+
+        let (oldest, rx) = edge(&downstream(BackPressure::DropOldest), 2);
+        for idx in 0..4 {
+            oldest.send(mock(idx)).await.unwrap();
+        }
+        assert_eq!(rx.drain().map(|obj| obj.get_sequence_number()).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(oldest.get_dropped().load(Ordering::Relaxed), 2);
+
+        drop(rx);
+        assert!(oldest.send(mock(4)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_conflate_per_symbol() {
+        let (conflate, rx) = edge(&downstream(BackPressure::ConflatePerSymbol), 2);
+        conflate.send(trade("BTCUSDT", 1)).await.unwrap();
+        conflate.send(trade("ETHUSDT", 2)).await.unwrap();
+        conflate.send(trade("BTCUSDT", 3)).await.unwrap();
+        conflate.send(trade("ETHUSDT", 4)).await.unwrap();
+
+        let queued = rx.drain().map(|obj| (String::from(obj.get_symbol().unwrap()), obj.get_sequence_number())).collect::<Vec<_>>();
+        assert_eq!(queued, vec![(String::from("BTCUSDT"), 3), (String::from("ETHUSDT"), 4)]);
+        assert_eq!(conflate.get_dropped().load(Ordering::Relaxed), 2);
+
+        // Objects without a symbol are never merged together
+        let (conflate, rx) = edge(&downstream(BackPressure::ConflatePerSymbol), 3);
+        conflate.send(trade("BTCUSDT", 1)).await.unwrap();
+        conflate.send(mock(2)).await.unwrap();
+        conflate.send(trade("BTCUSDT", 3)).await.unwrap();
+        conflate.send(mock(4)).await.unwrap();
+
+        assert_eq!(rx.drain().map(|obj| obj.get_sequence_number()).collect::<Vec<_>>(), vec![3, 2, 4]);
+        assert_eq!(conflate.get_dropped().load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_filters() {
+        let cfg: Downstream = toml::from_str(r#"
+            name = "Recorder"
+            types = [ "TradeV1" ]
+            symbols = [ "btcusdt", "ethusdt" ]
+            exclude_symbols = [ "ETHUSDT" ]
+            sources = [ "Test" ]
+        "#).expect("Cannot parse downstream");
+        let (filtered, _rx) = edge(&cfg, 8);
+
+        assert!(filtered.accepts(&trade("BTCUSDT", 1)));
+        assert!(!filtered.accepts(&trade("ETHUSDT", 2)));
+        assert!(!filtered.accepts(&trade("BNBUSDT", 3)));
+        assert!(!filtered.accepts(&mock(4)));
+
+        let (unfiltered, _rx) = edge(&Downstream::Name(String::from("Recorder")), 8);
+        assert!(unfiltered.accepts(&mock(4)));
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+// What the processor does once a component returns an error
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    // Drops the object at hand and carries on
+    Skip,
+    // Stops the component, which restarts according to its restart policy
+    Restart,
+    // Stops the whole pipeline
+    Stop
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HolocoreError {
+    // Missing or invalid settings, which no restart can fix
+    Config(String),
+    // Connection refused, lost or timed out
+    Network(String),
+    // File, mapping or socket failure
+    Io(String),
+    // A single message or object that cannot be decoded, encoded or handled
+    Malformed(String),
+    // A request refused by the exchange, e.g. an order with an invalid quantity
+    Rejected(String),
+    // Broken invariant, e.g. a lock poisoned by a panic
+    Internal(String)
+}
+
+impl HolocoreError {
+    pub fn action(&self) -> Action {
+        match self {
+            HolocoreError::Config(_) => Action::Stop,
+            HolocoreError::Network(_) | HolocoreError::Io(_) | HolocoreError::Internal(_) => Action::Restart,
+            HolocoreError::Malformed(_) | HolocoreError::Rejected(_) => Action::Skip
+        }
+    }
+
+    // Getters
+    pub fn get_message(&self) -> &str {
+        match self {
+            HolocoreError::Config(msg) |
+            HolocoreError::Network(msg) |
+            HolocoreError::Io(msg) |
+            HolocoreError::Malformed(msg) |
+            HolocoreError::Rejected(msg) |
+            HolocoreError::Internal(msg) => msg
+        }
+    }
+}
+
+impl fmt::Display for HolocoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            HolocoreError::Config(_) => "config",
+            HolocoreError::Network(_) => "network",
+            HolocoreError::Io(_) => "io",
+            HolocoreError::Malformed(_) => "malformed",
+            HolocoreError::Rejected(_) => "rejected",
+            HolocoreError::Internal(_) => "internal"
+        };
+        write!(f, "{} error - {}", kind, self.get_message())
+    }
+}
+
+impl std::error::Error for HolocoreError {}
+
+// The control API and the config loading still report plain messages
+impl From<HolocoreError> for String {
+    fn from(e: HolocoreError) -> Self {
+        e.to_string()
+    }
+}
@@ -0,0 +1,169 @@
+use crate::components::control::Controls;
+use crate::config::Config;
+use crate::config::edge::Downstream;
+use crate::edge::{ Edge, EdgeProbe, edge };
+use crate::metrics::ComponentMetrics;
+use crate::objects::Objects;
+use crate::supervisor::SupervisorStatus;
+use crate::utils::uuid::IdGenerator;
+
+use flume::{ Receiver, Sender };
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Running components and the edges between them. The config is kept in step with them,
+// so that every change made at runtime is validated like a config file.
+pub struct Graph {
+    cfg: Config,
+    ctrl_txs: HashMap<String, Sender<Controls>>,
+    statuses: HashMap<String, Arc<SupervisorStatus>>,
+    metrics: HashMap<String, Arc<ComponentMetrics>>,
+    ids: HashMap<String, Arc<dyn IdGenerator>>,
+    edges: Vec<EdgeProbe>,
+
+    // Control of the removed components, which stop once their inputs are drained. Dropped
+    // once their task has stopped and dropped the receiving half.
+    retired: Vec<Sender<Controls>>
+}
+
+impl Graph {
+    pub fn new(cfg: Config,
+               ctrl_txs: HashMap<String, Sender<Controls>>,
+               statuses: HashMap<String, Arc<SupervisorStatus>>,
+               metrics: HashMap<String, Arc<ComponentMetrics>>,
+               ids: HashMap<String, Arc<dyn IdGenerator>>,
+               edges: Vec<EdgeProbe>) -> Self {
+        Self {
+            cfg,
+            ctrl_txs,
+            statuses,
+            metrics,
+            ids,
+            edges,
+            retired: vec![]
+        }
+    }
+
+    // Records a component whose config is already part of cfg
+    pub fn insert(&mut self, cfg: Config, name: &str, ctrl_tx: Sender<Controls>, status: Arc<SupervisorStatus>, metrics: Arc<ComponentMetrics>, ids: Arc<dyn IdGenerator>) {
+        self.cfg = cfg;
+        self.ctrl_txs.insert(String::from(name), ctrl_tx);
+        self.statuses.insert(String::from(name), status);
+        self.metrics.insert(String::from(name), metrics);
+        self.ids.insert(String::from(name), ids);
+    }
+
+    // Creates an edge from a new component to a running one, which is handed the receiving half
+    pub fn attach(&mut self, upstream: &str, downstream: &Downstream) -> Edge {
+        let (tx, rx) = self.edge(upstream, downstream);
+        self.send(downstream.name(), Controls::Attach(rx));
+        tx
+    }
+
+    // Creates an edge from a running component to a new one, which is handed the receiving half
+    pub fn connect(&mut self, upstream: &str, downstream: &Downstream) -> Arc<Receiver<Objects>> {
+        let (tx, rx) = self.edge(upstream, downstream);
+        self.send(upstream, Controls::Connect(tx));
+        rx
+    }
+
+    fn edge(&mut self, upstream: &str, downstream: &Downstream) -> (Edge, Arc<Receiver<Objects>>) {
+        let queue_size = self.cfg.queue_size(upstream).unwrap_or_else(|| panic!("Cannot find queue size of {}", upstream));
+        let (tx, rx) = edge(downstream, queue_size);
+        self.edges.push(tx.probe(upstream));
+        (tx, rx)
+    }
+
+    // Detaches a component from its upstreams, so that it drains its inputs and stops.
+    // Sources have no inputs and are told to stop instead.
+    pub fn remove(&mut self, cfg: Config, name: &str) {
+        let upstreams = self.edges.iter()
+                                  .filter(|edge| edge.get_downstream() == name)
+                                  .map(|edge| String::from(edge.get_upstream()))
+                                  .collect::<Vec<_>>();
+        for upstream in upstreams {
+            self.send(&upstream, Controls::Disconnect(String::from(name)));
+        }
+
+        if self.cfg.sources.iter().any(|cfg| cfg.name() == name) {
+            self.send(name, Controls::Shutdown);
+        }
+
+        self.cfg = cfg;
+        self.edges.retain(|edge| edge.get_upstream() != name && edge.get_downstream() != name);
+        self.statuses.remove(name);
+        self.metrics.remove(name);
+        self.ids.remove(name);
+        self.retired.retain(|ctrl_tx| !ctrl_tx.is_disconnected());
+        if let Some(ctrl_tx) = self.ctrl_txs.remove(name) {
+            self.retired.push(ctrl_tx);
+        }
+    }
+
+    fn send(&self, name: &str, ctrl: Controls) {
+        match self.ctrl_txs.get(name) {
+            Some(ctrl_tx) => {
+                // Fails only if the component has already stopped
+                if ctrl_tx.send(ctrl).is_err() {
+                    log::warn!("Graph: {} has stopped, cannot change its edges", name);
+                }
+            },
+            None => log::error!("Graph: cannot find component {}", name)
+        }
+    }
+
+    // Getters
+    pub fn get_config(&self) -> &Config {
+        &self.cfg
+    }
+
+    pub fn get_ctrl_tx(&self, name: &str) -> Option<&Sender<Controls>> {
+        self.ctrl_txs.get(name)
+    }
+
+    pub fn get_statuses(&self) -> &HashMap<String, Arc<SupervisorStatus>> {
+        &self.statuses
+    }
+
+    pub fn get_metrics(&self) -> &HashMap<String, Arc<ComponentMetrics>> {
+        &self.metrics
+    }
+
+    // Shared with the component, for the objects its endpoints create on its behalf
+    pub fn get_ids(&self, name: &str) -> Option<Arc<dyn IdGenerator>> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn get_edges(&self) -> &[EdgeProbe] {
+        &self.edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::control::Controls;
+    use crate::config::Config;
+    use crate::graph::Graph;
+    use crate::utils::uuid::RandomIdGenerator;
+
+    use flume::unbounded;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_retired() {
+        let mut graph = Graph::new(Config::default(), HashMap::new(), HashMap::new(), HashMap::new(), HashMap::new(), vec![]);
+        let (first_tx, first_rx) = unbounded::<Controls>();
+        let (second_tx, _second_rx) = unbounded::<Controls>();
+        graph.insert(Config::default(), "First", first_tx, Arc::default(), Arc::default(), Arc::new(RandomIdGenerator));
+        graph.insert(Config::default(), "Second", second_tx, Arc::default(), Arc::default(), Arc::new(RandomIdGenerator));
+
+        // First is kept until its task stops
+        graph.remove(Config::default(), "First");
+        assert_eq!(graph.retired.len(), 1);
+        drop(first_rx);
+        graph.remove(Config::default(), "Second");
+        assert_eq!(graph.retired.len(), 1);
+        assert!(!graph.retired[0].is_disconnected());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::config::merge::MergePolicy;
+use crate::objects::Objects;
+use crate::scheduler::Timers;
+
+use flume::{ Receiver, TryRecvError };
+use futures::future::{ self, Either };
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{ Instant, sleep_until };
+
+// Receiving halves of the edges towards a PU or a sink. Rxs whose senders are gone are disabled,
+// and in timestamp mode each rx has at most one object pending, waiting for the other rxs.
+struct Queues {
+    rxs: Vec<Arc<Receiver<Objects>>>,
+    disabled: Vec<usize>,
+    policy: MergePolicy,
+    heads: Vec<Option<(Objects, Instant)>>
+}
+
+// Objects to execute or send, from the upstreams or from the timers of the component
+pub struct Inputs {
+    queues: Queues,
+    timers: Timers
+}
+
+impl Inputs {
+    pub fn new(rxs: Vec<Arc<Receiver<Objects>>>, policy: MergePolicy, timers: Timers) -> Self {
+        Self {
+            queues: Queues {
+                heads: rxs.iter().map(|_| None).collect(),
+                rxs,
+                disabled: vec![],
+                policy
+            },
+            timers
+        }
+    }
+
+    pub fn attach(&mut self, rx: Arc<Receiver<Objects>>) {
+        self.queues.rxs.push(rx);
+        self.queues.heads.push(None);
+    }
+
+    // Returns None once all the rxs are disconnected and drained, whatever the timers.
+    // Cancelling it loses nothing, the objects already taken stay pending.
+    pub async fn recv(&mut self, name: &str) -> Option<Objects> {
+        tokio::select! {
+            obj = self.queues.recv(name) => obj,
+            obj = self.timers.next() => Some(obj)
+        }
+    }
+}
+
+impl Queues {
+    async fn recv(&mut self, name: &str) -> Option<Objects> {
+        match self.policy {
+            MergePolicy::Arrival => self.collect(name).await,
+            MergePolicy::Timestamp { lateness } => self.merge(name, lateness.map(Duration::from_millis)).await
+        }
+    }
+
+    fn active(&self) -> Vec<usize> {
+        (0..self.rxs.len()).filter(|idx| !self.disabled.contains(idx)).collect()
+    }
+
+    fn disable(&mut self, name: &str, idx: usize, e: impl std::fmt::Display) {
+        log::info!("{}: rx at index {} disconnected - {}", name, idx, e);
+        self.disabled.push(idx);
+    }
+
+    // Receives from any enabled rx
+    async fn collect(&mut self, name: &str) -> Option<Objects> {
+        loop {
+            let active = self.active();
+            if active.is_empty() {
+                log::info!("{}: all the rxs are disconnected. Returning...", name);
+                return None;
+            }
+
+            let futures = active.iter()
+                                .map(|&idx| self.rxs[idx].recv_async())
+                                .collect::<Vec<_>>();
+
+            let (result, index, _) = future::select_all(futures).await;
+            match result {
+                Ok(obj) => return Some(obj),
+                Err(e) => self.disable(name, active[index], e)
+            }
+        }
+    }
+
+    // Releases the oldest pending object once every enabled rx has one, or once it is late
+    async fn merge(&mut self, name: &str, lateness: Option<Duration>) -> Option<Objects> {
+        loop {
+            for idx in self.active() {
+                if self.heads[idx].is_none() {
+                    match self.rxs[idx].try_recv() {
+                        Ok(obj) => self.heads[idx] = Some((obj, Instant::now())),
+                        Err(TryRecvError::Empty) => {},
+                        Err(e) => self.disable(name, idx, e)
+                    }
+                }
+            }
+
+            let oldest = self.heads.iter()
+                                   .enumerate()
+                                   .filter_map(|(idx, head)| head.as_ref().map(|(obj, arrival)| (idx, obj.get_timestamp(), *arrival)))
+                                   .min_by_key(|&(idx, timestamp, _)| (timestamp, idx));
+            let waiting = self.active().into_iter().filter(|&idx| self.heads[idx].is_none()).collect::<Vec<_>>();
+
+            let deadline = match oldest {
+                Some((idx, _, _)) if waiting.is_empty() => return self.heads[idx].take().map(|(obj, _)| obj),
+                Some((idx, _, arrival)) => match lateness {
+                    Some(lateness) if arrival + lateness <= Instant::now() => return self.heads[idx].take().map(|(obj, _)| obj),
+                    Some(lateness) => Some(arrival + lateness),
+                    None => None
+                },
+                None if waiting.is_empty() => {
+                    log::info!("{}: all the rxs are disconnected. Returning...", name);
+                    return None;
+                },
+                None => None
+            };
+
+            let futures = waiting.iter()
+                                 .map(|&idx| self.rxs[idx].recv_async())
+                                 .collect::<Vec<_>>();
+            let timer = match deadline {
+                Some(deadline) => Either::Left(sleep_until(deadline)),
+                None => Either::Right(future::pending())
+            };
+
+            let received = match future::select(future::select_all(futures), Box::pin(timer)).await {
+                Either::Left(((result, index, _), _)) => Some((result, waiting[index])),
+                // The oldest pending object is released on the next iteration
+                Either::Right(_) => None
+            };
+            match received {
+                Some((Ok(obj), idx)) => self.heads[idx] = Some((obj, Instant::now())),
+                Some((Err(e), idx)) => self.disable(name, idx, e),
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::merge::MergePolicy;
+    use crate::inputs::Inputs;
+    use crate::objects::{ Objects, mock::MockV1 };
+    use crate::scheduler::Timers;
+    use crate::utils::uuid;
+
+    use flume::unbounded;
+    use std::sync::Arc;
+
+    fn mock(timestamp: i64) -> Objects {
+        Objects::MockV1(MockV1::new(uuid::uuid(), timestamp, 0, uuid::uuid()))
+    }
+
+    #[tokio::test]
+    async fn test_timestamp_merge() {
+        let (trades_tx, trades_rx) = unbounded();
+        let (books_tx, books_rx) = unbounded();
+        let mut inputs = Inputs::new(vec![Arc::new(trades_rx), Arc::new(books_rx)], MergePolicy::Timestamp { lateness: None }, Timers::default());
+
+        // The trades are all queued before the books, yet they are interleaved by timestamp
+        for timestamp in [1, 4, 5] {
+            trades_tx.send(mock(timestamp)).unwrap();
+        }
+        for timestamp in [2, 3, 6] {
+            books_tx.send(mock(timestamp)).unwrap();
+        }
+        drop(trades_tx);
+        drop(books_tx);
+
+        let mut timestamps = vec![];
+        while let Some(obj) = inputs.recv("Test").await {
+            timestamps.push(obj.get_timestamp());
+        }
+        assert_eq!(timestamps, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_lateness() {
+        let (trades_tx, trades_rx) = unbounded();
+        let (_books_tx, books_rx) = unbounded();
+        let mut inputs = Inputs::new(vec![Arc::new(trades_rx), Arc::new(books_rx)], MergePolicy::Timestamp { lateness: Some(10) }, Timers::default());
+
+        // Books stay silent, so the trade is released once late
+        trades_tx.send(mock(1)).unwrap();
+        let obj = tokio::time::timeout(std::time::Duration::from_secs(1), inputs.recv("Test")).await.unwrap();
+        assert_eq!(obj.map(|obj| obj.get_timestamp()), Some(1));
+    }
+}
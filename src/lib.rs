@@ -1,14 +1,57 @@
 #![feature(generators, generator_trait)]
+pub mod cli;
 pub mod components;
 pub mod config;
+pub mod edge;
+pub mod error;
+pub mod graph;
+pub mod inputs;
+pub mod markets;
+pub mod metrics;
 pub mod objects;
 pub mod processor;
+pub mod scheduler;
+pub mod supervisor;
+pub mod trace;
 pub mod utils;
 
+use crate::cli::{ Cli, Command };
+
+use clap::Parser;
 use std::collections::HashMap;
+use std::sync::Arc;
+
+// The command line level wins over RUST_LOG, which wins over the config level
+fn init_logger(cfg_level: &str, cli_level: Option<&str>) {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(cfg_level));
+    if let Some(level) = cli_level {
+        builder.parse_filters(level);
+    }
+    builder.init();
+}
+
+// Loads and validates a config, exiting on errors
+fn load(path: &str, sets: &[String], registry: &components::registry::Registry) -> config::Config {
+    let cfg = match config::load_with_overrides(path, sets) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
-fn usage() {
-    println!("Usage: \n./main <main class> <config file>");
+    check(path, &cfg, registry);
+    cfg
+}
+
+fn check(path: &str, cfg: &config::Config, registry: &components::registry::Registry) {
+    if let Err(errors) = config::validation::validate(cfg, registry) {
+        println!("Config {} is invalid:", path);
+        for e in errors {
+            println!("  {}", e);
+        }
+        std::process::exit(1);
+    }
 }
 
 fn register(url: &str, cfg: &config::Config) {
@@ -32,15 +75,46 @@ fn register(url: &str, cfg: &config::Config) {
     };
 }
 
-pub fn entry_point(argc: usize, argv: Vec<String>) -> std::io::Result<()> {
-    if argc != 3 {
-        usage();
-        std::process::exit(0);
-    }
+pub fn entry_point(argv: Vec<String>) -> std::io::Result<()> {
+    entry_point_with_registry(argv, components::registry::Registry::new())
+}
 
-    env_logger::init();
+// Same as entry_point, building the components from a registry extended with custom types
+pub fn entry_point_with_registry(argv: Vec<String>, registry: components::registry::Registry) -> std::io::Result<()> {
+    let cli = Cli::parse_from(argv);
+    let cfg = match cli.command {
+        Command::Run { config, sets } => load(&config, &sets, &registry),
+        Command::Validate { config, sets } => {
+            load(&config, &sets, &registry);
+            println!("Config {} is valid", config);
+            return Ok(());
+        },
+        Command::Replay { config, file, format, speed, upcast, source, sets } => {
+            match cli::replay_config(load(&config, &sets, &registry), &file, format, speed, upcast, source.as_deref()) {
+                Ok(cfg) => {
+                    check(&config, &cfg, &registry);
+                    cfg
+                },
+                Err(e) => {
+                    println!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+        Command::Record { streams, output, format, ctrl_port, base_url, snapshot_url } => {
+            let cfg = cli::record_config(&streams, &output, format, ctrl_port, &base_url, &snapshot_url);
+            check("record", &cfg, &registry);
+            cfg
+        },
+        Command::Inspect { file, format } => return cli::inspect(&file, format)
+    };
 
-    let cfg: config::Config = confy::load_path(&argv[2]).expect("Cannot read config file");
+    init_logger(&cfg.log_level, cli.log_level.as_deref());
+    run(cfg, registry);
+    Ok(())
+}
+
+fn run(cfg: config::Config, registry: components::registry::Registry) {
     println!("*** CONFIG ***: \n{}\n*** ****** ***", serde_json::to_string_pretty(&cfg).expect("Cannot serialize Config to Json"));
 
     if let Some(url) = &cfg.discovery_service {
@@ -56,7 +130,10 @@ pub fn entry_point(argc: usize, argv: Vec<String>) -> std::io::Result<()> {
             .expect("Cannot build runtime");
 
     runtime.block_on(async move {
-        let mut processor = processor::Processor::new(&cfg.name, &cfg);
+        let mut processor = match processor::Processor::new(&cfg.name, &cfg, Arc::new(registry)) {
+            Ok(processor) => processor,
+            Err(e) => panic!("Cannot create processor {} - {}", cfg.name, e)
+        };
         match processor.init().await {
             Ok(()) => log::info!("Processor {} initialized successfully", &cfg.name),
             Err(e) => panic!("Cannot initializing processor {} - {}", &cfg.name, e)
@@ -67,6 +144,4 @@ pub fn entry_point(argc: usize, argv: Vec<String>) -> std::io::Result<()> {
             Err(e) => panic!("Processor {} exited with error: {}", &cfg.name, e)
         };
     });
-
-    Ok(())
 }
@@ -0,0 +1,177 @@
+use crate::error::HolocoreError;
+use crate::objects::market::Market;
+
+use std::collections::HashMap;
+use std::sync::{ Arc, Mutex, PoisonError };
+use tokio::sync::{ RwLock, RwLockReadGuard, RwLockWriteGuard, broadcast };
+
+// Notifications a slow reader can fall behind by before it misses some
+const CHANGES_CAPACITY: usize = 1024;
+
+struct SharedMarket {
+    market: Arc<RwLock<Market>>,
+    // Symbols whose book changed
+    changes: broadcast::Sender<String>,
+    // Component allowed to write the market
+    writer: Mutex<Option<String>>
+}
+
+impl SharedMarket {
+    fn new(name: &str) -> Self {
+        Self {
+            market: Arc::new(RwLock::new(Market::new(name))),
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
+            writer: Mutex::new(None)
+        }
+    }
+}
+
+// Markets shared by name between the PUs of a processor, each one built by a single writer
+#[derive(Default)]
+pub struct Markets {
+    markets: Mutex<HashMap<String, Arc<SharedMarket>>>
+}
+
+impl Markets {
+    fn get_or_create(&self, name: &str) -> Arc<SharedMarket> {
+        let mut markets = self.markets.lock().unwrap_or_else(PoisonError::into_inner);
+        markets.entry(String::from(name)).or_insert_with(|| Arc::new(SharedMarket::new(name))).clone()
+    }
+
+    // Fails if another component already writes the market. The same component can claim it
+    // again, e.g. when it restarts.
+    pub fn writer(&self, name: &str, component: &str) -> Result<MarketWriter, HolocoreError> {
+        let shared = self.get_or_create(name);
+        let mut writer = shared.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        match writer.as_deref() {
+            Some(owner) if owner != component => Err(HolocoreError::Config(format!("Market {} is already written by {}", name, owner))),
+            _ => {
+                *writer = Some(String::from(component));
+                Ok(MarketWriter {
+                    market: shared.market.clone(),
+                    changes: shared.changes.clone()
+                })
+            }
+        }
+    }
+
+    // Lets another component claim the market once component stops writing it
+    pub fn release(&self, name: &str, component: &str) {
+        let shared = self.get_or_create(name);
+        let mut writer = shared.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        if writer.as_deref() == Some(component) {
+            *writer = None;
+        }
+    }
+
+    // Readers can subscribe before the writer, they see an empty market until then
+    pub fn reader(&self, name: &str) -> MarketReader {
+        let shared = self.get_or_create(name);
+        MarketReader {
+            market: shared.market.clone(),
+            changes: shared.changes.subscribe()
+        }
+    }
+}
+
+pub struct MarketWriter {
+    market: Arc<RwLock<Market>>,
+    changes: broadcast::Sender<String>
+}
+
+impl MarketWriter {
+    // Market read by nobody else
+    pub fn new(name: &str) -> Self {
+        let shared = SharedMarket::new(name);
+        Self {
+            market: shared.market,
+            changes: shared.changes
+        }
+    }
+
+    pub async fn write(&self) -> RwLockWriteGuard<'_, Market> {
+        self.market.write().await
+    }
+
+    // Tells the readers the book of symbol changed
+    pub fn notify(&self, symbol: &str) {
+        // Fails only if there is no reader
+        let _ = self.changes.send(String::from(symbol));
+    }
+
+    // Getters
+    pub fn get_market(&self) -> Arc<RwLock<Market>> {
+        self.market.clone()
+    }
+}
+
+pub struct MarketReader {
+    market: Arc<RwLock<Market>>,
+    changes: broadcast::Receiver<String>
+}
+
+impl MarketReader {
+    pub async fn read(&self) -> RwLockReadGuard<'_, Market> {
+        self.market.read().await
+    }
+
+    // Waits for the next symbol whose book changed
+    pub async fn changed(&mut self) -> String {
+        loop {
+            match self.changes.recv().await {
+                Ok(symbol) => return symbol,
+                Err(broadcast::error::RecvError::Lagged(missed)) => log::warn!("Market reader missed {} notifications", missed),
+                // The sender lives as long as the market, which the reader keeps alive
+                Err(broadcast::error::RecvError::Closed) => std::future::pending::<()>().await
+            }
+        }
+    }
+
+    // Symbols whose book changed since the last call, without waiting
+    pub fn drain_changes(&mut self) -> Vec<String> {
+        let mut symbols = vec![];
+        loop {
+            match self.changes.try_recv() {
+                Ok(symbol) => if !symbols.contains(&symbol) {
+                    symbols.push(symbol);
+                },
+                Err(broadcast::error::TryRecvError::Lagged(missed)) => log::warn!("Market reader missed {} notifications", missed),
+                Err(_) => return symbols
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::binance::model::BinanceOrderBookEvent;
+    use crate::markets::Markets;
+    use crate::objects::orderbook::OrderbookV2;
+    use crate::utils::uuid;
+
+    #[tokio::test]
+    async fn test_shared_market() {
+        let markets = Markets::default();
+        let mut early = markets.reader("Books");
+        let writer = markets.writer("Books", "OrderbookManager").unwrap();
+        let mut late = markets.reader("Books");
+
+        let event = BinanceOrderBookEvent { last_update_id: 1, bids: vec![], asks: vec![] };
+        let book = OrderbookV2::from_binance(uuid::uuid(), 0, 0, uuid::uuid(), "BinanceSpotSource", "Binance", "Spot", "BTCUSDT", event);
+        writer.write().await.set_book(book);
+        writer.notify("BTCUSDT");
+
+        assert_eq!(early.changed().await, "BTCUSDT");
+        assert_eq!(late.drain_changes(), vec!["BTCUSDT"]);
+        assert!(early.read().await.contains("BTCUSDT"));
+
+        // A single writer per market, which can claim it again after a restart
+        assert!(markets.writer("Books", "OrderbookManager").is_ok());
+        assert!(markets.writer("Books", "OtherManager").is_err());
+        markets.release("Books", "OtherManager");
+        assert!(markets.writer("Books", "OtherManager").is_err());
+        markets.release("Books", "OrderbookManager");
+        assert!(markets.writer("Books", "OtherManager").is_ok());
+        assert!(!markets.reader("Klines").read().await.contains("BTCUSDT"));
+    }
+}
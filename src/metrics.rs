@@ -0,0 +1,191 @@
+use crate::edge::EdgeProbe;
+use crate::supervisor::SupervisorStatus;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{ Arc, atomic::{ AtomicU64, Ordering } };
+
+// Upper bounds of the histogram buckets, in nanoseconds
+const BUCKETS: [u64; 14] = [
+    1_000, 5_000, 10_000, 50_000, 100_000, 500_000,
+    1_000_000, 5_000_000, 10_000_000, 50_000_000, 100_000_000, 500_000_000,
+    1_000_000_000, 5_000_000_000
+];
+
+#[derive(Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS.len()],
+    count: AtomicU64,
+    sum: AtomicU64
+}
+
+impl Histogram {
+    pub fn observe(&self, nanos: u64) {
+        if let Some(idx) = BUCKETS.iter().position(|&bound| nanos <= bound) {
+            self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (idx, bound) in BUCKETS.iter().enumerate() {
+            cumulative += self.buckets[idx].load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, seconds(*bound), cumulative);
+        }
+
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{{},le=\"+Inf\"}} {}", name, labels, count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, seconds(self.sum.load(Ordering::Relaxed)));
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, count);
+    }
+}
+
+fn seconds(nanos: u64) -> f64 {
+    nanos as f64 / 1e9
+}
+
+// Counters of a component, updated by its task
+#[derive(Default)]
+pub struct ComponentMetrics {
+    objs_in: AtomicU64,
+    objs_out: AtomicU64,
+    // Emitted to a port no downstream is bound to
+    objs_unrouted: AtomicU64,
+    errors: AtomicU64,
+    // Time spent in execute for PUs and in send for sinks
+    duration: Histogram,
+    // From the object timestamp to the moment a sink has sent it, by the processor clock
+    latency: Histogram
+}
+
+impl ComponentMetrics {
+    pub fn received(&self) {
+        self.objs_in.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn emitted(&self, objs: u64) {
+        self.objs_out.fetch_add(objs, Ordering::Relaxed);
+    }
+
+    pub fn unrouted(&self) {
+        self.objs_unrouted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn failed(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn observe_duration(&self, nanos: u64) {
+        self.duration.observe(nanos);
+    }
+
+    pub fn observe_latency(&self, nanos: u64) {
+        self.latency.observe(nanos);
+    }
+
+    // Getters
+    pub fn get_objs_in(&self) -> u64 {
+        self.objs_in.load(Ordering::Relaxed)
+    }
+
+    pub fn get_objs_out(&self) -> u64 {
+        self.objs_out.load(Ordering::Relaxed)
+    }
+
+    pub fn get_objs_unrouted(&self) -> u64 {
+        self.objs_unrouted.load(Ordering::Relaxed)
+    }
+
+    pub fn get_errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+}
+
+type Counter = fn(&ComponentMetrics) -> u64;
+
+// Renders the pipeline metrics in the Prometheus text format
+pub fn render(processor: &str,
+              components: &HashMap<String, Arc<ComponentMetrics>>,
+              statuses: &HashMap<String, Arc<SupervisorStatus>>,
+              edges: &[EdgeProbe]) -> String {
+    let mut out = String::new();
+    let mut names = components.keys().collect::<Vec<_>>();
+    names.sort();
+
+    let counters: [(&str, &str, Counter); 4] = [
+        ("holocore_objects_in_total", "Objects received by a component", ComponentMetrics::get_objs_in),
+        ("holocore_objects_out_total", "Objects emitted by a component", ComponentMetrics::get_objs_out),
+        ("holocore_objects_unrouted_total", "Objects emitted by a component to a port without downstreams", ComponentMetrics::get_objs_unrouted),
+        ("holocore_errors_total", "Errors returned by a component", ComponentMetrics::get_errors)
+    ];
+
+    for (metric, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", metric, help, metric);
+        for name in &names {
+            let _ = writeln!(out, "{}{{processor=\"{}\",component=\"{}\"}} {}", metric, processor, name, value(&components[*name]));
+        }
+    }
+
+    let _ = writeln!(out, "# HELP holocore_restarts_total Restarts of a component by its supervisor\n# TYPE holocore_restarts_total counter");
+    for name in &names {
+        let restarts = statuses.get(*name).map(|status| status.report().restarts).unwrap_or(0);
+        let _ = writeln!(out, "holocore_restarts_total{{processor=\"{}\",component=\"{}\"}} {}", processor, name, restarts);
+    }
+
+    let _ = writeln!(out, "# HELP holocore_edge_queue_depth Objects waiting in an edge queue\n# TYPE holocore_edge_queue_depth gauge");
+    for edge in edges {
+        let _ = writeln!(out, "holocore_edge_queue_depth{{processor=\"{}\",upstream=\"{}\",downstream=\"{}\"}} {}", processor, edge.get_upstream(), edge.get_downstream(), edge.get_depth());
+    }
+
+    let _ = writeln!(out, "# HELP holocore_edge_dropped_total Objects dropped by an edge back-pressure policy\n# TYPE holocore_edge_dropped_total counter");
+    for edge in edges {
+        let _ = writeln!(out, "holocore_edge_dropped_total{{processor=\"{}\",upstream=\"{}\",downstream=\"{}\"}} {}", processor, edge.get_upstream(), edge.get_downstream(), edge.get_dropped());
+    }
+
+    let _ = writeln!(out, "# HELP holocore_duration_seconds Time spent in execute for PUs and in send for sinks\n# TYPE holocore_duration_seconds histogram");
+    for name in &names {
+        components[*name].duration.render(&mut out, "holocore_duration_seconds", &format!("processor=\"{}\",component=\"{}\"", processor, name));
+    }
+
+    let _ = writeln!(out, "# HELP holocore_latency_seconds Time from the object timestamp to the end of a sink send\n# TYPE holocore_latency_seconds histogram");
+    for name in &names {
+        components[*name].latency.render(&mut out, "holocore_latency_seconds", &format!("processor=\"{}\",component=\"{}\"", processor, name));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::{ ComponentMetrics, render };
+
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_render() {
+        let sink = Arc::new(ComponentMetrics::default());
+        sink.received();
+        sink.received();
+        sink.failed();
+        sink.unrouted();
+        sink.observe_duration(2_000);
+        sink.observe_latency(20_000_000);
+
+        let mut components = HashMap::new();
+        components.insert(String::from("FileSink"), sink);
+
+        let text = render("Test", &components, &HashMap::new(), &[]);
+        assert!(text.contains("holocore_objects_in_total{processor=\"Test\",component=\"FileSink\"} 2\n"));
+        assert!(text.contains("holocore_errors_total{processor=\"Test\",component=\"FileSink\"} 1\n"));
+        assert!(text.contains("holocore_objects_unrouted_total{processor=\"Test\",component=\"FileSink\"} 1\n"));
+        assert!(text.contains("holocore_restarts_total{processor=\"Test\",component=\"FileSink\"} 0\n"));
+        assert!(text.contains("holocore_duration_seconds_bucket{processor=\"Test\",component=\"FileSink\",le=\"0.000001\"} 0\n"));
+        assert!(text.contains("holocore_duration_seconds_bucket{processor=\"Test\",component=\"FileSink\",le=\"0.000005\"} 1\n"));
+        assert!(text.contains("holocore_latency_seconds_bucket{processor=\"Test\",component=\"FileSink\",le=\"0.01\"} 0\n"));
+        assert!(text.contains("holocore_latency_seconds_bucket{processor=\"Test\",component=\"FileSink\",le=\"0.05\"} 1\n"));
+        assert!(text.contains("holocore_latency_seconds_count{processor=\"Test\",component=\"FileSink\"} 1\n"));
+    }
+}
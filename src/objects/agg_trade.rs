@@ -0,0 +1,150 @@
+use crate::components::sources::binance::model::BinanceAggTradeEvent;
+use crate::objects::common::parse_number;
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Price, Quantity };
+use crate::objects::schema;
+
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+// Trades of a single taker order at the same price
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AggTradeV1 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    source: String,
+    exchange: String,
+    asset_type: String,
+
+    event_time: u64,
+    symbol: String,
+    agg_trade_id: u64,
+    price: Price,
+    quantity: Quantity,
+    first_trade_id: u64,
+    last_trade_id: u64,
+    trade_time: u64,
+    is_buyer_maker: bool
+}
+
+impl AggTradeV1 {
+    pub fn from_binance(id: Uuid,
+                        timestamp: i64,
+                        sequence_nr: u64,
+                        correlation_id: Uuid,
+                        source: &str,
+                        asset_type: &str,
+                        event: BinanceAggTradeEvent) -> Self {
+        Self {
+            id,
+            timestamp,
+            sequence_nr,
+            correlation_id,
+            hops: None,
+            source: String::from(source),
+            exchange: String::from("Binance"),
+            asset_type: String::from(asset_type),
+            event_time: event.event_time,
+            symbol: event.symbol,
+            agg_trade_id: event.agg_trade_id,
+            price: parse_number("aggregate trade price", &event.price),
+            quantity: parse_number("aggregate trade quantity", &event.qty),
+            first_trade_id: event.first_trade_id,
+            last_trade_id: event.last_trade_id,
+            trade_time: event.trade_time,
+            is_buyer_maker: event.is_buyer_maker
+        }
+    }
+
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::AGG_TRADE
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn get_exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    pub fn get_asset_type(&self) -> &str {
+        &self.asset_type
+    }
+
+    // Time the exchange sent the event, in milliseconds
+    pub fn get_event_time(&self) -> u64 {
+        self.event_time
+    }
+
+    pub fn get_agg_trade_id(&self) -> u64 {
+        self.agg_trade_id
+    }
+
+    pub fn get_price(&self) -> Price {
+        self.price
+    }
+
+    pub fn get_quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    // Range of the trades aggregated, both included
+    pub fn get_first_trade_id(&self) -> u64 {
+        self.first_trade_id
+    }
+
+    pub fn get_last_trade_id(&self) -> u64 {
+        self.last_trade_id
+    }
+
+    // Time of the trades, in milliseconds
+    pub fn get_trade_time(&self) -> u64 {
+        self.trade_time
+    }
+
+    pub fn is_buyer_maker(&self) -> bool {
+        self.is_buyer_maker
+    }
+}
@@ -0,0 +1,165 @@
+use crate::components::sources::binance::model::BinanceBookTickerEvent;
+use crate::objects::common::parse_number;
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Price, Quantity };
+use crate::objects::schema;
+
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+// Best bid and ask of a symbol, for when a full depth book is not needed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BookTickerV1 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    source: String,
+    exchange: String,
+    asset_type: String,
+
+    symbol: String,
+    update_id: u64,
+    bid_price: Price,
+    bid_quantity: Quantity,
+    ask_price: Price,
+    ask_quantity: Quantity
+}
+
+impl BookTickerV1 {
+    pub fn from_binance(id: Uuid,
+                        timestamp: i64,
+                        sequence_nr: u64,
+                        correlation_id: Uuid,
+                        source: &str,
+                        asset_type: &str,
+                        event: BinanceBookTickerEvent) -> Self {
+        Self {
+            id,
+            timestamp,
+            sequence_nr,
+            correlation_id,
+            hops: None,
+            source: String::from(source),
+            exchange: String::from("Binance"),
+            asset_type: String::from(asset_type),
+            symbol: event.symbol,
+            update_id: event.update_id,
+            bid_price: parse_number("book ticker bid price", &event.bid_price),
+            bid_quantity: parse_number("book ticker bid quantity", &event.bid_qty),
+            ask_price: parse_number("book ticker ask price", &event.ask_price),
+            ask_quantity: parse_number("book ticker ask quantity", &event.ask_qty)
+        }
+    }
+
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::BOOK_TICKER
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn get_exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    pub fn get_asset_type(&self) -> &str {
+        &self.asset_type
+    }
+
+    // Increases with every change of the top of the book
+    pub fn get_update_id(&self) -> u64 {
+        self.update_id
+    }
+
+    pub fn get_bid_price(&self) -> Price {
+        self.bid_price
+    }
+
+    pub fn get_bid_quantity(&self) -> Quantity {
+        self.bid_quantity
+    }
+
+    pub fn get_ask_price(&self) -> Price {
+        self.ask_price
+    }
+
+    pub fn get_ask_quantity(&self) -> Quantity {
+        self.ask_quantity
+    }
+
+    // Analytics, None when they overflow
+    pub fn spread(&self) -> Option<Price> {
+        self.ask_price.checked_sub(self.bid_price)
+    }
+
+    pub fn mid(&self) -> Option<Price> {
+        self.ask_price.checked_add(self.bid_price)?.checked_div_int(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::binance::model::BinanceBookTickerEvent;
+    use crate::objects::{ Objects, book_ticker::BookTickerV1, numeric::Price };
+    use crate::utils::binary::{ self, HEADER_SIZE, Header };
+    use crate::utils::uuid;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_book_ticker() {
+        let event = serde_json::from_str::<BinanceBookTickerEvent>(r#"{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#).unwrap();
+        let ticker = BookTickerV1::from_binance(uuid::uuid(), 0, 1, uuid::uuid(), "BinanceSpotSource", "Spot", event);
+        assert_eq!(ticker.spread(), Price::from_str("0.0133").ok());
+        assert_eq!(ticker.mid(), Price::from_str("25.35855").ok());
+
+        let obj = Objects::BookTickerV1(ticker);
+        let record = binary::encode(&obj).unwrap();
+        let header = Header::parse(record[..HEADER_SIZE].try_into().unwrap()).unwrap();
+        match binary::decode(&header, &record[HEADER_SIZE..]).unwrap() {
+            Objects::BookTickerV1(decoded) => assert_eq!((decoded.get_update_id(), decoded.get_bid_quantity().to_string()), (400900217, String::from("31.21"))),
+            obj => panic!("Expected BookTickerV1, got {}", obj.get_type_name())
+        }
+    }
+}
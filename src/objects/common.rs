@@ -1,72 +1,132 @@
+use crate::objects::numeric::{ Fixed, Price, Quantity };
+
 use fixed::FixedU64;
-use serde::{ Deserializer, Serializer, ser::SerializeMap, de::Visitor, de::MapAccess };
+use serde::{ Deserialize, Deserializer, Serialize, Serializer, ser::{ self, SerializeMap }, de::Visitor, de::MapAccess };
 use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Parses a decimal sent by the exchange, zero if it does not fit
+pub fn parse_number<T>(field: &str, value: &str) -> T
+where
+    T: FromStr + Default,
+    T::Err: Display
+{
+    match T::from_str(value) {
+        Ok(value) => value,
+        Err(e) => {
+            log::error!("Failed to parse {} {} - {}", field, value, e);
+            T::default()
+        }
+    }
+}
+
+// Binary layout of the versions recorded before the numeric types: the bits of a FixedU64<32>.
+// Only the V1 orders and books still use it so that their recordings decode, the newer versions
+// store the bits of the numeric types.
+type LegacyFixed = FixedU64<32>;
+
+fn to_legacy<T, E>(value: T) -> Result<u64, E>
+where
+    T: Into<Fixed>,
+    E: ser::Error
+{
+    let value: Fixed = value.into();
+    match LegacyFixed::checked_from_num(value) {
+        Some(legacy) => Ok(legacy.to_bits()),
+        None => Err(E::custom(format!("{} does not fit the recorded layout", value)))
+    }
+}
+
+fn from_legacy<T: From<Fixed>>(bits: u64) -> T {
+    T::from(Fixed::from_num(LegacyFixed::from_bits(bits)))
+}
 
 // Serializers
-pub fn serialize_fixed<S>(value: &FixedU64<32>, serializer: S) -> Result<S::Ok, S::Error>
+pub fn serialize_legacy<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
 where
+    T: Copy + Into<Fixed> + Serialize,
     S: Serializer
 {
-    serializer.serialize_str(&value.to_string())
+    if !serializer.is_human_readable() {
+        return serializer.serialize_u64(to_legacy(*value)?);
+    }
+
+    value.serialize(serializer)
 }
 
-pub fn serialize_bids<S>(levels: &BTreeMap<FixedU64<32>, FixedU64<32>>, serializer: S) -> Result<S::Ok, S::Error>
+fn serialize_legacy_levels<S>(levels: &BTreeMap<Price, Quantity>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer
 {
     let mut map = serializer.serialize_map(Some(levels.len()))?;
-    for (k, v) in levels.iter().rev() {
-        map.serialize_entry(&k.to_string(), &v.to_string())?;
+    for (k, v) in levels.iter() {
+        map.serialize_entry(&to_legacy::<_, S::Error>(*k)?, &to_legacy::<_, S::Error>(*v)?)?;
     }
     map.end()
 }
 
-pub fn serialize_asks<S>(levels: &BTreeMap<FixedU64<32>, FixedU64<32>>, serializer: S) -> Result<S::Ok, S::Error>
+pub fn serialize_legacy_bids<S>(levels: &BTreeMap<Price, Quantity>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer
 {
-    let mut map = serializer.serialize_map(Some(levels.len()))?;
-    for (k, v) in levels.iter() {
-        map.serialize_entry(&k.to_string(), &v.to_string())?;
+    if !serializer.is_human_readable() {
+        return serialize_legacy_levels(levels, serializer);
     }
-    map.end()
+
+    serialize_bids(levels, serializer)
 }
 
-// Deserializers
-struct DeserializerFixedVisitor;
+pub fn serialize_legacy_asks<S>(levels: &BTreeMap<Price, Quantity>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer
+{
+    if !serializer.is_human_readable() {
+        return serialize_legacy_levels(levels, serializer);
+    }
 
-impl<'de> Visitor<'de> for DeserializerFixedVisitor {
-    type Value = FixedU64<32>;
+    serialize_asks(levels, serializer)
+}
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("&str to FixedU64")
+pub fn serialize_bids<S>(levels: &BTreeMap<Price, Quantity>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer
+{
+    if !serializer.is_human_readable() {
+        return levels.serialize(serializer);
     }
 
-    fn visit_str<D>(self, v: &str) -> Result<Self::Value, D>
-    where
-        D: serde::de::Error
-    {
-        match FixedU64::<32>::from_str(v) {
-            Ok(value) => Ok(value),
-            Err(e) => {
-                log::error!("DeserializerFixedVisitor: cannot deserialize {} - {}", v, e);
-                Ok(FixedU64::<32>::from_num(0))
-            }
-        }
+    let mut map = serializer.serialize_map(Some(levels.len()))?;
+    for (k, v) in levels.iter().rev() {
+        map.serialize_entry(k, v)?;
     }
+    map.end()
 }
 
-pub fn deserialize_fixed<'de, D>(deserializer: D) -> Result<FixedU64<32>, D::Error>
+pub fn serialize_asks<S>(levels: &BTreeMap<Price, Quantity>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer
+{
+    levels.serialize(serializer)
+}
+
+// Deserializers
+pub fn deserialize_legacy<'de, T, D>(deserializer: D) -> Result<T, D::Error>
 where
+    T: From<Fixed> + Deserialize<'de>,
     D: Deserializer<'de>
 {
-    deserializer.deserialize_str(DeserializerFixedVisitor)
+    if !deserializer.is_human_readable() {
+        return u64::deserialize(deserializer).map(from_legacy);
+    }
+
+    T::deserialize(deserializer)
 }
 
 struct DeserializerVisitor;
 
 impl<'de> Visitor<'de> for DeserializerVisitor {
-    type Value = BTreeMap<FixedU64<32>, FixedU64<32>>;
+    type Value = BTreeMap<Price, Quantity>;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
         formatter.write_str("Map of Strings to BTreeMap")
@@ -76,10 +136,10 @@ impl<'de> Visitor<'de> for DeserializerVisitor {
     where
         A: MapAccess<'de>,
     {
-        let mut values = BTreeMap::<FixedU64<32>, FixedU64<32>>::new();
+        let mut values = BTreeMap::<Price, Quantity>::new();
 
         while let Some((key, value)) = access.next_entry()?
-            .map(|(k, v): (String, String)| (FixedU64::<32>::from_str(&k), FixedU64::<32>::from_str(&v))) {
+            .map(|(k, v): (String, String)| (Price::from_str(&k), Quantity::from_str(&v))) {
                 if let Ok(key) = key {
                     if let Ok(value) = value {
                        values.insert(key, value);
@@ -91,16 +151,25 @@ impl<'de> Visitor<'de> for DeserializerVisitor {
     }
 }
 
-pub fn deserialize_bids<'de, D>(deserializer: D) -> Result<BTreeMap<FixedU64<32>, FixedU64<32>>, D::Error>
+pub fn deserialize_legacy_levels<'de, D>(deserializer: D) -> Result<BTreeMap<Price, Quantity>, D::Error>
 where
     D: Deserializer<'de>
 {
+    if !deserializer.is_human_readable() {
+        let levels = BTreeMap::<u64, u64>::deserialize(deserializer)?;
+        return Ok(levels.into_iter().map(|(k, v)| (from_legacy(k), from_legacy(v))).collect());
+    }
+
     deserializer.deserialize_map(DeserializerVisitor)
 }
 
-pub fn deserialize_asks<'de, D>(deserializer: D) -> Result<BTreeMap<FixedU64<32>, FixedU64<32>>, D::Error>
+pub fn deserialize_levels<'de, D>(deserializer: D) -> Result<BTreeMap<Price, Quantity>, D::Error>
 where
     D: Deserializer<'de>
 {
+    if !deserializer.is_human_readable() {
+        return BTreeMap::<Price, Quantity>::deserialize(deserializer);
+    }
+
     deserializer.deserialize_map(DeserializerVisitor)
 }
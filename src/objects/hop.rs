@@ -0,0 +1,28 @@
+use crate::utils::time::monotonic_nanos;
+
+use serde::{ Deserialize, Serialize };
+
+// Stamp left by a component as an object enters or leaves it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Hop {
+    component: String,
+    timestamp: u64
+}
+
+impl Hop {
+    pub fn new(component: &str) -> Self {
+        Self {
+            component: String::from(component),
+            timestamp: monotonic_nanos()
+        }
+    }
+
+    // Getters
+    pub fn get_component(&self) -> &str {
+        &self.component
+    }
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
@@ -1,4 +1,8 @@
 use crate::components::sources::binance::model::BinanceKlineEvent;
+use crate::objects::common::parse_number;
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Notional, Price, Quantity };
+use crate::objects::schema;
 
 use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
@@ -9,6 +13,8 @@ pub struct KlineV1 {
     timestamp: i64,
     sequence_nr: u64,
     correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
     source: String,
     exchange: String,
     asset_type: String,
@@ -47,6 +53,7 @@ impl KlineV1 {
             timestamp,
             sequence_nr,
             correlation_id,
+            hops: None,
             source: String::from(source),
             exchange: String::from(exchange),
             asset_type: String::from(asset_type),
@@ -73,7 +80,7 @@ impl KlineV1 {
 
     // Getters
     pub fn get_type(&self) -> u64 {
-        16
+        schema::KLINE
     }
 
     pub fn get_version(&self) -> u64 {
@@ -95,4 +102,212 @@ impl KlineV1 {
     pub  fn get_correlation_id(&self) -> Uuid {
         self.correlation_id
     }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+}
+
+// KlineV1 with the prices and the volumes parsed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KlineV2 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    source: String,
+    exchange: String,
+    asset_type: String,
+
+    event_time: u64,
+    open_time: i64,
+    close_time: i64,
+    symbol: String,
+    interval: String,
+    first_trade_id: i64,
+    last_trade_id: i64,
+    open: Price,
+    close: Price,
+    high: Price,
+    low: Price,
+    volume: Quantity,
+    number_of_trades: i64,
+    is_final_bar: bool,
+    quote_asset_volume: Notional,
+    taker_buy_base_asset_volume: Quantity,
+    taker_buy_quote_asset_volume: Notional
+}
+
+impl KlineV2 {
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::KLINE
+    }
+
+    pub fn get_version(&self) -> u64 {
+        2
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn get_exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    pub fn get_asset_type(&self) -> &str {
+        &self.asset_type
+    }
+
+    // Time the exchange sent the event, in milliseconds
+    pub fn get_event_time(&self) -> u64 {
+        self.event_time
+    }
+
+    // Bounds of the bar, in milliseconds
+    pub fn get_open_time(&self) -> i64 {
+        self.open_time
+    }
+
+    pub fn get_close_time(&self) -> i64 {
+        self.close_time
+    }
+
+    // Length of the bar, e.g. 1m or 1h
+    pub fn get_interval(&self) -> &str {
+        &self.interval
+    }
+
+    pub fn get_first_trade_id(&self) -> i64 {
+        self.first_trade_id
+    }
+
+    pub fn get_last_trade_id(&self) -> i64 {
+        self.last_trade_id
+    }
+
+    pub fn get_open(&self) -> Price {
+        self.open
+    }
+
+    pub fn get_close(&self) -> Price {
+        self.close
+    }
+
+    pub fn get_high(&self) -> Price {
+        self.high
+    }
+
+    pub fn get_low(&self) -> Price {
+        self.low
+    }
+
+    // Volume in the base asset
+    pub fn get_volume(&self) -> Quantity {
+        self.volume
+    }
+
+    pub fn get_number_of_trades(&self) -> i64 {
+        self.number_of_trades
+    }
+
+    // Whether the bar has closed, it is updated until then
+    pub fn is_final_bar(&self) -> bool {
+        self.is_final_bar
+    }
+
+    pub fn get_quote_asset_volume(&self) -> Notional {
+        self.quote_asset_volume
+    }
+
+    pub fn get_taker_buy_base_asset_volume(&self) -> Quantity {
+        self.taker_buy_base_asset_volume
+    }
+
+    pub fn get_taker_buy_quote_asset_volume(&self) -> Notional {
+        self.taker_buy_quote_asset_volume
+    }
+}
+
+impl From<KlineV1> for KlineV2 {
+    fn from(obj: KlineV1) -> Self {
+        Self {
+            id: obj.id,
+            timestamp: obj.timestamp,
+            sequence_nr: obj.sequence_nr,
+            correlation_id: obj.correlation_id,
+            hops: obj.hops,
+            source: obj.source,
+            exchange: obj.exchange,
+            asset_type: obj.asset_type,
+            event_time: obj.event_time,
+            open_time: obj.open_time,
+            close_time: obj.close_time,
+            symbol: obj.symbol,
+            interval: obj.interval,
+            first_trade_id: obj.first_trade_id,
+            last_trade_id: obj.last_trade_id,
+            open: parse_number("kline open", &obj.open),
+            close: parse_number("kline close", &obj.close),
+            high: parse_number("kline high", &obj.high),
+            low: parse_number("kline low", &obj.low),
+            volume: parse_number("kline volume", &obj.volume),
+            number_of_trades: obj.number_of_trades,
+            is_final_bar: obj.is_final_bar,
+            quote_asset_volume: parse_number("kline quote asset volume", &obj.quote_asset_volume),
+            taker_buy_base_asset_volume: parse_number("kline taker buy base asset volume", &obj.taker_buy_base_asset_volume),
+            taker_buy_quote_asset_volume: parse_number("kline taker buy quote asset volume", &obj.taker_buy_quote_asset_volume)
+        }
+    }
 }
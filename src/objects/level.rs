@@ -1,21 +1,34 @@
-use crate::objects::common::{ deserialize_fixed, serialize_fixed };
+use crate::objects::common::{ deserialize_legacy, serialize_legacy };
+use crate::objects::numeric::{ Price, Quantity };
 
-use fixed::FixedU64;
 use serde::{ Deserialize, Serialize };
 
+// Level of an OrderbookUpdateV1, recorded with the bits of a FixedU64<32>
 #[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct Level {
-    #[serde(serialize_with = "serialize_fixed", deserialize_with = "deserialize_fixed")]
-    pub price: FixedU64<32>,
-    #[serde(serialize_with = "serialize_fixed", deserialize_with = "deserialize_fixed")]
-    pub quantity: FixedU64<32>
+pub struct LevelV1 {
+    #[serde(serialize_with = "serialize_legacy", deserialize_with = "deserialize_legacy")]
+    pub price: Price,
+    #[serde(serialize_with = "serialize_legacy", deserialize_with = "deserialize_legacy")]
+    pub quantity: Quantity
 }
 
-impl Level {
-    pub fn new(price: FixedU64<32>, quantity: FixedU64<32>) -> Self {
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LevelV2 {
+    pub price: Price,
+    pub quantity: Quantity
+}
+
+impl LevelV2 {
+    pub fn new(price: Price, quantity: Quantity) -> Self {
         Self {
             price,
             quantity
         }
     }
 }
+
+impl From<LevelV1> for LevelV2 {
+    fn from(level: LevelV1) -> Self {
+        Self::new(level.price, level.quantity)
+    }
+}
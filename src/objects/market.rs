@@ -1,10 +1,10 @@
-use crate::objects::orderbook::OrderbookV1;
+use crate::objects::orderbook::OrderbookV2;
 
 use std::collections::HashMap;
 
 pub struct Market {
     name: String,
-    books: HashMap<String, OrderbookV1>,
+    books: HashMap<String, OrderbookV2>,
     status: HashMap<String, bool>
 }
 
@@ -12,7 +12,7 @@ impl Market {
     pub fn new(name: &str) -> Self {
         Self {
             name: String::from(name),
-            books: HashMap::<String, OrderbookV1>::new(),
+            books: HashMap::<String, OrderbookV2>::new(),
             status: HashMap::<String, bool>::new()
         }
     }
@@ -26,7 +26,7 @@ impl Market {
         false
     }
 
-    pub fn get_book(&self, symbol: &str) -> Option<&OrderbookV1> {
+    pub fn get_book(&self, symbol: &str) -> Option<&OrderbookV2> {
         if let Some(book) = self.books.get(symbol) {
             return Some(book);
         }
@@ -35,7 +35,7 @@ impl Market {
         None
     }
 
-    pub fn get_mut_book(&mut self, symbol: &str) -> Option<&mut OrderbookV1> {
+    pub fn get_mut_book(&mut self, symbol: &str) -> Option<&mut OrderbookV2> {
         if let Some(book) = self.books.get_mut(symbol) {
             return Some(book);
         }
@@ -44,7 +44,7 @@ impl Market {
         None
     }
 
-    pub fn set_book(&mut self, new_book: OrderbookV1) {
+    pub fn set_book(&mut self, new_book: OrderbookV2) {
         self.books.insert(String::from(&new_book.symbol), new_book);
     }
 
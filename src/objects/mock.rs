@@ -1,3 +1,6 @@
+use crate::objects::hop::Hop;
+use crate::objects::schema;
+
 use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 
@@ -6,7 +9,9 @@ pub struct MockV1 {
     id: Uuid,
     timestamp: i64,
     sequence_number: u64,
-    correlation_id: Uuid
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>
 }
 
 impl MockV1 {
@@ -15,13 +20,14 @@ impl MockV1 {
             id,
             timestamp,
             sequence_number,
-            correlation_id
+            correlation_id,
+            hops: None
         }
     }
 
     // Getters
     pub fn get_type(&self) -> u64 {
-        1
+        schema::MOCK
     }
 
     pub fn get_version(&self) -> u64 {
@@ -43,4 +49,16 @@ impl MockV1 {
     pub fn get_correlation_id(&self) -> Uuid {
         self.correlation_id
     }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
 }
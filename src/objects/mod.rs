@@ -1,13 +1,22 @@
+pub mod agg_trade;
+pub mod book_ticker;
 pub mod common;
+pub mod hop;
 pub mod mock;
 pub mod kline;
 pub mod level;
 pub mod market;
 pub mod new_order;
+pub mod numeric;
 pub mod orderbook;
 pub mod orderbook_update;
+pub mod schema;
+pub mod ticker;
+pub mod timer;
 pub mod trade;
 
+use crate::objects::hop::Hop;
+
 use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 
@@ -15,21 +24,59 @@ use uuid::Uuid;
 pub enum Objects {
     MockV1(mock::MockV1),
     KlineV1(kline::KlineV1),
+    KlineV2(kline::KlineV2),
     NewOrderV1(new_order::NewOrderV1),
+    NewOrderV2(new_order::NewOrderV2),
     OrderbookV1(orderbook::OrderbookV1),
+    OrderbookV2(orderbook::OrderbookV2),
     OrderbookUpdateV1(orderbook_update::OrderbookUpdateV1),
-    TradeV1(trade::TradeV1)
+    OrderbookUpdateV2(orderbook_update::OrderbookUpdateV2),
+    TimerV1(timer::TimerV1),
+    TradeV1(trade::TradeV1),
+    TradeV2(trade::TradeV2),
+    AggTradeV1(agg_trade::AggTradeV1),
+    BookTickerV1(book_ticker::BookTickerV1),
+    TickerV1(ticker::TickerV1)
 }
 
 impl Objects {
+    pub fn get_type_name(&self) -> &'static str {
+        match self {
+            Self::MockV1(_) => "MockV1",
+            Self::KlineV1(_) => "KlineV1",
+            Self::KlineV2(_) => "KlineV2",
+            Self::NewOrderV1(_) => "NewOrderV1",
+            Self::NewOrderV2(_) => "NewOrderV2",
+            Self::OrderbookV1(_) => "OrderbookV1",
+            Self::OrderbookV2(_) => "OrderbookV2",
+            Self::OrderbookUpdateV1(_) => "OrderbookUpdateV1",
+            Self::OrderbookUpdateV2(_) => "OrderbookUpdateV2",
+            Self::TimerV1(_) => "TimerV1",
+            Self::TradeV1(_) => "TradeV1",
+            Self::TradeV2(_) => "TradeV2",
+            Self::AggTradeV1(_) => "AggTradeV1",
+            Self::BookTickerV1(_) => "BookTickerV1",
+            Self::TickerV1(_) => "TickerV1"
+        }
+    }
+
     pub fn get_type(&self) -> u64 {
         match self {
             Self::MockV1(obj) => obj.get_type(),
             Self::KlineV1(obj) => obj.get_type(),
+            Self::KlineV2(obj) => obj.get_type(),
             Self::NewOrderV1(obj) => obj.get_type(),
+            Self::NewOrderV2(obj) => obj.get_type(),
             Self::OrderbookV1(obj) => obj.get_type(),
+            Self::OrderbookV2(obj) => obj.get_type(),
             Self::OrderbookUpdateV1(obj) => obj.get_type(),
-            Self::TradeV1(obj) => obj.get_type()
+            Self::OrderbookUpdateV2(obj) => obj.get_type(),
+            Self::TimerV1(obj) => obj.get_type(),
+            Self::TradeV1(obj) => obj.get_type(),
+            Self::TradeV2(obj) => obj.get_type(),
+            Self::AggTradeV1(obj) => obj.get_type(),
+            Self::BookTickerV1(obj) => obj.get_type(),
+            Self::TickerV1(obj) => obj.get_type()
         }
     }
 
@@ -37,10 +84,19 @@ impl Objects {
         match self {
             Self::MockV1(obj) => obj.get_version(),
             Self::KlineV1(obj) => obj.get_version(),
+            Self::KlineV2(obj) => obj.get_version(),
             Self::NewOrderV1(obj) => obj.get_version(),
+            Self::NewOrderV2(obj) => obj.get_version(),
             Self::OrderbookV1(obj) => obj.get_version(),
+            Self::OrderbookV2(obj) => obj.get_version(),
             Self::OrderbookUpdateV1(obj) => obj.get_version(),
-            Self::TradeV1(obj) => obj.get_version()
+            Self::OrderbookUpdateV2(obj) => obj.get_version(),
+            Self::TimerV1(obj) => obj.get_version(),
+            Self::TradeV1(obj) => obj.get_version(),
+            Self::TradeV2(obj) => obj.get_version(),
+            Self::AggTradeV1(obj) => obj.get_version(),
+            Self::BookTickerV1(obj) => obj.get_version(),
+            Self::TickerV1(obj) => obj.get_version()
         }
     }
 
@@ -48,10 +104,19 @@ impl Objects {
         match self {
             Self::MockV1(obj) => obj.get_id(),
             Self::KlineV1(obj) => obj.get_id(),
+            Self::KlineV2(obj) => obj.get_id(),
             Self::NewOrderV1(obj) => obj.get_id(),
+            Self::NewOrderV2(obj) => obj.get_id(),
             Self::OrderbookV1(obj) => obj.get_id(),
+            Self::OrderbookV2(obj) => obj.get_id(),
             Self::OrderbookUpdateV1(obj) => obj.get_id(),
-            Self::TradeV1(obj) => obj.get_id()
+            Self::OrderbookUpdateV2(obj) => obj.get_id(),
+            Self::TimerV1(obj) => obj.get_id(),
+            Self::TradeV1(obj) => obj.get_id(),
+            Self::TradeV2(obj) => obj.get_id(),
+            Self::AggTradeV1(obj) => obj.get_id(),
+            Self::BookTickerV1(obj) => obj.get_id(),
+            Self::TickerV1(obj) => obj.get_id()
         }
     }
 
@@ -59,10 +124,19 @@ impl Objects {
         match self {
             Self::MockV1(obj) => obj.get_timestamp(),
             Self::KlineV1(obj) => obj.get_timestamp(),
+            Self::KlineV2(obj) => obj.get_timestamp(),
             Self::NewOrderV1(obj) => obj.get_timestamp(),
+            Self::NewOrderV2(obj) => obj.get_timestamp(),
             Self::OrderbookV1(obj) => obj.get_timestamp(),
+            Self::OrderbookV2(obj) => obj.get_timestamp(),
             Self::OrderbookUpdateV1(obj) => obj.get_timestamp(),
-            Self::TradeV1(obj) => obj.get_timestamp()
+            Self::OrderbookUpdateV2(obj) => obj.get_timestamp(),
+            Self::TimerV1(obj) => obj.get_timestamp(),
+            Self::TradeV1(obj) => obj.get_timestamp(),
+            Self::TradeV2(obj) => obj.get_timestamp(),
+            Self::AggTradeV1(obj) => obj.get_timestamp(),
+            Self::BookTickerV1(obj) => obj.get_timestamp(),
+            Self::TickerV1(obj) => obj.get_timestamp()
         }
     }
 
@@ -70,10 +144,19 @@ impl Objects {
         match self {
             Self::MockV1(obj) => obj.get_sequence_number(),
             Self::KlineV1(obj) => obj.get_sequence_number(),
+            Self::KlineV2(obj) => obj.get_sequence_number(),
             Self::NewOrderV1(obj) => obj.get_sequence_number(),
+            Self::NewOrderV2(obj) => obj.get_sequence_number(),
             Self::OrderbookV1(obj) => obj.get_sequence_number(),
+            Self::OrderbookV2(obj) => obj.get_sequence_number(),
             Self::OrderbookUpdateV1(obj) => obj.get_sequence_number(),
-            Self::TradeV1(obj) => obj.get_sequence_number()
+            Self::OrderbookUpdateV2(obj) => obj.get_sequence_number(),
+            Self::TimerV1(obj) => obj.get_sequence_number(),
+            Self::TradeV1(obj) => obj.get_sequence_number(),
+            Self::TradeV2(obj) => obj.get_sequence_number(),
+            Self::AggTradeV1(obj) => obj.get_sequence_number(),
+            Self::BookTickerV1(obj) => obj.get_sequence_number(),
+            Self::TickerV1(obj) => obj.get_sequence_number()
         }
     }
 
@@ -81,10 +164,131 @@ impl Objects {
         match self {
             Self::MockV1(obj) => obj.get_correlation_id(),
             Self::KlineV1(obj) => obj.get_correlation_id(),
+            Self::KlineV2(obj) => obj.get_correlation_id(),
             Self::NewOrderV1(obj) => obj.get_correlation_id(),
+            Self::NewOrderV2(obj) => obj.get_correlation_id(),
             Self::OrderbookV1(obj) => obj.get_correlation_id(),
+            Self::OrderbookV2(obj) => obj.get_correlation_id(),
             Self::OrderbookUpdateV1(obj) => obj.get_correlation_id(),
-            Self::TradeV1(obj) => obj.get_correlation_id()
+            Self::OrderbookUpdateV2(obj) => obj.get_correlation_id(),
+            Self::TimerV1(obj) => obj.get_correlation_id(),
+            Self::TradeV1(obj) => obj.get_correlation_id(),
+            Self::TradeV2(obj) => obj.get_correlation_id(),
+            Self::AggTradeV1(obj) => obj.get_correlation_id(),
+            Self::BookTickerV1(obj) => obj.get_correlation_id(),
+            Self::TickerV1(obj) => obj.get_correlation_id()
+        }
+    }
+
+    pub fn get_symbol(&self) -> Option<&str> {
+        match self {
+            Self::MockV1(_) => None,
+            Self::KlineV1(obj) => Some(obj.get_symbol()),
+            Self::KlineV2(obj) => Some(obj.get_symbol()),
+            Self::NewOrderV1(obj) => Some(obj.get_symbol()),
+            Self::NewOrderV2(obj) => Some(obj.get_symbol()),
+            Self::OrderbookV1(obj) => Some(obj.get_symbol()),
+            Self::OrderbookV2(obj) => Some(obj.get_symbol()),
+            Self::OrderbookUpdateV1(obj) => Some(obj.get_symbol()),
+            Self::OrderbookUpdateV2(obj) => Some(obj.get_symbol()),
+            Self::TimerV1(_) => None,
+            Self::TradeV1(obj) => Some(obj.get_symbol()),
+            Self::TradeV2(obj) => Some(obj.get_symbol()),
+            Self::AggTradeV1(obj) => Some(obj.get_symbol()),
+            Self::BookTickerV1(obj) => Some(obj.get_symbol()),
+            Self::TickerV1(obj) => Some(obj.get_symbol())
+        }
+    }
+
+    // Name of the source component which created the object
+    pub fn get_source(&self) -> Option<&str> {
+        match self {
+            Self::MockV1(_) => None,
+            Self::KlineV1(obj) => Some(obj.get_source()),
+            Self::KlineV2(obj) => Some(obj.get_source()),
+            Self::NewOrderV1(_) => None,
+            Self::NewOrderV2(_) => None,
+            Self::OrderbookV1(obj) => Some(obj.get_source()),
+            Self::OrderbookV2(obj) => Some(obj.get_source()),
+            Self::OrderbookUpdateV1(obj) => Some(obj.get_source()),
+            Self::OrderbookUpdateV2(obj) => Some(obj.get_source()),
+            Self::TimerV1(_) => None,
+            Self::TradeV1(obj) => Some(obj.get_source()),
+            Self::TradeV2(obj) => Some(obj.get_source()),
+            Self::AggTradeV1(obj) => Some(obj.get_source()),
+            Self::BookTickerV1(obj) => Some(obj.get_source()),
+            Self::TickerV1(obj) => Some(obj.get_source())
+        }
+    }
+
+    // Hops stamped so far, if the object is traced
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        match self {
+            Self::MockV1(obj) => obj.get_hops(),
+            Self::KlineV1(obj) => obj.get_hops(),
+            Self::KlineV2(obj) => obj.get_hops(),
+            Self::NewOrderV1(obj) => obj.get_hops(),
+            Self::NewOrderV2(obj) => obj.get_hops(),
+            Self::OrderbookV1(obj) => obj.get_hops(),
+            Self::OrderbookV2(obj) => obj.get_hops(),
+            Self::OrderbookUpdateV1(obj) => obj.get_hops(),
+            Self::OrderbookUpdateV2(obj) => obj.get_hops(),
+            Self::TimerV1(obj) => obj.get_hops(),
+            Self::TradeV1(obj) => obj.get_hops(),
+            Self::TradeV2(obj) => obj.get_hops(),
+            Self::AggTradeV1(obj) => obj.get_hops(),
+            Self::BookTickerV1(obj) => obj.get_hops(),
+            Self::TickerV1(obj) => obj.get_hops()
+        }
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        match self {
+            Self::MockV1(obj) => obj.set_hops(hops),
+            Self::KlineV1(obj) => obj.set_hops(hops),
+            Self::KlineV2(obj) => obj.set_hops(hops),
+            Self::NewOrderV1(obj) => obj.set_hops(hops),
+            Self::NewOrderV2(obj) => obj.set_hops(hops),
+            Self::OrderbookV1(obj) => obj.set_hops(hops),
+            Self::OrderbookV2(obj) => obj.set_hops(hops),
+            Self::OrderbookUpdateV1(obj) => obj.set_hops(hops),
+            Self::OrderbookUpdateV2(obj) => obj.set_hops(hops),
+            Self::TimerV1(obj) => obj.set_hops(hops),
+            Self::TradeV1(obj) => obj.set_hops(hops),
+            Self::TradeV2(obj) => obj.set_hops(hops),
+            Self::AggTradeV1(obj) => obj.set_hops(hops),
+            Self::BookTickerV1(obj) => obj.set_hops(hops),
+            Self::TickerV1(obj) => obj.set_hops(hops)
+        }
+    }
+
+    // Starts a new trace, discarding the hops stamped in any other process
+    pub fn start_trace(&mut self, component: &str) {
+        self.set_hops(Some(vec![Hop::new(component)]));
+    }
+
+    // Adds a hop to traced objects only
+    pub fn stamp(&mut self, component: &str) {
+        let hops = match self {
+            Self::MockV1(obj) => obj.get_mut_hops(),
+            Self::KlineV1(obj) => obj.get_mut_hops(),
+            Self::KlineV2(obj) => obj.get_mut_hops(),
+            Self::NewOrderV1(obj) => obj.get_mut_hops(),
+            Self::NewOrderV2(obj) => obj.get_mut_hops(),
+            Self::OrderbookV1(obj) => obj.get_mut_hops(),
+            Self::OrderbookV2(obj) => obj.get_mut_hops(),
+            Self::OrderbookUpdateV1(obj) => obj.get_mut_hops(),
+            Self::OrderbookUpdateV2(obj) => obj.get_mut_hops(),
+            Self::TimerV1(obj) => obj.get_mut_hops(),
+            Self::TradeV1(obj) => obj.get_mut_hops(),
+            Self::TradeV2(obj) => obj.get_mut_hops(),
+            Self::AggTradeV1(obj) => obj.get_mut_hops(),
+            Self::BookTickerV1(obj) => obj.get_mut_hops(),
+            Self::TickerV1(obj) => obj.get_mut_hops()
+        };
+
+        if let Some(hops) = hops {
+            hops.push(Hop::new(component));
         }
     }
 }
@@ -1,5 +1,10 @@
-use fixed::FixedU64;
-use serde::{ Deserializer, Deserialize, Serialize };
+use crate::error::HolocoreError;
+use crate::objects::common::{ deserialize_legacy, serialize_legacy };
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Precision, Price, Quantity };
+use crate::objects::schema;
+
+use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -19,37 +24,93 @@ pub enum OrderType {
     LimitMaker
 }
 
-fn deserialize_f64_to_fixed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FixedU64<32>, D::Error>
-{
-    match Deserialize::deserialize(deserializer) {
-        Ok(value) => Ok(FixedU64::<32>::from_num::<f64>(value)),
-        Err(e) => Err(e)
+// An order as recorded before the numeric types, with the bits of a FixedU64<32>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewOrderV1 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+
+    pub symbol: String,
+    pub order_side: OrderSide,
+    pub order_type: OrderType,
+    #[serde(serialize_with = "serialize_legacy", deserialize_with = "deserialize_legacy")]
+    pub quantity: Quantity,
+    #[serde(serialize_with = "serialize_legacy", deserialize_with = "deserialize_legacy")]
+    pub price: Price
+}
+
+impl NewOrderV1 {
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::NEW_ORDER
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct NewOrderV1 {
+pub struct NewOrderV2 {
     id: Uuid,
     timestamp: i64,
     sequence_nr: u64,
     correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
 
     pub symbol: String,
     pub order_side: OrderSide,
     pub order_type: OrderType,
-    #[serde(deserialize_with = "deserialize_f64_to_fixed")]
-    pub quantity: FixedU64<32>,
-    #[serde(deserialize_with = "deserialize_f64_to_fixed")]
-    pub price: FixedU64<32>
+    // Decimal strings, numbers are still read for older orders
+    pub quantity: Quantity,
+    pub price: Price
 }
 
-impl NewOrderV1 {
-    pub fn new(id: Uuid, timestamp: i64, sequence_nr: u64, correlation_id: Uuid, symbol: &str, order_side: OrderSide, order_type: OrderType, quantity: FixedU64<32>, price: FixedU64<32>) -> Self {
+impl NewOrderV2 {
+    pub fn new(id: Uuid, timestamp: i64, sequence_nr: u64, correlation_id: Uuid, symbol: &str, order_side: OrderSide, order_type: OrderType, quantity: Quantity, price: Price) -> Self {
         Self {
             id,
             timestamp,
             sequence_nr,
             correlation_id,
+            hops: None,
             symbol: String::from(symbol),
             order_side,
             order_type,
@@ -60,11 +121,11 @@ impl NewOrderV1 {
 
     // Getters
     pub fn get_type(&self) -> u64 {
-        20
+        schema::NEW_ORDER
     }
 
     pub fn get_version(&self) -> u64 {
-        1
+        2
     }
 
     pub fn get_id(&self) -> Uuid {
@@ -82,4 +143,50 @@ impl NewOrderV1 {
     pub fn get_correlation_id(&self) -> Uuid {
         self.correlation_id
     }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    // Setters
+    // Rounds the quantity and the price to the decimals of the instrument
+    pub fn set_precision(&mut self, precision: &Precision) -> Result<(), HolocoreError> {
+        match (precision.quantity(self.quantity), precision.price(self.price)) {
+            (Some(quantity), Some(price)) => {
+                self.quantity = quantity;
+                self.price = price;
+                Ok(())
+            },
+            _ => Err(HolocoreError::Malformed(format!("Order {} cannot be rounded to {:?}", self.id, precision)))
+        }
+    }
+}
+
+impl From<NewOrderV1> for NewOrderV2 {
+    fn from(obj: NewOrderV1) -> Self {
+        Self {
+            id: obj.id,
+            timestamp: obj.timestamp,
+            sequence_nr: obj.sequence_nr,
+            correlation_id: obj.correlation_id,
+            hops: obj.hops,
+            symbol: obj.symbol,
+            order_side: obj.order_side,
+            order_type: obj.order_type,
+            quantity: obj.quantity,
+            price: obj.price
+        }
+    }
 }
@@ -0,0 +1,264 @@
+use fixed::FixedI128;
+use serde::{ Deserialize, Deserializer, Serialize, Serializer, de::{ self, Visitor } };
+use std::fmt;
+use std::str::FromStr;
+
+// Backs the numeric types: 63 integer bits hold any notional, 64 fractional bits are finer than
+// any tick. Negative values are PnL, deltas or basis.
+pub type Fixed = FixedI128<64>;
+
+// Decimals the numeric types can be rounded to, the fractional bits hold about 19
+pub const MAX_DECIMALS: u32 = 18;
+
+// Rounds the decimal digits of value rather than its bits, which are inexact for most decimals,
+// e.g. 0.45 is stored a little below and would round down
+fn round_decimal(value: Fixed, decimals: u32, nearest: bool) -> Option<Fixed> {
+    if decimals > MAX_DECIMALS {
+        return None;
+    }
+
+    // Shortest decimal that reads back as the same bits
+    let text = value.to_string();
+    let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+    let kept = &frac[..frac.len().min(decimals as usize)];
+    let truncated = Fixed::from_str(&format!("{}.{}0", int, kept)).ok()?;
+    if !nearest || frac.as_bytes().get(decimals as usize).is_none_or(|digit| *digit < b'5') {
+        return Some(truncated);
+    }
+
+    let tick = match decimals {
+        0 => Fixed::from_num(1),
+        _ => Fixed::from_str(&format!("0.{}1", "0".repeat(decimals as usize - 1))).ok()?
+    };
+    if value.is_negative() { truncated.checked_sub(tick) } else { truncated.checked_add(tick) }
+}
+
+struct NumericVisitor(&'static str);
+
+impl<'de> Visitor<'de> for NumericVisitor {
+    type Value = Fixed;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a {} as a decimal string or a number", self.0)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Fixed::from_str(v).map_err(|e| E::custom(format!("invalid {} {} - {}", self.0, v, e)))
+    }
+
+    // Through the shortest decimal of v, as its bits are not the decimal that was written
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Fixed::from_num(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Fixed::checked_from_num(v).ok_or_else(|| E::custom(format!("{} {} is out of range", self.0, v)))
+    }
+}
+
+macro_rules! numeric {
+    ($name:ident, $what:literal) => {
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $name(Fixed);
+
+        impl $name {
+            pub const ZERO: Self = Self(Fixed::ZERO);
+
+            pub fn from_fixed(value: Fixed) -> Self {
+                Self(value)
+            }
+
+            pub fn from_int(value: i64) -> Self {
+                Self(Fixed::from_num(value))
+            }
+
+            // Through the shortest decimal of value, None if it is not finite or out of range
+            pub fn from_f64(value: f64) -> Option<Self> {
+                Fixed::from_str(&value.to_string()).ok().map(Self)
+            }
+
+            // Getters
+            pub fn to_fixed(self) -> Fixed {
+                self.0
+            }
+
+            pub fn to_f64(self) -> f64 {
+                self.0.to_num()
+            }
+
+            pub fn is_zero(self) -> bool {
+                self.0 == Fixed::ZERO
+            }
+
+            pub fn is_negative(self) -> bool {
+                self.0.is_negative()
+            }
+
+            // Checked arithmetic, None on overflow or division by zero
+            pub fn checked_add(self, other: Self) -> Option<Self> {
+                self.0.checked_add(other.0).map(Self)
+            }
+
+            pub fn checked_sub(self, other: Self) -> Option<Self> {
+                self.0.checked_sub(other.0).map(Self)
+            }
+
+            pub fn checked_neg(self) -> Option<Self> {
+                self.0.checked_neg().map(Self)
+            }
+
+            pub fn checked_abs(self) -> Option<Self> {
+                self.0.checked_abs().map(Self)
+            }
+
+            pub fn checked_scale(self, factor: Fixed) -> Option<Self> {
+                self.0.checked_mul(factor).map(Self)
+            }
+
+            pub fn checked_div_int(self, divisor: i64) -> Option<Self> {
+                self.0.checked_div(Fixed::from_num(divisor)).map(Self)
+            }
+
+            // Nearest multiple of 10^-decimals, halves away from zero
+            pub fn round_dp(self, decimals: u32) -> Option<Self> {
+                round_decimal(self.0, decimals, true).map(Self)
+            }
+
+            // Multiple of 10^-decimals towards zero
+            pub fn trunc_dp(self, decimals: u32) -> Option<Self> {
+                round_decimal(self.0, decimals, false).map(Self)
+            }
+        }
+
+        impl From<Fixed> for $name {
+            fn from(value: Fixed) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for Fixed {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(text: &str) -> Result<Self, Self::Err> {
+                Fixed::from_str(text).map(Self).map_err(|e| format!("Invalid {} {} - {}", $what, text, e))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        // Decimal strings for humans, bits for binary formats
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.collect_str(&self.0)
+                } else {
+                    serializer.serialize_i128(self.0.to_bits())
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_any(NumericVisitor($what)).map(Self)
+                } else {
+                    i128::deserialize(deserializer).map(|bits| Self(Fixed::from_bits(bits)))
+                }
+            }
+        }
+    };
+}
+
+numeric!(Price, "price");
+numeric!(Quantity, "quantity");
+numeric!(Notional, "notional");
+
+impl Price {
+    pub fn checked_mul(self, quantity: Quantity) -> Option<Notional> {
+        self.0.checked_mul(quantity.0).map(Notional)
+    }
+}
+
+impl Quantity {
+    // Share of total, e.g. the imbalance of a book
+    pub fn checked_ratio(self, total: Quantity) -> Option<Fixed> {
+        self.0.checked_div(total.0)
+    }
+}
+
+impl Notional {
+    // Average price of quantity
+    pub fn checked_div(self, quantity: Quantity) -> Option<Price> {
+        self.0.checked_div(quantity.0).map(Price)
+    }
+}
+
+// Decimals an instrument is quoted and traded in
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Precision {
+    pub price: u32,
+    pub quantity: u32
+}
+
+impl Precision {
+    // To the nearest tick
+    pub fn price(&self, price: Price) -> Option<Price> {
+        price.round_dp(self.price)
+    }
+
+    // Towards zero, so that an order never exceeds the quantity asked for
+    pub fn quantity(&self, quantity: Quantity) -> Option<Quantity> {
+        quantity.trunc_dp(self.quantity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::numeric::{ Notional, Precision, Price, Quantity };
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_numeric() {
+        let price = Price::from_str("64123.45").unwrap();
+        let quantity = Quantity::from_str("0.1").unwrap();
+        assert_eq!((price.to_string(), quantity.to_string()), (String::from("64123.45"), String::from("0.1")));
+
+        // Signed, and wide enough for large notionals
+        let pnl = Notional::from_int(100).checked_sub(Notional::from_int(250)).unwrap();
+        assert!(pnl.is_negative());
+        let notional = price.checked_mul(Quantity::from_int(1_000_000)).unwrap();
+        assert_eq!(notional.round_dp(8), Notional::from_str("64123450000").ok());
+        assert_eq!(notional.checked_div(Quantity::from_int(1_000_000)).and_then(|price| price.round_dp(8)), price.round_dp(8));
+        assert!(Price::from_int(i64::MAX).checked_add(Price::from_int(1)).is_none());
+        assert!(Price::from_int(1 << 40).checked_mul(Quantity::from_int(1 << 40)).is_none());
+        assert!(notional.checked_div(Quantity::ZERO).is_none());
+
+        let precision = Precision { price: 1, quantity: 3 };
+        assert_eq!(precision.price(price), Price::from_str("64123.5").ok());
+        assert_eq!(precision.quantity(Quantity::from_str("1.23456").unwrap()), Quantity::from_str("1.234").ok());
+        assert_eq!(Price::from_str("-2.5").unwrap().round_dp(0), Price::from_str("-3").ok());
+        assert_eq!(Quantity::from_str("-1.99").unwrap().trunc_dp(1), Quantity::from_str("-1.9").ok());
+        assert!(price.round_dp(19).is_none());
+
+        // Strings or numbers in JSON, bits in binary
+        assert_eq!(serde_json::to_string(&price).unwrap(), "\"64123.45\"");
+        assert_eq!(serde_json::from_str::<Price>("64123.45").unwrap(), serde_json::from_str::<Price>("\"64123.45\"").unwrap());
+        assert!(serde_json::from_str::<Price>("\"64,123\"").is_err());
+        assert_eq!(bincode::deserialize::<Price>(&bincode::serialize(&price).unwrap()).unwrap(), price);
+    }
+}
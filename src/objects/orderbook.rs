@@ -1,10 +1,13 @@
-use crate::objects::common::{ serialize_asks, deserialize_asks, serialize_bids, deserialize_bids };
-use crate::objects::orderbook_update::OrderbookUpdateV1;
+use crate::objects::common::{ deserialize_legacy_levels, deserialize_levels, serialize_asks, serialize_bids, serialize_legacy_asks, serialize_legacy_bids };
+use crate::objects::orderbook_update::OrderbookUpdateV2;
 use crate::components::sources::binance::model::BinanceOrderBookEvent;
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Fixed, Notional, Price, Quantity };
+use crate::objects::schema;
 
-use fixed::FixedU64;
 use serde::{ Deserialize, Serialize };
 use std::collections::BTreeMap;
+use std::str::FromStr;
 use uuid::Uuid;
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
@@ -15,12 +18,15 @@ pub enum OrderbookStatus {
     Stale
 }
 
+// A book as recorded before the numeric types, with the bits of a FixedU64<32>
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct OrderbookV1 {
     id: Uuid,
     timestamp: i64,
     sequence_nr: u64,
     correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
     source: String,
     exchange: String,
     asset_type: String,
@@ -31,14 +37,86 @@ pub struct OrderbookV1 {
 
     pub status: OrderbookStatus,
 
-    #[serde(serialize_with = "serialize_bids", deserialize_with = "deserialize_bids")]
-    bids: BTreeMap<FixedU64<32>, FixedU64<32>>,
+    #[serde(serialize_with = "serialize_legacy_bids", deserialize_with = "deserialize_legacy_levels")]
+    bids: BTreeMap<Price, Quantity>,
 
-    #[serde(serialize_with = "serialize_asks", deserialize_with = "deserialize_asks")]
-    asks: BTreeMap<FixedU64<32>, FixedU64<32>>
+    #[serde(serialize_with = "serialize_legacy_asks", deserialize_with = "deserialize_legacy_levels")]
+    asks: BTreeMap<Price, Quantity>
 }
 
 impl OrderbookV1 {
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::ORDERBOOK
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OrderbookV2 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    source: String,
+    exchange: String,
+    asset_type: String,
+
+    pub symbol: String,
+    pub first_update: bool,
+    pub last_update_id: u64,
+
+    pub status: OrderbookStatus,
+
+    #[serde(serialize_with = "serialize_bids", deserialize_with = "deserialize_levels")]
+    bids: BTreeMap<Price, Quantity>,
+
+    #[serde(serialize_with = "serialize_asks", deserialize_with = "deserialize_levels")]
+    asks: BTreeMap<Price, Quantity>
+}
+
+impl OrderbookV2 {
     pub fn from_binance(id: Uuid,
                         timestamp: i64,
                         sequence_nr: u64,
@@ -48,19 +126,19 @@ impl OrderbookV1 {
                         asset_type: &str,
                         symbol: &str,
                         event: BinanceOrderBookEvent) -> Self {
-        let mut bids = BTreeMap::<FixedU64<32>, FixedU64<32>>::new();
+        let mut bids = BTreeMap::<Price, Quantity>::new();
         for bid in event.bids {
-            let price = match FixedU64::<32>::from_str(&bid.price) {
+            let price = match Price::from_str(&bid.price) {
                 Ok(price) => price,
                 Err(e) => {
-                    log::error!("OrderbookV1: failed to parse price {} - {}", bid.price, e);
+                    log::error!("OrderbookV2: failed to parse price {} - {}", bid.price, e);
                     continue;
                 }
             };
-            let volume = match FixedU64::<32>::from_str(&bid.qty) {
+            let volume = match Quantity::from_str(&bid.qty) {
                 Ok(volume) => volume,
                 Err(e) => {
-                    log::error!("OrderbookV1: failed to parse volume {} - {}", bid.qty, e);
+                    log::error!("OrderbookV2: failed to parse volume {} - {}", bid.qty, e);
                     continue;
                 }
             };
@@ -68,19 +146,19 @@ impl OrderbookV1 {
             bids.insert(price, volume);
         }
 
-        let mut asks = BTreeMap::<FixedU64<32>, FixedU64<32>>::new();
+        let mut asks = BTreeMap::<Price, Quantity>::new();
         for ask in event.asks {
-            let price = match FixedU64::<32>::from_str(&ask.price) {
+            let price = match Price::from_str(&ask.price) {
                 Ok(price) => price,
                 Err(e) => {
-                    log::error!("OrderbookV1: failed to parse price {} - {}", ask.price, e);
+                    log::error!("OrderbookV2: failed to parse price {} - {}", ask.price, e);
                     continue;
                 }
             };
-            let volume = match FixedU64::<32>::from_str(&ask.qty) {
+            let volume = match Quantity::from_str(&ask.qty) {
                 Ok(volume) => volume,
                 Err(e) => {
-                    log::error!("OrderbookV1: failed to parse volume {} - {}", ask.qty, e);
+                    log::error!("OrderbookV2: failed to parse volume {} - {}", ask.qty, e);
                     continue;
                 }
             };
@@ -93,6 +171,7 @@ impl OrderbookV1 {
             timestamp,
             sequence_nr,
             correlation_id,
+            hops: None,
             source: String::from(source),
             exchange: String::from(exchange),
             asset_type: String::from(asset_type),
@@ -107,11 +186,11 @@ impl OrderbookV1 {
 
     // Getters
     pub fn get_type(&self) -> u64 {
-        19
+        schema::ORDERBOOK
     }
 
     pub fn get_version(&self) -> u64 {
-        1
+        2
     }
 
     pub fn get_id(&self) -> Uuid {
@@ -130,28 +209,48 @@ impl OrderbookV1 {
         self.correlation_id
     }
 
-    pub fn best_bid(&self) -> (FixedU64<32>, FixedU64<32>) {
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn best_bid(&self) -> (Price, Quantity) {
         match self.bids.iter().next_back() {
             Some((price, volume)) => (*price, *volume),
             None => {
                 log::error!("Orderbook {}: does not have a best bid level, returning zeroes", self.symbol);
-                (FixedU64::<32>::from_num(0), FixedU64::<32>::from_num(0))
+                (Price::ZERO, Quantity::ZERO)
             }
         }
     }
 
-    pub fn best_ask(&self) -> (FixedU64<32>, FixedU64<32>) {
+    pub fn best_ask(&self) -> (Price, Quantity) {
         match self.asks.iter().next() {
             Some((price, volume)) => (*price, *volume),
             None => {
                 log::error!("Orderbook {}: does not have a best ask level, returning zeroes", self.symbol);
-                (FixedU64::<32>::from_num(0), FixedU64::<32>::from_num(0))
+                (Price::ZERO, Quantity::ZERO)
             }
         }
     }
 
     // Methods
-    pub fn update(&mut self, obj: &OrderbookUpdateV1) {
+    pub fn update(&mut self, obj: &OrderbookUpdateV2) {
         // Drop any event where u is <= lastUpdateId in the snapshot.
         if obj.final_update_id <= self.last_update_id {
             if self.last_update_id - obj.final_update_id > 20000 {
@@ -179,7 +278,7 @@ impl OrderbookV1 {
         }
 
         for bid in &obj.bids {
-            if bid.quantity == 0 {
+            if bid.quantity.is_zero() {
                 self.bids.remove(&bid.price);
             } else {
                 self.bids.insert(bid.price, bid.quantity);
@@ -187,7 +286,7 @@ impl OrderbookV1 {
         }
 
         for ask in &obj.asks {
-            if ask.quantity == 0 {
+            if ask.quantity.is_zero() {
                 self.asks.remove(&ask.price);
             } else {
                 self.asks.insert(ask.price, ask.quantity);
@@ -206,76 +305,121 @@ impl OrderbookV1 {
         log::trace!("Updated orderbook {} to update_id {}", obj.symbol, self.last_update_id);
     }
 
+    // Analytics are None when they overflow or divide by zero, e.g. on an empty book
     // Spread(s)
-    pub fn spread(&self) -> f64 {
+    pub fn spread(&self) -> Option<Price> {
         let (bid_price, _bid_volume) = self.best_bid();
         let (ask_price, _ask_volume) = self.best_ask();
-        (ask_price - bid_price).to_num()
+        ask_price.checked_sub(bid_price)
     }
 
-    pub fn weighted_spread(&self, requested_volume: f64) -> f64 {
-        let mut average_bid = FixedU64::<32>::from_num(0);
-        let mut average_ask = FixedU64::<32>::from_num(0);
-
-        let mut running = FixedU64::<32>::from_num(requested_volume);
-        for (price, volume) in self.bids.iter().rev() {
-            if &running >= volume {
-                // This level is used completely
-                average_bid += price * volume;
-                running -= volume;
-                continue;
-            }
-
-            // We now run out of requested volume
-            average_bid += price * running;
-            break;
-        }
+    pub fn weighted_spread(&self, requested_volume: Quantity) -> Option<Notional> {
+        let average_bid = Self::weighted_cost(self.bids.iter().rev(), requested_volume)?;
+        let average_ask = Self::weighted_cost(self.asks.iter(), requested_volume)?;
+        average_ask.checked_sub(average_bid)
+    }
 
-        running = FixedU64::<32>::from_num(requested_volume);
-        for (price, volume) in self.asks.iter() {
+    // Cost of requested_volume taken from the levels in order
+    fn weighted_cost<'a>(levels: impl Iterator<Item = (&'a Price, &'a Quantity)>, requested_volume: Quantity) -> Option<Notional> {
+        let mut cost = Notional::ZERO;
+        let mut running = requested_volume;
+        for (price, volume) in levels {
             if &running >= volume {
                 // This level is used completely
-                average_ask += price * volume;
-                running -= volume;
+                cost = cost.checked_add(price.checked_mul(*volume)?)?;
+                running = running.checked_sub(*volume)?;
                 continue;
             }
 
             // We now run out of requested volume
-            average_ask += price * running;
+            cost = cost.checked_add(price.checked_mul(running)?)?;
             break;
         }
 
-        (average_ask - average_bid).to_num()
+        Some(cost)
     }
 
     // Mid(s)
-    pub fn mid(&self) -> FixedU64<32> {
+    pub fn mid(&self) -> Option<Price> {
         let (bid_price, _bid_volume) = self.best_bid();
         let (ask_price, _ask_volume) = self.best_ask();
-        (ask_price + bid_price) / 2
+        ask_price.checked_add(bid_price)?.checked_div_int(2)
     }
 
-    pub fn weighted_mid(&self) -> FixedU64<32> {
+    pub fn weighted_mid(&self) -> Option<Price> {
         let (bid_price, _bid_volume) = self.best_bid();
         let (ask_price, _ask_volume) = self.best_ask();
-        let imbalance = self.imbalance();
-        let weighted_mid = imbalance * ask_price + (FixedU64::<32>::from_num(1) - imbalance) * bid_price;
-        weighted_mid
+        let imbalance = self.imbalance()?;
+        let weighted_ask = ask_price.checked_scale(imbalance)?;
+        let weighted_bid = bid_price.checked_scale(Fixed::from_num(1).checked_sub(imbalance)?)?;
+        weighted_ask.checked_add(weighted_bid)
     }
 
     // Microprice
-    pub fn microprice(&self) -> FixedU64<32> {
-        let mid = self.mid();
-        let imbalance = self.imbalance();
-        let microprice = mid + (imbalance * 1);
-
-        microprice
+    pub fn microprice(&self) -> Option<Price> {
+        let mid = self.mid()?;
+        let imbalance = self.imbalance()?;
+        mid.checked_add(Price::from_fixed(imbalance))
     }
 
     // Imbalance
-    fn imbalance(&self) -> FixedU64<32> {
+    fn imbalance(&self) -> Option<Fixed> {
         let (_bid_price, bid_volume) = self.best_bid();
         let (_ask_price, ask_volume) = self.best_ask();
-        bid_volume / (bid_volume + ask_volume)
+        bid_volume.checked_ratio(bid_volume.checked_add(ask_volume)?)
+    }
+}
+
+impl From<OrderbookV1> for OrderbookV2 {
+    fn from(obj: OrderbookV1) -> Self {
+        Self {
+            id: obj.id,
+            timestamp: obj.timestamp,
+            sequence_nr: obj.sequence_nr,
+            correlation_id: obj.correlation_id,
+            hops: obj.hops,
+            source: obj.source,
+            exchange: obj.exchange,
+            asset_type: obj.asset_type,
+            symbol: obj.symbol,
+            first_update: obj.first_update,
+            last_update_id: obj.last_update_id,
+            status: obj.status,
+            bids: obj.bids,
+            asks: obj.asks
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::binance::model::{ BinanceAsks, BinanceBids, BinanceOrderBookEvent };
+    use crate::objects::numeric::{ Notional, Price, Quantity };
+    use crate::objects::orderbook::OrderbookV2;
+    use crate::utils::uuid;
+
+    use std::str::FromStr;
+
+    fn new_book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderbookV2 {
+        let event = BinanceOrderBookEvent {
+            last_update_id: 1,
+            bids: bids.iter().map(|(price, qty)| BinanceBids { price: String::from(*price), qty: String::from(*qty) }).collect(),
+            asks: asks.iter().map(|(price, qty)| BinanceAsks { price: String::from(*price), qty: String::from(*qty) }).collect()
+        };
+        OrderbookV2::from_binance(uuid::uuid(), 0, 0, uuid::uuid(), "BinanceSpotSource", "Binance", "Spot", "BTCUSDT", event)
+    }
+
+    #[test]
+    fn test_analytics() {
+        let book = new_book(&[("99.5", "1"), ("99", "2")], &[("100.5", "1"), ("101", "3")]);
+        assert_eq!(book.spread(), Price::from_str("1").ok());
+        assert_eq!(book.mid(), Price::from_str("100").ok());
+        // 1 @ 100.5 + 0.5 @ 101 = 151 against 1 @ 99.5 + 0.5 @ 99 = 149
+        assert_eq!(book.weighted_spread(Quantity::from_str("1.5").unwrap()), Some(Notional::from_int(2)));
+
+        // Overflows instead of wrapping, and an empty book has no imbalance
+        let huge = new_book(&[("1", "1")], &[("9000000000000", "9000000000000")]);
+        assert!(huge.weighted_spread(Quantity::from_int(1_000_000_000_000)).is_none());
+        assert!(new_book(&[], &[]).weighted_mid().is_none());
     }
 }
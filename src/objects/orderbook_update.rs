@@ -1,16 +1,22 @@
 use crate::components::sources::binance::model::BinanceOrderbookUpdateEvent;
-use crate::objects::level::Level;
+use crate::objects::level::{ LevelV1, LevelV2 };
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Price, Quantity };
+use crate::objects::schema;
 
-use fixed::FixedU64;
 use serde::{ Deserialize, Serialize };
+use std::str::FromStr;
 use uuid::Uuid;
 
+// An update as recorded before the numeric types, with the bits of a FixedU64<32>
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct OrderbookUpdateV1 {
     id: Uuid,
     timestamp: i64,
     sequence_nr: u64,
     correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
     pub source: String,
     exchange: String,
     asset_type: String,
@@ -21,11 +27,80 @@ pub struct OrderbookUpdateV1 {
     pub first_update_id: u64,
     pub final_update_id: u64,
     previous_final_update_id: Option<u64>,
-    pub bids: Vec<Level>,
-    pub asks: Vec<Level>
+    pub bids: Vec<LevelV1>,
+    pub asks: Vec<LevelV1>
 }
 
 impl OrderbookUpdateV1 {
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::ORDERBOOK_UPDATE
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OrderbookUpdateV2 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    pub source: String,
+    exchange: String,
+    asset_type: String,
+
+    event_type: String,
+    pub event_time: u64,
+    pub symbol: String,
+    pub first_update_id: u64,
+    pub final_update_id: u64,
+    previous_final_update_id: Option<u64>,
+    pub bids: Vec<LevelV2>,
+    pub asks: Vec<LevelV2>
+}
+
+impl OrderbookUpdateV2 {
     pub fn from_binance(id: Uuid,
                         timestamp: i64,
                         sequence_nr: u64,
@@ -34,9 +109,9 @@ impl OrderbookUpdateV1 {
                         exchange: &str,
                         asset_type: &str,
                         event: BinanceOrderbookUpdateEvent) -> Self {
-        let mut bids = Vec::<Level>::new();
+        let mut bids = Vec::<LevelV2>::new();
         for bid in event.bids {
-            let price = match FixedU64::<32>::from_str(&bid.price) {
+            let price = match Price::from_str(&bid.price) {
                 Ok(price) => price,
                 Err(e) => {
                     log::error!("Failed to parse bid price {} - {}", bid.price, e);
@@ -44,7 +119,7 @@ impl OrderbookUpdateV1 {
                 }
             };
 
-            let volume = match FixedU64::<32>::from_str(&bid.qty) {
+            let volume = match Quantity::from_str(&bid.qty) {
                 Ok(volume) => volume,
                 Err(e) => {
                     log::error!("Failed to parse bid volume {} - {}", bid.qty, e);
@@ -52,13 +127,13 @@ impl OrderbookUpdateV1 {
                 }
             };
 
-            let level = Level::new(price, volume);
+            let level = LevelV2::new(price, volume);
             bids.push(level);
         }
 
-        let mut asks = Vec::<Level>::new();
+        let mut asks = Vec::<LevelV2>::new();
         for ask in event.asks {
-            let price = match FixedU64::<32>::from_str(&ask.price) {
+            let price = match Price::from_str(&ask.price) {
                 Ok(price) => price,
                 Err(e) => {
                     log::error!("Failed to parse ask price {} - {}", ask.price, e);
@@ -66,7 +141,7 @@ impl OrderbookUpdateV1 {
                 }
             };
 
-            let volume = match FixedU64::<32>::from_str(&ask.qty) {
+            let volume = match Quantity::from_str(&ask.qty) {
                 Ok(volume) => volume,
                 Err(e) => {
                     log::error!("Failed to parse ask volume {} - {}", ask.qty, e);
@@ -74,7 +149,7 @@ impl OrderbookUpdateV1 {
                 }
             };
 
-            let level = Level::new(price, volume);
+            let level = LevelV2::new(price, volume);
             asks.push(level);
         }
 
@@ -83,6 +158,7 @@ impl OrderbookUpdateV1 {
             timestamp,
             sequence_nr,
             correlation_id,
+            hops: None,
             source: String::from(source),
             exchange: String::from(exchange),
             asset_type: String::from(asset_type),
@@ -99,11 +175,11 @@ impl OrderbookUpdateV1 {
 
     // Getters
     pub fn get_type(&self) -> u64 {
-        18
+        schema::ORDERBOOK_UPDATE
     }
 
     pub fn get_version(&self) -> u64 {
-        1
+        2
     }
 
     pub fn get_id(&self) -> Uuid {
@@ -121,4 +197,47 @@ impl OrderbookUpdateV1 {
     pub  fn get_correlation_id(&self) -> Uuid {
         self.correlation_id
     }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl From<OrderbookUpdateV1> for OrderbookUpdateV2 {
+    fn from(obj: OrderbookUpdateV1) -> Self {
+        Self {
+            id: obj.id,
+            timestamp: obj.timestamp,
+            sequence_nr: obj.sequence_nr,
+            correlation_id: obj.correlation_id,
+            hops: obj.hops,
+            source: obj.source,
+            exchange: obj.exchange,
+            asset_type: obj.asset_type,
+            event_type: obj.event_type,
+            event_time: obj.event_time,
+            symbol: obj.symbol,
+            first_update_id: obj.first_update_id,
+            final_update_id: obj.final_update_id,
+            previous_final_update_id: obj.previous_final_update_id,
+            bids: obj.bids.into_iter().map(LevelV2::from).collect(),
+            asks: obj.asks.into_iter().map(LevelV2::from).collect()
+        }
+    }
 }
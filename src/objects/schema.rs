@@ -0,0 +1,208 @@
+use crate::objects::Objects;
+
+// Type ids, shared by every version of a type. They are written to the recordings, so a type
+// keeps its id forever and a new type takes a new one.
+pub const MOCK: u64 = 1;
+pub const KLINE: u64 = 16;
+pub const TRADE: u64 = 17;
+pub const ORDERBOOK_UPDATE: u64 = 18;
+pub const ORDERBOOK: u64 = 19;
+pub const NEW_ORDER: u64 = 20;
+pub const TIMER: u64 = 21;
+pub const BOOK_TICKER: u64 = 22;
+pub const AGG_TRADE: u64 = 23;
+pub const TICKER: u64 = 24;
+
+pub struct Schema {
+    pub type_id: u64,
+    pub version: u64,
+    // Name of the Objects variant, as used in configs
+    pub name: &'static str,
+    // Decodes a binary payload of this version
+    pub decode: fn(&[u8]) -> bincode::Result<Objects>,
+    // Converts an object of this version into the next one, unset for the latest version
+    pub upcast: Option<fn(Objects) -> Objects>
+}
+
+// Every version of every type. A new version is added next to the previous ones, which get an
+// upcast towards it, so that old recordings can still be read.
+pub const SCHEMAS: [Schema; 15] = [
+    Schema { type_id: MOCK, version: 1, name: "MockV1", decode: |payload| bincode::deserialize(payload).map(Objects::MockV1), upcast: None },
+    Schema { type_id: KLINE, version: 1, name: "KlineV1", decode: |payload| bincode::deserialize(payload).map(Objects::KlineV1), upcast: Some(upcast_kline_v1) },
+    Schema { type_id: KLINE, version: 2, name: "KlineV2", decode: |payload| bincode::deserialize(payload).map(Objects::KlineV2), upcast: None },
+    Schema { type_id: TRADE, version: 1, name: "TradeV1", decode: |payload| bincode::deserialize(payload).map(Objects::TradeV1), upcast: Some(upcast_trade_v1) },
+    Schema { type_id: TRADE, version: 2, name: "TradeV2", decode: |payload| bincode::deserialize(payload).map(Objects::TradeV2), upcast: None },
+    Schema { type_id: ORDERBOOK_UPDATE, version: 1, name: "OrderbookUpdateV1", decode: |payload| bincode::deserialize(payload).map(Objects::OrderbookUpdateV1), upcast: Some(upcast_orderbook_update_v1) },
+    Schema { type_id: ORDERBOOK_UPDATE, version: 2, name: "OrderbookUpdateV2", decode: |payload| bincode::deserialize(payload).map(Objects::OrderbookUpdateV2), upcast: None },
+    Schema { type_id: ORDERBOOK, version: 1, name: "OrderbookV1", decode: |payload| bincode::deserialize(payload).map(Objects::OrderbookV1), upcast: Some(upcast_orderbook_v1) },
+    Schema { type_id: ORDERBOOK, version: 2, name: "OrderbookV2", decode: |payload| bincode::deserialize(payload).map(Objects::OrderbookV2), upcast: None },
+    Schema { type_id: NEW_ORDER, version: 1, name: "NewOrderV1", decode: |payload| bincode::deserialize(payload).map(Objects::NewOrderV1), upcast: Some(upcast_new_order_v1) },
+    Schema { type_id: NEW_ORDER, version: 2, name: "NewOrderV2", decode: |payload| bincode::deserialize(payload).map(Objects::NewOrderV2), upcast: None },
+    Schema { type_id: TIMER, version: 1, name: "TimerV1", decode: |payload| bincode::deserialize(payload).map(Objects::TimerV1), upcast: None },
+    Schema { type_id: BOOK_TICKER, version: 1, name: "BookTickerV1", decode: |payload| bincode::deserialize(payload).map(Objects::BookTickerV1), upcast: None },
+    Schema { type_id: AGG_TRADE, version: 1, name: "AggTradeV1", decode: |payload| bincode::deserialize(payload).map(Objects::AggTradeV1), upcast: None },
+    Schema { type_id: TICKER, version: 1, name: "TickerV1", decode: |payload| bincode::deserialize(payload).map(Objects::TickerV1), upcast: None }
+];
+
+fn upcast_kline_v1(obj: Objects) -> Objects {
+    match obj {
+        Objects::KlineV1(obj) => Objects::KlineV2(obj.into()),
+        obj => obj
+    }
+}
+
+fn upcast_trade_v1(obj: Objects) -> Objects {
+    match obj {
+        Objects::TradeV1(obj) => Objects::TradeV2(obj.into()),
+        obj => obj
+    }
+}
+
+fn upcast_orderbook_update_v1(obj: Objects) -> Objects {
+    match obj {
+        Objects::OrderbookUpdateV1(obj) => Objects::OrderbookUpdateV2(obj.into()),
+        obj => obj
+    }
+}
+
+fn upcast_orderbook_v1(obj: Objects) -> Objects {
+    match obj {
+        Objects::OrderbookV1(obj) => Objects::OrderbookV2(obj.into()),
+        obj => obj
+    }
+}
+
+fn upcast_new_order_v1(obj: Objects) -> Objects {
+    match obj {
+        Objects::NewOrderV1(obj) => Objects::NewOrderV2(obj.into()),
+        obj => obj
+    }
+}
+
+pub fn find(type_id: u64, version: u64) -> Option<&'static Schema> {
+    SCHEMAS.iter().find(|schema| schema.type_id == type_id && schema.version == version)
+}
+
+pub fn find_by_name(name: &str) -> Option<&'static Schema> {
+    SCHEMAS.iter().find(|schema| schema.name == name)
+}
+
+pub fn latest(type_id: u64) -> Option<&'static Schema> {
+    SCHEMAS.iter().filter(|schema| schema.type_id == type_id).max_by_key(|schema| schema.version)
+}
+
+// Brings an object to the latest version of its type, one version at a time
+pub fn upcast(mut obj: Objects) -> Objects {
+    while let Some(upcast) = find(obj.get_type(), obj.get_version()).and_then(|schema| schema.upcast) {
+        obj = upcast(obj);
+    }
+
+    obj
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::binance::model::BinanceTradeEvent;
+    use crate::objects::{ Objects, hop::Hop, mock::MockV1, new_order::{ NewOrderV2, OrderSide, OrderType }, numeric::{ Price, Quantity }, schema::{ self, SCHEMAS }, trade::TradeV1 };
+    use crate::utils::binary::{ self, HEADER_SIZE, Header };
+    use crate::utils::uuid;
+
+    use fixed::FixedU64;
+    use serde::Serialize;
+    use std::collections::HashSet;
+    use std::str::FromStr;
+    use ::uuid::Uuid;
+
+    // NewOrderV1 as recorded before the numeric types, with the bits of a FixedU64<32>
+    #[derive(Serialize)]
+    struct RecordedNewOrderV1 {
+        id: Uuid,
+        timestamp: i64,
+        sequence_nr: u64,
+        correlation_id: Uuid,
+        hops: Option<Vec<Hop>>,
+        symbol: String,
+        order_side: OrderSide,
+        order_type: OrderType,
+        quantity: u64,
+        price: u64
+    }
+
+    #[test]
+    fn test_schemas() {
+        let mut versions = HashSet::new();
+        for schema in &SCHEMAS {
+            assert!(versions.insert((schema.type_id, schema.version)), "{} is registered twice", schema.name);
+            assert_eq!(schema::find_by_name(schema.name).map(|found| (found.type_id, found.version)), Some((schema.type_id, schema.version)));
+            // Each version but the latest can be brought to the next one
+            let latest = schema::latest(schema.type_id).unwrap();
+            assert_eq!(schema.upcast.is_none(), latest.version == schema.version, "{}", schema.name);
+        }
+
+        let obj = Objects::MockV1(MockV1::new(uuid::uuid(), 1, 7, uuid::uuid()));
+        let schema = schema::find(obj.get_type(), obj.get_version()).unwrap();
+        assert_eq!(schema.name, obj.get_type_name());
+        assert!(matches!(schema::upcast(obj), Objects::MockV1(_)));
+        assert!(schema::find_by_name("Trades").is_none());
+    }
+
+    #[test]
+    fn test_upcast() {
+        let event = serde_json::from_str::<BinanceTradeEvent>(r#"{"e":"trade","E":1,"s":"BTCUSDT","t":12345,"p":"0.001","q":"100.5","b":88,"a":50,"T":2,"m":true,"M":true}"#).unwrap();
+        let obj = Objects::TradeV1(TradeV1::from_binance(uuid::uuid(), 0, 1, uuid::uuid(), "BinanceSpotSource", "Binance", "Spot", event));
+        let id = obj.get_id();
+
+        // Recorded before TradeV2 existed
+        let record = binary::encode(&obj).unwrap();
+        let header = Header::parse(record[..HEADER_SIZE].try_into().unwrap()).unwrap();
+        match schema::upcast(binary::decode(&header, &record[HEADER_SIZE..]).unwrap()) {
+            Objects::TradeV2(trade) => {
+                assert_eq!((trade.get_id(), trade.get_version(), trade.get_trade_id()), (id, 2, 12345));
+                assert_eq!((trade.get_price(), trade.get_quantity()), (Price::from_str("0.001").unwrap(), Quantity::from_str("100.5").unwrap()));
+            },
+            obj => panic!("Expected TradeV2, got {}", obj.get_type_name())
+        }
+    }
+
+    #[test]
+    fn test_recorded_layout() {
+        let (id, correlation_id) = (uuid::uuid(), uuid::uuid());
+        let recorded = RecordedNewOrderV1 {
+            id,
+            timestamp: 3,
+            sequence_nr: 4,
+            correlation_id,
+            hops: None,
+            symbol: String::from("BTCUSDT"),
+            order_side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: FixedU64::<32>::from_str("0.5").unwrap().to_bits(),
+            price: FixedU64::<32>::from_str("64123.25").unwrap().to_bits()
+        };
+        let payload = bincode::serialize(&recorded).unwrap();
+
+        let header = Header { type_id: 20, version: 1, len: payload.len() };
+        let order: NewOrderV2 = match schema::upcast(binary::decode(&header, &payload).unwrap()) {
+            Objects::NewOrderV2(order) => order,
+            obj => panic!("Expected NewOrderV2, got {}", obj.get_type_name())
+        };
+        assert_eq!((order.get_id(), order.get_correlation_id(), order.get_version()), (id, correlation_id, 2));
+        assert_eq!((order.price, order.quantity), (Price::from_str("64123.25").unwrap(), Quantity::from_str("0.5").unwrap()));
+    }
+
+    #[test]
+    fn test_native_layout() {
+        // Negative, beyond 32 integer bits and finer than 32 fractional bits
+        let price = Price::from_str("-9000000000000.0000000000125").unwrap();
+        let quantity = Quantity::from_str("12345678901234.5").unwrap();
+        let obj = Objects::NewOrderV2(NewOrderV2::new(uuid::uuid(), 3, 4, uuid::uuid(), "BTCUSDT", OrderSide::Sell, OrderType::Limit, quantity, price));
+
+        let record = binary::encode(&obj).unwrap();
+        let header = Header::parse(record[..HEADER_SIZE].try_into().unwrap()).unwrap();
+        assert_eq!((header.type_id, header.version), (20, 2));
+        match binary::decode(&header, &record[HEADER_SIZE..]).unwrap() {
+            Objects::NewOrderV2(order) => assert_eq!((order.price, order.quantity), (price, quantity)),
+            obj => panic!("Expected NewOrderV2, got {}", obj.get_type_name())
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::components::sources::binance::model::BinanceTickerEvent;
+use crate::objects::common::parse_number;
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Notional, Price, Quantity };
+use crate::objects::schema;
+
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+// Sent by the full ticker only
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TickerStatistics {
+    pub price_change: Price,
+    pub weighted_average_price: Price,
+    pub last_quantity: Quantity,
+    pub bid_price: Price,
+    pub bid_quantity: Quantity,
+    pub ask_price: Price,
+    pub ask_quantity: Quantity,
+    // Window of the statistics, in milliseconds
+    pub open_time: u64,
+    pub close_time: u64,
+    // -1 without trades in the window
+    pub first_trade_id: i64,
+    pub last_trade_id: i64,
+    pub number_of_trades: u64
+}
+
+// Rolling 24h statistics of a symbol, from the full or the mini ticker
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TickerV1 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    source: String,
+    exchange: String,
+    asset_type: String,
+
+    event_time: u64,
+    symbol: String,
+    open: Price,
+    high: Price,
+    low: Price,
+    close: Price,
+    volume: Quantity,
+    quote_volume: Notional,
+    statistics: Option<TickerStatistics>
+}
+
+impl TickerV1 {
+    pub fn from_binance(id: Uuid,
+                        timestamp: i64,
+                        sequence_nr: u64,
+                        correlation_id: Uuid,
+                        source: &str,
+                        asset_type: &str,
+                        event: BinanceTickerEvent) -> Self {
+        let statistics = match event.event_type.as_str() {
+            "24hrTicker" => Some(TickerStatistics {
+                price_change: parse_number("ticker price change", &event.price_change),
+                weighted_average_price: parse_number("ticker weighted average price", &event.weighted_average_price),
+                last_quantity: parse_number("ticker last quantity", &event.last_qty),
+                bid_price: parse_number("ticker bid price", &event.bid_price),
+                bid_quantity: parse_number("ticker bid quantity", &event.bid_qty),
+                ask_price: parse_number("ticker ask price", &event.ask_price),
+                ask_quantity: parse_number("ticker ask quantity", &event.ask_qty),
+                open_time: event.open_time,
+                close_time: event.close_time,
+                first_trade_id: event.first_trade_id,
+                last_trade_id: event.last_trade_id,
+                number_of_trades: event.number_of_trades
+            }),
+            _ => None
+        };
+
+        Self {
+            id,
+            timestamp,
+            sequence_nr,
+            correlation_id,
+            hops: None,
+            source: String::from(source),
+            exchange: String::from("Binance"),
+            asset_type: String::from(asset_type),
+            event_time: event.event_time,
+            symbol: event.symbol,
+            open: parse_number("ticker open", &event.open),
+            high: parse_number("ticker high", &event.high),
+            low: parse_number("ticker low", &event.low),
+            close: parse_number("ticker close", &event.close),
+            volume: parse_number("ticker volume", &event.volume),
+            quote_volume: parse_number("ticker quote volume", &event.quote_volume),
+            statistics
+        }
+    }
+
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::TICKER
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn get_exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    pub fn get_asset_type(&self) -> &str {
+        &self.asset_type
+    }
+
+    // Time the exchange sent the event, in milliseconds
+    pub fn get_event_time(&self) -> u64 {
+        self.event_time
+    }
+
+    pub fn get_open(&self) -> Price {
+        self.open
+    }
+
+    pub fn get_high(&self) -> Price {
+        self.high
+    }
+
+    pub fn get_low(&self) -> Price {
+        self.low
+    }
+
+    pub fn get_close(&self) -> Price {
+        self.close
+    }
+
+    // Base asset traded in the window
+    pub fn get_volume(&self) -> Quantity {
+        self.volume
+    }
+
+    pub fn get_quote_volume(&self) -> Notional {
+        self.quote_volume
+    }
+
+    // None for the mini ticker
+    pub fn get_statistics(&self) -> Option<&TickerStatistics> {
+        self.statistics.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::binance::model::BinanceTickerEvent;
+    use crate::objects::numeric::{ Notional, Price };
+    use crate::objects::ticker::TickerV1;
+    use crate::utils::uuid;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_ticker() {
+        let full = r#"{"e":"24hrTicker","E":123456789,"s":"BNBBTC","p":"0.0015","P":"250.00","w":"0.0018","x":"0.0009","c":"0.0025","Q":"10","b":"0.0024","B":"10","a":"0.0026","A":"100","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18","O":0,"C":86400000,"F":0,"L":18150,"n":18151}"#;
+        let event = serde_json::from_str::<BinanceTickerEvent>(full).unwrap();
+        let ticker = TickerV1::from_binance(uuid::uuid(), 0, 1, uuid::uuid(), "BinanceSpotSource", "Spot", event);
+        assert_eq!((ticker.get_close(), ticker.get_quote_volume()), (Price::from_str("0.0025").unwrap(), Notional::from_int(18)));
+        let statistics = ticker.get_statistics().unwrap();
+        assert_eq!((statistics.ask_price, statistics.number_of_trades), (Price::from_str("0.0026").unwrap(), 18151));
+
+        // The mini ticker has no statistics
+        let mini = r#"{"e":"24hrMiniTicker","E":123456789,"s":"BNBBTC","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"}"#;
+        let event = serde_json::from_str::<BinanceTickerEvent>(mini).unwrap();
+        let ticker = TickerV1::from_binance(uuid::uuid(), 0, 2, uuid::uuid(), "BinanceSpotSource", "Spot", event);
+        assert_eq!(ticker.get_open(), Price::from_str("0.001").unwrap());
+        assert!(ticker.get_statistics().is_none());
+    }
+}
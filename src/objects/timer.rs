@@ -0,0 +1,73 @@
+use crate::objects::hop::Hop;
+use crate::objects::schema;
+
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+// Fired by a timer declared in the config of a PU or a sink, and delivered to that component only
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimerV1 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_number: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+
+    name: String
+}
+
+impl TimerV1 {
+    pub fn new(id: Uuid, timestamp: i64, sequence_number: u64, correlation_id: Uuid, name: &str) -> Self {
+        Self {
+            id,
+            timestamp,
+            sequence_number,
+            correlation_id,
+            hops: None,
+            name: String::from(name)
+        }
+    }
+
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::TIMER
+    }
+
+    pub fn get_version(&self) -> u64 {
+        1
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    // Name of the timer in the config
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+}
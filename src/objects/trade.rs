@@ -1,4 +1,8 @@
 use crate::components::sources::binance::model::BinanceTradeEvent;
+use crate::objects::common::parse_number;
+use crate::objects::hop::Hop;
+use crate::objects::numeric::{ Price, Quantity };
+use crate::objects::schema;
 
 use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
@@ -9,6 +13,8 @@ pub struct TradeV1 {
     timestamp: i64,
     sequence_nr: u64,
     correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
     source: String,
     exchange: String,
     asset_type: String,
@@ -39,6 +45,7 @@ impl TradeV1 {
             timestamp,
             sequence_nr,
             correlation_id,
+            hops: None,
             source: String::from(source),
             exchange: String::from(exchange),
             asset_type: String::from(asset_type),
@@ -57,7 +64,7 @@ impl TradeV1 {
 
     // Getters
     pub fn get_type(&self) -> u64 {
-        17
+        schema::TRADE
     }
 
     pub fn get_version(&self) -> u64 {
@@ -79,4 +86,161 @@ impl TradeV1 {
     pub  fn get_correlation_id(&self) -> Uuid {
         self.correlation_id
     }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+}
+
+// TradeV1 with the price and the quantity parsed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TradeV2 {
+    id: Uuid,
+    timestamp: i64,
+    sequence_nr: u64,
+    correlation_id: Uuid,
+    #[serde(default)]
+    hops: Option<Vec<Hop>>,
+    source: String,
+    exchange: String,
+    asset_type: String,
+
+    event_time: u64,
+    symbol: String,
+    trade_id: u64,
+    price: Price,
+    quantity: Quantity,
+    buyer_order_id: u64,
+    seller_order_id: u64,
+    trade_order_time: u64,
+    is_buyer_maker: bool
+}
+
+impl TradeV2 {
+    // Getters
+    pub fn get_type(&self) -> u64 {
+        schema::TRADE
+    }
+
+    pub fn get_version(&self) -> u64 {
+        2
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_sequence_number(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    pub fn get_correlation_id(&self) -> Uuid {
+        self.correlation_id
+    }
+
+    pub fn get_hops(&self) -> Option<&Vec<Hop>> {
+        self.hops.as_ref()
+    }
+
+    pub fn get_mut_hops(&mut self) -> Option<&mut Vec<Hop>> {
+        self.hops.as_mut()
+    }
+
+    pub fn set_hops(&mut self, hops: Option<Vec<Hop>>) {
+        self.hops = hops;
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn get_exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    pub fn get_asset_type(&self) -> &str {
+        &self.asset_type
+    }
+
+    // Time the exchange sent the event, in milliseconds
+    pub fn get_event_time(&self) -> u64 {
+        self.event_time
+    }
+
+    pub fn get_trade_id(&self) -> u64 {
+        self.trade_id
+    }
+
+    pub fn get_price(&self) -> Price {
+        self.price
+    }
+
+    pub fn get_quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    pub fn get_buyer_order_id(&self) -> u64 {
+        self.buyer_order_id
+    }
+
+    pub fn get_seller_order_id(&self) -> u64 {
+        self.seller_order_id
+    }
+
+    // Time of the trade, in milliseconds
+    pub fn get_trade_order_time(&self) -> u64 {
+        self.trade_order_time
+    }
+
+    pub fn is_buyer_maker(&self) -> bool {
+        self.is_buyer_maker
+    }
+}
+
+impl From<TradeV1> for TradeV2 {
+    fn from(obj: TradeV1) -> Self {
+        Self {
+            id: obj.id,
+            timestamp: obj.timestamp,
+            sequence_nr: obj.sequence_nr,
+            correlation_id: obj.correlation_id,
+            hops: obj.hops,
+            source: obj.source,
+            exchange: obj.exchange,
+            asset_type: obj.asset_type,
+            event_time: obj.event_time,
+            price: parse_number("trade price", &obj.price),
+            quantity: parse_number("trade quantity", &obj.quantity),
+            symbol: obj.symbol,
+            trade_id: obj.trade_id,
+            buyer_order_id: obj.buyer_order_id,
+            seller_order_id: obj.seller_order_id,
+            trade_order_time: obj.trade_order_time,
+            is_buyer_maker: obj.is_buyer_maker
+        }
+    }
 }
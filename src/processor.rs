@@ -1,434 +1,792 @@
 use crate::components::control::{ self, Controls, ControlState };
-use crate::components::processing_units::{ self, PUs };
-use crate::components::sinks::{ self, Sinks };
-use crate::components::sources::{ self, Sources};
-use crate::config::Config;
-use crate::inject;
-use crate::objects::Objects;
-
-use flume::{ Receiver, Sender, bounded, unbounded };
-use futures::future;
+use crate::components::processing_units::ProcessingUnit;
+use crate::components::registry::{ Registry, SharedPU, SharedSink, SharedSource };
+use crate::components::sinks::Sink;
+use crate::components::sources::Source;
+use crate::config::{ Config, edge::Downstream, merge::MergePolicy, placement::Placement, pus::PUConfigs, sinks::SinkConfigs, sources::SourceConfigs, validation };
+use crate::edge::{ Edge, edge };
+use crate::error::{ Action, HolocoreError };
+use crate::graph::Graph;
+use crate::inputs::Inputs;
+use crate::markets::Markets;
+use crate::metrics::ComponentMetrics;
+use crate::objects::{ Objects, hop::Hop };
+use crate::scheduler::Timers;
+use crate::supervisor::{ Exit, Supervisor };
+use crate::trace::Traces;
+use crate::utils::{ clock::{ self, Clock }, threads, uuid::{ IdGenerator, id_generator } };
+
+use flume::{ Receiver, Sender, unbounded };
+use futures::{ future, stream::{ FuturesUnordered, StreamExt } };
 use std::collections::HashMap;
-use tokio::task::spawn;
+use std::future::Future;
+use std::sync::{ Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard };
+use std::time::Duration;
+use tokio::task::{ JoinHandle, spawn };
 
-macro_rules! init {
-    ($component:ident) => {
-        {
-            let mut unlocked_component = $component.lock().await;
-            match unlocked_component.init().await {
-                Ok(()) => log::info!("Initialized component {}", &unlocked_component.get_name()),
-                Err(e) => log::error!("Failed to initialize component {} - {}", &unlocked_component.get_name(), e)
+// Milliseconds given to the components to drain and shut down
+const DEFAULT_SHUTDOWN_TIMEOUT: u64 = 5000;
+
+// Milliseconds given to the components left once the drain timed out to run their shutdown hooks
+const HOOKS_TIMEOUT: u64 = 1000;
+
+// Sends obj to every enabled edge bound to port and accepting it, disabling the ones whose receiver is gone.
+// Returns false once all the txs are disabled.
+async fn dispatch(name: &str, txs: &[Edge], port: Option<&str>, obj: &Objects, disabled: &mut Vec<usize>) -> bool {
+    let mut futures = txs.iter()
+                         .enumerate()
+                         .filter(|&(idx, tx)| !disabled.contains(&idx) && tx.get_port() == port && tx.accepts(obj))
+                         .map(|(idx, tx)| Box::pin(async move { (idx, tx.send(obj.clone()).await) }))
+                         .collect::<Vec<_>>();
+
+    while !futures.is_empty() {
+        match future::select_all(futures).await {
+            ((_idx, Ok(())), _index, remaining) => futures = remaining,
+            ((idx, Err(e)), _index, remaining) => {
+                log::error!("{}: failed while sending to {} - {}", name, txs[idx].get_downstream(), e);
+                futures = remaining;
+                disabled.push(idx);
+                if disabled.len() == txs.len() {
+                    log::error!("{}: all the txs are disabled. Returning...", name);
+                    return false;
+                }
             }
         }
-    };
+    }
+
+    true
 }
 
-macro_rules! run_source {
-    ($component:ident, $self:ident, $ctrl:ident, $tasks:ident) => {
-        {
-            let unlocked_component = $component.lock().await;
+// Removes the edges towards downstream, keeping the disabled indices in step
+fn disconnect(txs: &mut Vec<Edge>, disabled: &mut Vec<usize>, downstream: &str) {
+    for idx in (0..txs.len()).rev() {
+        if txs[idx].get_downstream() == downstream {
+            txs.remove(idx);
+            disabled.retain(|&disabled_idx| disabled_idx != idx);
+            for disabled_idx in disabled.iter_mut().filter(|disabled_idx| **disabled_idx > idx) {
+                *disabled_idx -= 1;
+            }
+        }
+    }
+}
+
+// Spawns the task of a component on the runtime its config places it on
+fn place(name: &str, placement: &Placement, stack_size: usize, task: impl Future<Output = ()> + Send + 'static) -> Result<JoinHandle<()>, HolocoreError> {
+    match placement {
+        Placement::Shared => Ok(spawn(task)),
+        Placement::Dedicated { core } => threads::spawn_dedicated(name, *core, stack_size, task)
+    }
+}
 
-            $ctrl = unlocked_component.endpoints($ctrl);
-            let ctrl_rx = $self.ctrl_rxs.remove(unlocked_component.get_name()).expect(&format!("Cannot find ctrl rx for source {}", unlocked_component.get_name()));
+// How a task exits on e, once the component can no longer handle objects
+fn exit_on(e: &HolocoreError) -> Exit {
+    match e.action() {
+        Action::Stop => Exit::Fatal(String::from(e.get_message())),
+        Action::Skip | Action::Restart => Exit::Failed(String::from(e.get_message()))
+    }
+}
 
-            let txs = $self.txs.remove(unlocked_component.get_name()).expect(&format!("Cannot find txs for source {}", unlocked_component.get_name()));
-            $tasks.push(recv!($component, txs, ctrl_rx));
+// Drops the object at hand if e allows it, otherwise returns how the task exits
+fn classify(name: &str, e: &HolocoreError, metrics: &ComponentMetrics) -> Option<Exit> {
+    metrics.failed();
+    match e.action() {
+        Action::Skip => {
+            log::warn!("Task {} skipping object - {}", name, e);
+            None
+        },
+        Action::Restart | Action::Stop => {
+            log::error!("Task {} exited - {}", name, e);
+            Some(exit_on(e))
         }
-    };
+    }
 }
 
-macro_rules! run_pu {
-    ($component:ident, $self:ident, $ctrl:ident, $tasks:ident) => {
-        {
-            let unlocked_component = $component.lock().await;
+// Awaits future, recording how long it took
+async fn timed<T>(metrics: &ComponentMetrics, future: impl std::future::Future<Output = T>) -> T {
+    let start = std::time::Instant::now();
+    let result = future.await;
+    metrics.observe_duration(start.elapsed().as_nanos() as u64);
+    result
+}
 
-            $ctrl = unlocked_component.endpoints($ctrl);
-            let ctrl_rx = $self.ctrl_rxs.remove(unlocked_component.get_name()).expect(&format!("Cannot find ctrl rx for source {}", unlocked_component.get_name()));
+// How a source task stamps the objects it emits
+#[derive(Clone)]
+struct SourceContext {
+    trace: bool,
+    clock: Arc<dyn Clock>
+}
 
-            let txs = $self.txs.remove(unlocked_component.get_name()).expect(&format!("Cannot find txs for PU {}", unlocked_component.get_name()));
-            let rxs = $self.rxs.remove(unlocked_component.get_name()).expect(&format!("Cannot find rxs for PU {}", unlocked_component.get_name()));
-            $tasks.push(execute!($component, txs, rxs, ctrl_rx));
-        }
-    };
+impl SourceContext {
+    fn new(trace: bool, clock: Arc<dyn Clock>) -> Self {
+        Self { trace, clock }
+    }
 }
 
-macro_rules! run_sink {
-    ($component:ident, $self:ident, $ctrl:ident, $tasks:ident) => {
-        {
-            let unlocked_component = $component.lock().await;
+// Where a sink task records the traces and measures the latency of the objects it sends
+#[derive(Clone)]
+struct SinkContext {
+    traces: Arc<Traces>,
+    clock: Arc<dyn Clock>
+}
+
+impl SinkContext {
+    fn new(traces: Arc<Traces>, clock: Arc<dyn Clock>) -> Self {
+        Self { traces, clock }
+    }
+}
 
-            $ctrl = unlocked_component.endpoints($ctrl);
-            let ctrl_rx = $self.ctrl_rxs.remove(unlocked_component.get_name()).expect(&format!("Cannot find ctrl rx for source {}", unlocked_component.get_name()));
+async fn source_loop(component: &mut dyn Source, name: &str, txs: &mut Vec<Edge>, ctrl_rx: &Receiver<Controls>, supervisor: &mut Supervisor, metrics: &ComponentMetrics, ctx: &SourceContext) -> Exit {
+    let mut disabled = vec![];
 
-            let rxs = $self.rxs.remove(unlocked_component.get_name()).expect(&format!("Cannot find txs for source {}", unlocked_component.get_name()));
-            $tasks.push(send!($component, rxs, ctrl_rx));
+    loop {
+        tokio::select! {
+            // Controls first, so that those deferred during a restart apply before the next object
+            biased;
+
+            ctrl = supervisor.next_control(ctrl_rx) => match ctrl {
+                Ok(Controls::Inject(obj)) => {
+                    if !dispatch(name, txs, None, &obj, &mut disabled).await {
+                        return Exit::Stopped;
+                    }
+                },
+                Ok(Controls::SleepTime(sleep_time)) => component.set_sleep_time(sleep_time),
+                Ok(Controls::Shutdown) => {
+                    log::info!("Task {} stopping", name);
+                    return Exit::Stopped;
+                },
+                Ok(Controls::Connect(edge)) => txs.push(edge),
+                Ok(Controls::Disconnect(downstream)) => disconnect(txs, &mut disabled, &downstream),
+                Ok(Controls::Attach(_)) => log::error!("Task {} is a source and cannot receive objects", name),
+                Err(e) => {
+                    log::error!("Task {} bad control request received - {}", name, e);
+                    return Exit::Stopped;
+                }
+            },
+            result = component.recv() => match result {
+                Ok((Some(mut obj), _completed)) => {
+                    supervisor.healthy();
+                    metrics.emitted(1);
+                    ctx.clock.advance(obj.get_timestamp());
+                    // Hops stamped by another process cannot be compared with ours
+                    if ctx.trace {
+                        obj.start_trace(name);
+                    } else {
+                        obj.set_hops(None);
+                    }
+                    if !dispatch(name, txs, None, &obj, &mut disabled).await {
+                        return Exit::Stopped;
+                    }
+                },
+                Ok((None, completed)) => {
+                    if completed {
+                        log::info!("Task {} completed", name);
+                        return Exit::Completed;
+                    }
+                },
+                Err(e) => {
+                    if let Some(exit) = classify(name, &e, metrics) {
+                        return exit;
+                    }
+                }
+            }
         }
-    };
+    }
 }
 
-macro_rules! recv {
-    ($source:ident, $txs:ident, $ctrl_rx:ident) => {
-        {
-            let cloned = $source.clone();
-
-            spawn(async move {
-                let mut unlocked_component = cloned.lock().await;
-                let mut disabled = vec![];
-                let num_txs = $txs.len();
-
-                loop {
-                    tokio::select! {
-                        obj = $ctrl_rx.recv_async() => {
-                            match obj {
-                                Ok(Controls::Inject(obj)) => {
-                                    inject!(unlocked_component, $txs, obj, disabled);
-                                },
-                                Ok(Controls::SleepTime(sleep_time)) => {
-                                    unlocked_component.set_sleep_time(sleep_time);
-                                },
-                                Err(e) => {
-                                    log::error!("Task {} bad control request received - {}", unlocked_component.get_name(), e);
-                                    break;
+async fn run_source(source: SharedSource, mut txs: Vec<Edge>, ctrl_rx: Receiver<Controls>, mut supervisor: Supervisor, metrics: Arc<ComponentMetrics>, ctx: SourceContext) {
+    let mut unlocked_component = source.lock().await;
+    let name = String::from(unlocked_component.get_name());
+    let mut exit = source_loop(&mut *unlocked_component, &name, &mut txs, &ctrl_rx, &mut supervisor, &metrics, &ctx).await;
+    while supervisor.restart(&exit, &ctrl_rx).await {
+        exit = match unlocked_component.init().await {
+            Ok(()) => {
+                log::info!("Task {} restarted", name);
+                source_loop(&mut *unlocked_component, &name, &mut txs, &ctrl_rx, &mut supervisor, &metrics, &ctx).await
+            },
+            Err(e) => {
+                log::error!("Task {} failed to restart - {}", name, e);
+                metrics.failed();
+                exit_on(&e)
+            }
+        };
+    }
+
+    // Closing the txs lets the downstreams drain and stop in turn
+    drop(txs);
+    match unlocked_component.shutdown().await {
+        Ok(()) => log::info!("Task {} shut down", name),
+        Err(e) => log::error!("Task {} failed to shut down - {}", name, e)
+    }
+}
+
+async fn pu_loop(component: &mut dyn ProcessingUnit, name: &str, txs: &mut Vec<Edge>, inputs: &mut Inputs, ctrl_rx: &Receiver<Controls>, supervisor: &mut Supervisor, metrics: &ComponentMetrics) -> Exit {
+    let mut disabled_txs = vec![];
+
+    loop {
+        tokio::select! {
+            // Controls first, so that those deferred during a restart apply before the next object
+            biased;
+
+            ctrl = supervisor.next_control(ctrl_rx) => match ctrl {
+                Ok(Controls::Inject(obj)) => {
+                    if !dispatch(name, txs, None, &obj, &mut disabled_txs).await {
+                        return Exit::Stopped;
+                    }
+                },
+                Ok(Controls::Connect(edge)) => txs.push(edge),
+                Ok(Controls::Disconnect(downstream)) => disconnect(txs, &mut disabled_txs, &downstream),
+                Ok(Controls::Attach(rx)) => inputs.attach(rx),
+                // Sent once the processor gave up waiting for the inputs to drain
+                Ok(Controls::Shutdown) => {
+                    log::warn!("Task {} stopping before its inputs are drained", name);
+                    return Exit::Stopped;
+                },
+                Ok(_) => (),
+                Err(e) => {
+                    log::error!("Task {} bad control request received - {}", name, e);
+                    return Exit::Stopped;
+                }
+            },
+            obj = inputs.recv(name) => match obj.inspect(|_| metrics.received()) {
+                Some(mut obj) => {
+                    obj.stamp(name);
+                    let hops = obj.get_hops().cloned();
+                    match timed(metrics, component.execute(obj)).await {
+                        Ok((mut outputs, completed)) => {
+                            supervisor.healthy();
+                            metrics.emitted(outputs.len() as u64);
+                            // Objects created by the PU carry on the trace of their input
+                            for obj in outputs.objs_mut() {
+                                if obj.get_hops().is_none() {
+                                    obj.set_hops(hops.clone());
                                 }
+                                obj.stamp(name);
                             }
-                        },
-                        result = unlocked_component.recv() => match result {
-                            Ok((obj, completed)) => match obj {
-                                Some(obj) => {
-                                    let mut futures = $txs.iter()
-                                                          .enumerate()
-                                                          .filter(|&(idx, _)| !disabled.contains(&idx))
-                                                          .map(|(_, tx)| tx.send_async(obj.clone()))
-                                                          .collect::<Vec<_>>();
-
-                                    while !futures.is_empty() {
-                                        match future::select_all(futures).await {
-                                            (Ok(_value), _index, remaining) => futures = remaining,
-                                            (Err(e), index, remaining) => {
-                                                log::error!("{}: failed while sending at index {} - {}", unlocked_component.get_name(), index, e);
-                                                futures = remaining;
-                                                disabled.push(index);
-                                                if disabled.len() == num_txs {
-                                                    log::error!("{}: all the txs are disabled. Returning...", unlocked_component.get_name());
-                                                    return;
-                                                }
-                                            }
-                                        }
-                                    }
-                                },
-                                None => {
-                                    if completed == true {
-                                        log::info!("Task {} completed", unlocked_component.get_name());
-                                        break;
-                                    }
+
+                            for (port, obj) in outputs {
+                                // Nothing would receive it, e.g. the orders of a strategy run without an order manager
+                                if !txs.iter().any(|tx| tx.get_port() == port.as_deref()) {
+                                    metrics.unrouted();
+                                    log::debug!("Task {} dropping {} for port {}, which has no downstream", name, obj.get_type_name(), port.as_deref().unwrap_or("default"));
+                                    continue;
                                 }
-                            },
-                            Err(e) => {
-                                log::error!("Task {} exited - {}", unlocked_component.get_name(), e);
-                                break;
-                            }
-                        }
-                    };
-                }
-            })
-        }
-    };
-}
 
-macro_rules! execute {
-    ($component:ident, $txs:ident, $rxs:ident, $ctrl_rx:ident) => {
-        {
-            let cloned = $component.clone();
-
-            spawn(async move {
-                let mut unlocked_component = cloned.lock().await;
-                let mut disabled_txs = vec![];
-                let mut disabled_rxs = vec![];
-                let num_txs = $txs.len();
-                let num_rxs = $rxs.len();
-
-                loop {
-                    tokio::select! {
-                        obj = $ctrl_rx.recv_async() => {
-                            match obj {
-                                Ok(Controls::Inject(obj)) => {
-                                    inject!(unlocked_component, $txs, obj, disabled_txs);
-                                },
-                                Ok(Controls::SleepTime(_sleep_time)) => (),
-                                Err(e) => {
-                                    log::error!("Task {} bad control request received - {}", unlocked_component.get_name(), e);
-                                    return;
+                                if !dispatch(name, txs, port.as_deref(), &obj, &mut disabled_txs).await {
+                                    return Exit::Stopped;
                                 }
                             }
+
+                            if completed {
+                                log::info!("Task {} completed", name);
+                                return Exit::Completed;
+                            }
                         },
-                        _ = async {
-                            let futures = $rxs.iter()
-                                              .enumerate()
-                                              .filter(|&(idx, _)| !disabled_rxs.contains(&idx))
-                                              .map(|(_, rx)| rx.recv_async())
-                                              .collect::<Vec<_>>();
-
-                            match future::select_all(futures).await {
-                                (Ok(obj), _index, _remaining) => match unlocked_component.execute(obj).await {
-                                    Ok((obj, completed)) => match obj {
-                                        Some(obj) => {
-                                            let mut futures = $txs.iter()
-                                                                  .enumerate()
-                                                                  .filter(|&(idx, _)| !disabled_txs.contains(&idx))
-                                                                  .map(|(_, tx)| tx.send_async(obj.clone()))
-                                                                  .collect::<Vec<_>>();
-
-                                            while !futures.is_empty() {
-                                                match future::select_all(futures).await {
-                                                    (Ok(_value), _index, remaining) => futures = remaining,
-                                                    (Err(e), index, remaining) => {
-                                                        log::error!("{}: failed while sending at index {} - {}", unlocked_component.get_name(), index, e);
-                                                        futures = remaining;
-                                                        disabled_txs.push(index);
-                                                        if disabled_txs.len() == num_txs {
-                                                            log::error!("{}: all the txs are disabled. Returning...", unlocked_component.get_name());
-                                                            return;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        },
-                                        None => {
-                                            if completed == true {
-                                                log::info!("Task {} completed", unlocked_component.get_name());
-                                                return;
-                                            }
-                                        }
-                                    },
-                                    Err(e) => {
-                                        log::error!("Task {} exited - {}", unlocked_component.get_name(), e);
-                                        return;
-                                    }
-                                },
-                                (Err(e), index, _remaining) => {
-                                    log::error!("{}: failed while receiving at index {} - {}", unlocked_component.get_name(), index, e);
-                                    disabled_rxs.push(index);
-                                    if disabled_rxs.len() == num_rxs {
-                                        log::error!("{}: all the rxs are disabled. Returning...", unlocked_component.get_name());
-                                        return;
-                                    }
-                                }
+                        Err(e) => {
+                            if let Some(exit) = classify(name, &e, metrics) {
+                                return exit;
                             }
-                        } => ()
+                        }
                     }
-                }
-            })
+                },
+                // Every upstream has stopped and the inputs are drained
+                None => return Exit::Stopped
+            }
         }
-    };
+    }
 }
 
-macro_rules! send {
-    ($source:ident, $rxs:ident, $ctrl_rx:ident) => {
-        {
-            let cloned = $source.clone();
-
-            spawn(async move {
-                let mut unlocked_component = cloned.lock().await;
-                let mut disabled = vec![];
-                let num_rxs = $rxs.len();
-
-                loop {
-                    tokio::select! {
-                        obj = $ctrl_rx.recv_async() => {
-                            match obj {
-                                Ok(Controls::SleepTime(_sleep_time)) => (),
-                                Ok(_) => (),
-                                Err(e) => {
-                                    log::error!("Task {} bad control request received - {}", unlocked_component.get_name(), e);
-                                    return;
-                                }
+async fn run_pu(pu: SharedPU, mut txs: Vec<Edge>, mut inputs: Inputs, ctrl_rx: Receiver<Controls>, mut supervisor: Supervisor, metrics: Arc<ComponentMetrics>) {
+    let mut unlocked_component = pu.lock().await;
+    let name = String::from(unlocked_component.get_name());
+    let mut exit = pu_loop(&mut *unlocked_component, &name, &mut txs, &mut inputs, &ctrl_rx, &mut supervisor, &metrics).await;
+    while supervisor.restart(&exit, &ctrl_rx).await {
+        exit = match unlocked_component.init().await {
+            Ok(()) => {
+                log::info!("Task {} restarted", name);
+                pu_loop(&mut *unlocked_component, &name, &mut txs, &mut inputs, &ctrl_rx, &mut supervisor, &metrics).await
+            },
+            Err(e) => {
+                log::error!("Task {} failed to restart - {}", name, e);
+                metrics.failed();
+                exit_on(&e)
+            }
+        };
+    }
+
+    drop(txs);
+    match unlocked_component.shutdown().await {
+        Ok(()) => log::info!("Task {} shut down", name),
+        Err(e) => log::error!("Task {} failed to shut down - {}", name, e)
+    }
+}
+
+async fn sink_loop(component: &mut dyn Sink, name: &str, inputs: &mut Inputs, ctrl_rx: &Receiver<Controls>, supervisor: &mut Supervisor, metrics: &ComponentMetrics, ctx: &SinkContext) -> Exit {
+    loop {
+        tokio::select! {
+            // Controls first, so that those deferred during a restart apply before the next object
+            biased;
+
+            ctrl = supervisor.next_control(ctrl_rx) => match ctrl {
+                Ok(Controls::Attach(rx)) => inputs.attach(rx),
+                // Sent once the processor gave up waiting for the inputs to drain
+                Ok(Controls::Shutdown) => {
+                    log::warn!("Task {} stopping before its inputs are drained", name);
+                    return Exit::Stopped;
+                },
+                Ok(_) => (),
+                Err(e) => {
+                    log::error!("Task {} bad control request received - {}", name, e);
+                    return Exit::Stopped;
+                }
+            },
+            obj = inputs.recv(name) => match obj.inspect(|_| metrics.received()) {
+                Some(mut obj) => {
+                    obj.stamp(name);
+                    let timestamp = obj.get_timestamp();
+                    let trace = obj.get_hops().cloned().map(|hops| (obj.get_correlation_id(), hops));
+                    match timed(metrics, component.send(obj)).await {
+                        Ok(()) => {
+                            supervisor.healthy();
+                            // By the processor clock, so that a replay measures the pipeline rather than the age of the recording
+                            metrics.observe_latency((ctx.clock.now_nanos() - timestamp).max(0) as u64);
+                            if let Some((correlation_id, mut hops)) = trace {
+                                hops.push(Hop::new(name));
+                                ctx.traces.record(correlation_id, &hops);
                             }
                         },
-                        () = async {
-                            let futures = $rxs.iter()
-                                              .enumerate()
-                                              .filter(|&(idx, _)| !disabled.contains(&idx))
-                                              .map(|(_, rx)| rx.recv_async())
-                                              .collect::<Vec<_>>();
-
-                            match future::select_all(futures).await {
-                                (Ok(obj), _index, _remaining) => match unlocked_component.send(obj).await {
-                                    Ok(()) => (),
-                                    Err(e) => {
-                                        log::error!("Task {} exited - {}", unlocked_component.get_name(), e);
-                                        return;
-                                    }
-                                },
-                                (Err(e), index, _remaining) => {
-                                    log::error!("{}: failed while receiving at index {} - {}", unlocked_component.get_name(), index, e);
-                                    disabled.push(index);
-                                    if disabled.len() == num_rxs {
-                                        log::error!("{}: all the rxs are disabled. Returning...", unlocked_component.get_name());
-                                        return;
-                                    }
-                                }
+                        Err(e) => {
+                            if let Some(exit) = classify(name, &e, metrics) {
+                                return exit;
                             }
-                        } => ()
+                        }
                     }
-                }
-            })
+                },
+                // Every upstream has stopped and the inputs are drained
+                None => return Exit::Stopped
+            }
         }
-    };
+    }
+}
+
+async fn run_sink(sink: SharedSink, mut inputs: Inputs, ctrl_rx: Receiver<Controls>, mut supervisor: Supervisor, metrics: Arc<ComponentMetrics>, ctx: SinkContext) {
+    let mut unlocked_component = sink.lock().await;
+    let name = String::from(unlocked_component.get_name());
+    let mut exit = sink_loop(&mut *unlocked_component, &name, &mut inputs, &ctrl_rx, &mut supervisor, &metrics, &ctx).await;
+    while supervisor.restart(&exit, &ctrl_rx).await {
+        exit = match unlocked_component.init().await {
+            Ok(()) => {
+                log::info!("Task {} restarted", name);
+                sink_loop(&mut *unlocked_component, &name, &mut inputs, &ctrl_rx, &mut supervisor, &metrics, &ctx).await
+            },
+            Err(e) => {
+                log::error!("Task {} failed to restart - {}", name, e);
+                metrics.failed();
+                exit_on(&e)
+            }
+        };
+    }
+
+    match unlocked_component.shutdown().await {
+        Ok(()) => log::info!("Task {} shut down", name),
+        Err(e) => log::error!("Task {} failed to shut down - {}", name, e)
+    }
+}
+
+// Shared by the processor and the control API, which adds and removes components while the graph runs.
+// Routes cannot be mounted once the control has launched, so the endpoints of the added components are not served.
+pub struct Runtime {
+    registry: Arc<Registry>,
+    graph: RwLock<Graph>,
+    traces: Arc<Traces>,
+    clock: Arc<dyn Clock>,
+    markets: Arc<Markets>,
+    tasks: Sender<JoinHandle<()>>,
+    stop: Sender<String>,
+    // Changes await the init of the new components, so they are applied one at a time
+    changes: tokio::sync::Mutex<()>
+}
+
+impl Runtime {
+    fn new(registry: Arc<Registry>, graph: Graph, traces: Arc<Traces>, clock: Arc<dyn Clock>, markets: Arc<Markets>, tasks: Sender<JoinHandle<()>>, stop: Sender<String>) -> Self {
+        Self {
+            registry,
+            graph: RwLock::new(graph),
+            traces,
+            clock,
+            markets,
+            tasks,
+            stop,
+            changes: tokio::sync::Mutex::new(())
+        }
+    }
+
+    // The graph must stay a valid config after every change
+    fn validate(&self, cfg: &Config) -> Result<(), String> {
+        validation::validate(cfg, &self.registry).map_err(|errors| {
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+        })
+    }
+
+    fn add_upstreams(cfg: &mut Config, name: &str, upstreams: &[Downstream]) -> Result<(), String> {
+        for upstream in upstreams {
+            match cfg.downstreams_mut(upstream.name()) {
+                Some(downstreams) => downstreams.push(upstream.with_name(name)),
+                None => return Err(format!("Cannot find source or PU {} upstream of {}", upstream.name(), name))
+            }
+        }
+
+        Ok(())
+    }
+
+    // Takes the component back out of the graph if its task cannot start
+    fn spawn(&self, name: &str, placement: &Placement, task: impl Future<Output = ()> + Send + 'static) -> Result<(), String> {
+        let stack_size = self.graph().get_config().stack_size * 1024 * 1024;
+        let task = match place(name, placement, stack_size, task) {
+            Ok(task) => task,
+            Err(e) => {
+                let mut previous = Config::clone(self.graph().get_config());
+                previous.remove(name);
+                self.graph_mut().remove(previous, name);
+                return Err(e.to_string());
+            }
+        };
+
+        // Fails only if the processor has already stopped
+        if self.tasks.send(task).is_err() {
+            log::error!("Task {} started after the processor stopped", name);
+        }
+
+        Ok(())
+    }
+
+    pub async fn add_source(&self, cfg: SourceConfigs) -> Result<(), String> {
+        let _change = self.changes.lock().await;
+        let mut candidate = Config::clone(self.graph().get_config());
+        candidate.sources.push(SourceConfigs::clone(&cfg));
+        self.validate(&candidate)?;
+
+        let ids = id_generator(candidate.id_seed, cfg.name());
+        let source = self.registry.make_source(&cfg)?;
+        {
+            let mut unlocked_component = source.lock().await;
+            unlocked_component.set_clock(self.clock.clone());
+            unlocked_component.set_id_generator(ids.clone());
+            unlocked_component.init().await?;
+        }
+
+        let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
+        let mut supervisor = Supervisor::new(cfg.name(), cfg.restart());
+        supervisor.set_stop(self.stop.clone());
+        let metrics = Arc::new(ComponentMetrics::default());
+        let txs = {
+            let mut graph = self.graph_mut();
+            graph.insert(candidate, cfg.name(), ctrl_tx, supervisor.get_status(), metrics.clone(), ids);
+            cfg.downstreams().iter().map(|downstream| graph.attach(cfg.name(), downstream)).collect()
+        };
+
+        self.spawn(cfg.name(), cfg.placement(), run_source(source, txs, ctrl_rx, supervisor, metrics, SourceContext::new(cfg.trace(), self.clock.clone())))?;
+        log::info!("Added source {}", cfg.name());
+        Ok(())
+    }
+
+    pub async fn add_pu(&self, cfg: PUConfigs, upstreams: Vec<Downstream>) -> Result<(), String> {
+        let _change = self.changes.lock().await;
+        let mut candidate = Config::clone(self.graph().get_config());
+        candidate.pus.get_or_insert_with(Vec::new).push(PUConfigs::clone(&cfg));
+        Self::add_upstreams(&mut candidate, cfg.name(), &upstreams)?;
+        self.validate(&candidate)?;
+
+        let ids = id_generator(candidate.id_seed, cfg.name());
+        let timers = Timers::new(cfg.timers(), self.clock.clone(), ids.clone())?;
+        let pu = self.registry.make_pu(&cfg)?;
+        {
+            let mut unlocked_component = pu.lock().await;
+            unlocked_component.set_clock(self.clock.clone());
+            unlocked_component.set_id_generator(ids.clone());
+            unlocked_component.set_markets(self.markets.clone());
+            unlocked_component.init().await?;
+        }
+
+        let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
+        let mut supervisor = Supervisor::new(cfg.name(), cfg.restart());
+        supervisor.set_stop(self.stop.clone());
+        let metrics = Arc::new(ComponentMetrics::default());
+        let (txs, rxs) = {
+            let mut graph = self.graph_mut();
+            graph.insert(candidate, cfg.name(), ctrl_tx, supervisor.get_status(), metrics.clone(), ids);
+            let txs = cfg.downstreams().iter().map(|downstream| graph.attach(cfg.name(), downstream)).collect();
+            let rxs = upstreams.iter().map(|upstream| graph.connect(upstream.name(), &upstream.with_name(cfg.name()))).collect();
+            (txs, rxs)
+        };
+
+        self.spawn(cfg.name(), cfg.placement(), run_pu(pu, txs, Inputs::new(rxs, MergePolicy::clone(cfg.merge()), timers), ctrl_rx, supervisor, metrics))?;
+        log::info!("Added PU {}", cfg.name());
+        Ok(())
+    }
+
+    pub async fn add_sink(&self, cfg: SinkConfigs, upstreams: Vec<Downstream>) -> Result<(), String> {
+        let _change = self.changes.lock().await;
+        let mut candidate = Config::clone(self.graph().get_config());
+        candidate.sinks.push(SinkConfigs::clone(&cfg));
+        Self::add_upstreams(&mut candidate, cfg.name(), &upstreams)?;
+        self.validate(&candidate)?;
+
+        let ids = id_generator(candidate.id_seed, cfg.name());
+        let timers = Timers::new(cfg.timers(), self.clock.clone(), ids.clone())?;
+        let sink = self.registry.make_sink(&cfg)?;
+        {
+            let mut unlocked_component = sink.lock().await;
+            unlocked_component.set_clock(self.clock.clone());
+            unlocked_component.set_id_generator(ids.clone());
+            unlocked_component.init().await?;
+        }
+
+        let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
+        let mut supervisor = Supervisor::new(cfg.name(), cfg.restart());
+        supervisor.set_stop(self.stop.clone());
+        let metrics = Arc::new(ComponentMetrics::default());
+        let rxs = {
+            let mut graph = self.graph_mut();
+            graph.insert(candidate, cfg.name(), ctrl_tx, supervisor.get_status(), metrics.clone(), ids);
+            upstreams.iter().map(|upstream| graph.connect(upstream.name(), &upstream.with_name(cfg.name()))).collect()
+        };
+
+        self.spawn(cfg.name(), cfg.placement(), run_sink(sink, Inputs::new(rxs, MergePolicy::clone(cfg.merge()), timers), ctrl_rx, supervisor, metrics, SinkContext::new(self.traces.clone(), self.clock.clone())))?;
+        log::info!("Added sink {}", cfg.name());
+        Ok(())
+    }
+
+    // The neighbours keep running, so a component cannot be removed if that leaves one of them without inputs or outputs
+    pub async fn remove(&self, name: &str) -> Result<(), String> {
+        let _change = self.changes.lock().await;
+        let mut candidate = Config::clone(self.graph().get_config());
+        if !candidate.remove(name) {
+            return Err(format!("Cannot find component {}", name));
+        }
+        self.validate(&candidate)?;
+
+        self.graph_mut().remove(candidate, name);
+        log::info!("Removed component {}", name);
+        Ok(())
+    }
+
+    // Getters
+    pub fn graph(&self) -> RwLockReadGuard<'_, Graph> {
+        self.graph.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn graph_mut(&self) -> RwLockWriteGuard<'_, Graph> {
+        self.graph.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get_traces(&self) -> &Traces {
+        &self.traces
+    }
+
+    pub fn get_clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
 }
 
 pub(crate) struct Processor {
     name: String,
-    sources: Vec<Sources>,
-    pus: Vec<PUs>,
-    sinks: Vec<Sinks>,
-    txs: HashMap<String, Vec<Sender<Objects>>>,
-    rxs: HashMap<String, Vec<Receiver<Objects>>>,
+    sources: Vec<SharedSource>,
+    pus: Vec<SharedPU>,
+    sinks: Vec<SharedSink>,
+    txs: HashMap<String, Vec<Edge>>,
+    rxs: HashMap<String, Vec<Arc<Receiver<Objects>>>>,
+    timers: HashMap<String, Timers>,
 
     // Config
     cfg: Config,
+    registry: Arc<Registry>,
+    clock: Arc<dyn Clock>,
+    markets: Arc<Markets>,
+    // Shared by each component and its timers
+    ids: HashMap<String, Arc<dyn IdGenerator>>,
 
     // Control
     ctrl_txs: HashMap<String, Sender<Controls>>,
-    ctrl_rxs: HashMap<String, Receiver<Controls>>
+    ctrl_rxs: HashMap<String, Receiver<Controls>>,
+
+    // Supervision
+    supervisors: HashMap<String, Supervisor>,
+    metrics: HashMap<String, Arc<ComponentMetrics>>
 }
 
 impl Processor {
-    pub fn new(processor_name: &str, cfg: &Config) -> Self {
+    pub fn new(processor_name: &str, cfg: &Config, registry: Arc<Registry>) -> Result<Self, String> {
         log::info!("Creating processor {} - version {}", processor_name, cfg.version);
 
+        let clock = clock::clock(cfg.clock);
+        let id_seed = cfg.id_seed;
+
         // Channels store
-        let mut txs = HashMap::<String, Vec<Sender<Objects>>>::new();
-        let mut rxs = HashMap::<String, Vec<Receiver<Objects>>>::new();
+        let mut txs = HashMap::<String, Vec<Edge>>::new();
+        let mut rxs = HashMap::<String, Vec<Arc<Receiver<Objects>>>>::new();
+        let mut timers = HashMap::<String, Timers>::new();
+        let mut ids = HashMap::<String, Arc<dyn IdGenerator>>::new();
         let mut ctrl_txs = HashMap::<String, Sender<Controls>>::new();
         let mut ctrl_rxs = HashMap::<String, Receiver<Controls>>::new();
+        let mut supervisors = HashMap::<String, Supervisor>::new();
+        let mut metrics = HashMap::<String, Arc<ComponentMetrics>>::new();
 
         // Populate channels
         for cfg in &cfg.sources {
             // Data
             txs.insert(String::from(cfg.name()), vec![]);
+            ids.insert(String::from(cfg.name()), id_generator(id_seed, cfg.name()));
 
             // Control
             let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
             ctrl_txs.insert(String::from(cfg.name()), ctrl_tx);
             ctrl_rxs.insert(String::from(cfg.name()), ctrl_rx);
+
+            // Supervision
+            supervisors.insert(String::from(cfg.name()), Supervisor::new(cfg.name(), cfg.restart()));
+            metrics.insert(String::from(cfg.name()), Arc::new(ComponentMetrics::default()));
         }
 
-        match &cfg.pus {
-            Some(cfgs) => {
-                for cfg in cfgs {
-                    // Data
-                    txs.insert(String::from(cfg.name()), vec![]);
-                    rxs.insert(String::from(cfg.name()), vec![]);
+        if let Some(cfgs) = &cfg.pus {
+            for cfg in cfgs {
+                // Data
+                txs.insert(String::from(cfg.name()), vec![]);
+                rxs.insert(String::from(cfg.name()), vec![]);
+                let component_ids = id_generator(id_seed, cfg.name());
+                timers.insert(String::from(cfg.name()), Timers::new(cfg.timers(), clock.clone(), component_ids.clone())?);
+                ids.insert(String::from(cfg.name()), component_ids);
 
-                    // Control
-                    let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
-                    ctrl_txs.insert(String::from(cfg.name()), ctrl_tx);
-                    ctrl_rxs.insert(String::from(cfg.name()), ctrl_rx);
-                }
-            },
-            None => ()
+                // Control
+                let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
+                ctrl_txs.insert(String::from(cfg.name()), ctrl_tx);
+                ctrl_rxs.insert(String::from(cfg.name()), ctrl_rx);
+
+                // Supervision
+                supervisors.insert(String::from(cfg.name()), Supervisor::new(cfg.name(), cfg.restart()));
+                metrics.insert(String::from(cfg.name()), Arc::new(ComponentMetrics::default()));
+            }
         }
 
         for cfg in &cfg.sinks {
             // Data
             rxs.insert(String::from(cfg.name()), vec![]);
+            let component_ids = id_generator(id_seed, cfg.name());
+            timers.insert(String::from(cfg.name()), Timers::new(cfg.timers(), clock.clone(), component_ids.clone())?);
+            ids.insert(String::from(cfg.name()), component_ids);
 
             // Control
             let (ctrl_tx, ctrl_rx) = unbounded::<Controls>();
             ctrl_txs.insert(String::from(cfg.name()), ctrl_tx);
             ctrl_rxs.insert(String::from(cfg.name()), ctrl_rx);
+
+            // Supervision
+            supervisors.insert(String::from(cfg.name()), Supervisor::new(cfg.name(), cfg.restart()));
+            metrics.insert(String::from(cfg.name()), Arc::new(ComponentMetrics::default()));
         }
 
         // Create sources
-        let mut sources = Vec::<Sources>::new();
+        let mut sources = Vec::<SharedSource>::new();
         for cfg in &cfg.sources {
-            let source = sources::factory::make(cfg);
-            sources.push(source);
+            sources.push(registry.make_source(cfg)?);
 
             for downstream in cfg.downstreams() {
-                let (tx, rx) = bounded(cfg.queue_size());
+                let (tx, rx) = edge(downstream, cfg.queue_size());
                 match txs.get_mut(cfg.name()) {
                     Some(txs) => txs.push(tx),
-                    None => panic!("Cannot find source {} inlet", cfg.name())
+                    None => return Err(format!("Cannot find source {} inlet", cfg.name()))
                 }
 
-                match rxs.get_mut(downstream) {
+                match rxs.get_mut(downstream.name()) {
                     Some(rxs) => rxs.push(rx),
-                    None => panic!("Cannot find source {} outlet for {}", cfg.name(), downstream)
+                    None => return Err(format!("Cannot find source {} outlet for {}", cfg.name(), downstream.name()))
                 }
             }
         }
 
         // Create processing units
-        let mut pus = Vec::<PUs>::new();
-        match &cfg.pus {
-            Some(cfgs) => {
-                for cfg in cfgs {
-                    let proc = processing_units::factory::make(cfg);
-                    pus.push(proc);
-
-                    for downstream in cfg.downstreams() {
-                        let (tx, rx) = bounded(cfg.queue_size());
-                        match txs.get_mut(cfg.name()) {
-                            Some(txs) => txs.push(tx),
-                            None => panic!("Cannot find source {} inlet", cfg.name())
-                        }
+        let mut pus = Vec::<SharedPU>::new();
+        if let Some(cfgs) = &cfg.pus {
+            for cfg in cfgs {
+                pus.push(registry.make_pu(cfg)?);
 
-                        match rxs.get_mut(downstream) {
-                            Some(rxs) => rxs.push(rx),
-                            None => panic!("Cannot find source {} outlet for {}", cfg.name(), downstream)
-                        }
+                for downstream in cfg.downstreams() {
+                    let (tx, rx) = edge(downstream, cfg.queue_size());
+                    match txs.get_mut(cfg.name()) {
+                        Some(txs) => txs.push(tx),
+                        None => return Err(format!("Cannot find PU {} inlet", cfg.name()))
+                    }
+
+                    match rxs.get_mut(downstream.name()) {
+                        Some(rxs) => rxs.push(rx),
+                        None => return Err(format!("Cannot find PU {} outlet for {}", cfg.name(), downstream.name()))
                     }
                 }
             }
-            None => ()
         }
 
         // Create sinks
-        let mut sinks = Vec::<Sinks>::new();
+        let mut sinks = Vec::<SharedSink>::new();
         for cfg in &cfg.sinks {
-            let sink = sinks::factory::make(cfg);
-            sinks.push(sink);
+            sinks.push(registry.make_sink(cfg)?);
         }
 
-        Self {
+        Ok(Self {
             name: String::from(processor_name),
             sources,
             pus,
             sinks,
             txs,
             rxs,
+            timers,
             cfg: Config::clone(cfg),
+            registry,
+            clock,
+            markets: Arc::new(Markets::default()),
+            ids,
             ctrl_txs,
-            ctrl_rxs
-        }
+            ctrl_rxs,
+            supervisors,
+            metrics
+        })
     }
 
     pub async fn init(&mut self) -> std::io::Result<()> {
         log::info!("Initializing processor {}", self.name);
 
-        for source_enum in &self.sources {
-            match source_enum {
-                Sources::BinanceSpotSource(source) => init!(source),
-                Sources::FileSource(source) => init!(source),
-                Sources::MockSource(source) => init!(source)
+        for source in &self.sources {
+            let mut unlocked_component = source.lock().await;
+            unlocked_component.set_clock(self.clock.clone());
+            let ids = self.ids.get(unlocked_component.get_name()).cloned().unwrap_or_else(|| panic!("Cannot find IDs for component {}", unlocked_component.get_name()));
+            unlocked_component.set_id_generator(ids);
+            match unlocked_component.init().await {
+                Ok(()) => log::info!("Initialized component {}", unlocked_component.get_name()),
+                Err(e) if e.action() == Action::Stop => return Err(std::io::Error::other(format!("Cannot initialize component {} - {}", unlocked_component.get_name(), e))),
+                Err(e) => log::error!("Failed to initialize component {} - {}", unlocked_component.get_name(), e)
             }
         }
 
-        for processing_enum in &self.pus {
-            match processing_enum {
-                PUs::MockPU(processing) => init!(processing),
-                PUs::OrderManagerPU(processing) => init!(processing),
-                PUs::OrderbookManagerPU(processing) => init!(processing),
-                PUs::StrategyPU(processing) => init!(processing)
+        for pu in &self.pus {
+            let mut unlocked_component = pu.lock().await;
+            unlocked_component.set_clock(self.clock.clone());
+            let ids = self.ids.get(unlocked_component.get_name()).cloned().unwrap_or_else(|| panic!("Cannot find IDs for component {}", unlocked_component.get_name()));
+            unlocked_component.set_id_generator(ids);
+            unlocked_component.set_markets(self.markets.clone());
+            match unlocked_component.init().await {
+                Ok(()) => log::info!("Initialized component {}", unlocked_component.get_name()),
+                Err(e) if e.action() == Action::Stop => return Err(std::io::Error::other(format!("Cannot initialize component {} - {}", unlocked_component.get_name(), e))),
+                Err(e) => log::error!("Failed to initialize component {} - {}", unlocked_component.get_name(), e)
             }
         }
 
-        for sink_enum in &self.sinks {
-            match sink_enum {
-                Sinks::BinanceSpotSink(sink) => init!(sink),
-                Sinks::FileSink(sink) => init!(sink),
-                Sinks::MockSink(sink) => init!(sink)
+        for sink in &self.sinks {
+            let mut unlocked_component = sink.lock().await;
+            unlocked_component.set_clock(self.clock.clone());
+            let ids = self.ids.get(unlocked_component.get_name()).cloned().unwrap_or_else(|| panic!("Cannot find IDs for component {}", unlocked_component.get_name()));
+            unlocked_component.set_id_generator(ids);
+            match unlocked_component.init().await {
+                Ok(()) => log::info!("Initialized component {}", unlocked_component.get_name()),
+                Err(e) if e.action() == Action::Stop => return Err(std::io::Error::other(format!("Cannot initialize component {} - {}", unlocked_component.get_name(), e))),
+                Err(e) => log::error!("Failed to initialize component {} - {}", unlocked_component.get_name(), e)
             }
         }
 
@@ -438,40 +796,540 @@ impl Processor {
     pub async fn run(mut self) -> std::io::Result<()> {
         log::info!("Running processor {}", self.name);
 
-        let control_state = ControlState::new(self.cfg, self.ctrl_txs);
-        let mut ctrl = control::build_control(control_state);
-        let mut tasks = Vec::new();
+        let shutdown_timeout = self.cfg.shutdown_timeout.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
+        let statuses = self.supervisors.iter().map(|(name, supervisor)| (String::clone(name), supervisor.get_status())).collect();
+        let edges = self.txs.iter().flat_map(|(name, txs)| txs.iter().map(|tx| tx.probe(name))).collect();
+        let traced = self.cfg.sources.iter().filter(|cfg| cfg.trace()).map(|cfg| String::from(cfg.name())).collect::<Vec<_>>();
+        let stack_size = self.cfg.stack_size * 1024 * 1024;
+        let mut placements = self.cfg.sources.iter().map(|cfg| (String::from(cfg.name()), Placement::clone(cfg.placement())))
+                                                    .chain(self.cfg.pus.iter().flatten().map(|cfg| (String::from(cfg.name()), Placement::clone(cfg.placement()))))
+                                                    .chain(self.cfg.sinks.iter().map(|cfg| (String::from(cfg.name()), Placement::clone(cfg.placement()))))
+                                                    .collect::<HashMap<_, _>>();
+        let mut merges = self.cfg.pus.iter().flatten().map(|cfg| (String::from(cfg.name()), MergePolicy::clone(cfg.merge())))
+                                                      .chain(self.cfg.sinks.iter().map(|cfg| (String::from(cfg.name()), MergePolicy::clone(cfg.merge()))))
+                                                      .collect::<HashMap<_, _>>();
+        let traces = Arc::new(Traces::default());
+        let (task_tx, task_rx) = unbounded::<JoinHandle<()>>();
+        let (stop_tx, stop_rx) = unbounded::<String>();
+
+        // Kept until the end, so that the tasks can still be stopped once the control has shut down
+        let graph = Graph::new(self.cfg, self.ctrl_txs, statuses, self.metrics.clone(), self.ids, edges);
+        let runtime = Arc::new(Runtime::new(self.registry, graph, traces.clone(), self.clock.clone(), self.markets, task_tx, stop_tx.clone()));
+        let mut ctrl = control::build_control(ControlState::new(runtime.clone()));
+        let mut tasks = Vec::<JoinHandle<()>>::new();
 
-        for source_enum in &self.sources {
-            match source_enum {
-                Sources::BinanceSpotSource(source) => run_source!(source, self, ctrl, tasks),
-                Sources::FileSource(source) => run_source!(source, self, ctrl, tasks),
-                Sources::MockSource(source) => run_source!(source, self, ctrl, tasks)
+        for source in &self.sources {
+            let name = {
+                let unlocked_component = source.lock().await;
+                ctrl = unlocked_component.endpoints(ctrl);
+                String::from(unlocked_component.get_name())
             };
+
+            let ctrl_rx = self.ctrl_rxs.remove(&name).unwrap_or_else(|| panic!("Cannot find ctrl rx for source {}", name));
+            let txs = self.txs.remove(&name).unwrap_or_else(|| panic!("Cannot find txs for source {}", name));
+            let mut supervisor = self.supervisors.remove(&name).unwrap_or_else(|| panic!("Cannot find supervisor for source {}", name));
+            supervisor.set_stop(stop_tx.clone());
+            let metrics = self.metrics.get(&name).cloned().unwrap_or_else(|| panic!("Cannot find metrics for source {}", name));
+            let placement = placements.remove(&name).unwrap_or_default();
+            tasks.push(place(&name, &placement, stack_size, run_source(source.clone(), txs, ctrl_rx, supervisor, metrics, SourceContext::new(traced.contains(&name), self.clock.clone()))).map_err(|e| std::io::Error::other(e.to_string()))?);
         }
 
-        for processing_enum in &self.pus {
-            match processing_enum {
-                PUs::MockPU(punit) => run_pu!(punit, self, ctrl, tasks),
-                PUs::OrderManagerPU(punit) => run_pu!(punit, self, ctrl, tasks),
-                PUs::OrderbookManagerPU(punit) => run_pu!(punit, self, ctrl, tasks),
-                PUs::StrategyPU(punit) => run_pu!(punit, self, ctrl, tasks)
+        for pu in &self.pus {
+            let name = {
+                let unlocked_component = pu.lock().await;
+                ctrl = unlocked_component.endpoints(ctrl);
+                String::from(unlocked_component.get_name())
             };
+
+            let ctrl_rx = self.ctrl_rxs.remove(&name).unwrap_or_else(|| panic!("Cannot find ctrl rx for PU {}", name));
+            let txs = self.txs.remove(&name).unwrap_or_else(|| panic!("Cannot find txs for PU {}", name));
+            let rxs = self.rxs.remove(&name).unwrap_or_else(|| panic!("Cannot find rxs for PU {}", name));
+            let mut supervisor = self.supervisors.remove(&name).unwrap_or_else(|| panic!("Cannot find supervisor for PU {}", name));
+            supervisor.set_stop(stop_tx.clone());
+            let metrics = self.metrics.get(&name).cloned().unwrap_or_else(|| panic!("Cannot find metrics for PU {}", name));
+            let timers = self.timers.remove(&name).unwrap_or_else(|| panic!("Cannot find timers for PU {}", name));
+            let inputs = Inputs::new(rxs, merges.remove(&name).unwrap_or_default(), timers);
+            let placement = placements.remove(&name).unwrap_or_default();
+            tasks.push(place(&name, &placement, stack_size, run_pu(pu.clone(), txs, inputs, ctrl_rx, supervisor, metrics)).map_err(|e| std::io::Error::other(e.to_string()))?);
         }
 
-        for sink_enum in &self.sinks {
-            match sink_enum {
-                Sinks::BinanceSpotSink(sink) => run_sink!(sink, self, ctrl, tasks),
-                Sinks::FileSink(sink) => run_sink!(sink, self, ctrl, tasks),
-                Sinks::MockSink(sink) => run_sink!(sink, self, ctrl, tasks)
+        for sink in &self.sinks {
+            let name = {
+                let unlocked_component = sink.lock().await;
+                ctrl = unlocked_component.endpoints(ctrl);
+                String::from(unlocked_component.get_name())
             };
+
+            let ctrl_rx = self.ctrl_rxs.remove(&name).unwrap_or_else(|| panic!("Cannot find ctrl rx for sink {}", name));
+            let rxs = self.rxs.remove(&name).unwrap_or_else(|| panic!("Cannot find rxs for sink {}", name));
+            let mut supervisor = self.supervisors.remove(&name).unwrap_or_else(|| panic!("Cannot find supervisor for sink {}", name));
+            supervisor.set_stop(stop_tx.clone());
+            let metrics = self.metrics.get(&name).cloned().unwrap_or_else(|| panic!("Cannot find metrics for sink {}", name));
+            let timers = self.timers.remove(&name).unwrap_or_else(|| panic!("Cannot find timers for sink {}", name));
+            let inputs = Inputs::new(rxs, merges.remove(&name).unwrap_or_default(), timers);
+            let placement = placements.remove(&name).unwrap_or_default();
+            tasks.push(place(&name, &placement, stack_size, run_sink(sink.clone(), inputs, ctrl_rx, supervisor, metrics, SinkContext::new(traces.clone(), self.clock.clone()))).map_err(|e| std::io::Error::other(e.to_string()))?);
         }
 
-        tokio::select! {
-            _ = futures::future::join_all(tasks) => (),
-            _ = ctrl.launch() => ()
+        // The tasks of the components added at runtime join the others
+        let mut tasks = tasks.into_iter().collect::<FuturesUnordered<_>>();
+        let launch = ctrl.launch();
+        tokio::pin!(launch);
+
+        loop {
+            tokio::select! {
+                Ok(task) = task_rx.recv_async() => tasks.push(task),
+                // The processor completes once every task has, e.g. at the end of a replay
+                task = tasks.next() => if task.is_none() {
+                    return Ok(());
+                },
+                Ok(e) = stop_rx.recv_async() => {
+                    log::error!("Stopping processor {} - {}", self.name, e);
+                    break;
+                },
+                _ = &mut launch => break
+            }
+        }
+
+        // Sources stop first, then every PU and sink stops once its inputs are drained
+        log::info!("Shutting down processor {}", self.name);
+        let sources = {
+            let graph = runtime.graph();
+            graph.get_config().sources.iter().filter_map(|cfg| graph.get_ctrl_tx(cfg.name()).cloned()).collect::<Vec<_>>()
         };
+        for ctrl_tx in sources {
+            // Fails only if the source has already completed
+            let _ = ctrl_tx.send(Controls::Shutdown);
+        }
+
+        tasks.extend(task_rx.drain());
+        if tokio::time::timeout(Duration::from_millis(shutdown_timeout), tasks.by_ref().count()).await.is_ok() {
+            log::info!("Processor {} drained", self.name);
+            return Ok(());
+        }
+
+        // The PUs and sinks left stop without draining, but still run their shutdown hooks, e.g. to flush a file
+        log::error!("Processor {} not drained within {} ms, stopping the remaining tasks", self.name, shutdown_timeout);
+        let others = {
+            let graph = runtime.graph();
+            let cfg = graph.get_config();
+            cfg.pus.iter().flatten().map(|cfg| cfg.name())
+                   .chain(cfg.sinks.iter().map(|cfg| cfg.name()))
+                   .filter_map(|name| graph.get_ctrl_tx(name).cloned())
+                   .collect::<Vec<_>>()
+        };
+        for ctrl_tx in others {
+            // Fails only if the task has already completed
+            let _ = ctrl_tx.send(Controls::Shutdown);
+        }
+
+        match tokio::time::timeout(Duration::from_millis(HOOKS_TIMEOUT), tasks.count()).await {
+            Ok(_) => log::info!("Processor {} shut down", self.name),
+            Err(_) => log::error!("Processor {} not shut down within {} ms, dropping the remaining tasks", self.name, HOOKS_TIMEOUT)
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::control::Controls;
+    use crate::components::processing_units::{ Outputs, ProcessingUnit };
+    use crate::components::sinks::Sink;
+    use crate::components::sources::Source;
+    use crate::config::{ edge::{ Downstream, EdgeConfig }, merge::MergePolicy, restart::RestartPolicy };
+    use crate::edge::edge;
+    use crate::error::HolocoreError;
+    use crate::inputs::Inputs;
+    use crate::metrics::ComponentMetrics;
+    use crate::objects::{ Objects, mock::MockV1 };
+    use crate::processor::{ SinkContext, SourceContext, run_pu, run_sink, run_source };
+    use crate::scheduler::Timers;
+    use crate::supervisor::Supervisor;
+    use crate::trace::Traces;
+    use crate::utils::{ clock::WallClock, time, uuid };
+
+    use async_trait::async_trait;
+    use flume::unbounded;
+    use std::sync::{ Arc, atomic::{ AtomicBool, AtomicU64, Ordering } };
+    use tokio::sync::Mutex;
+
+    struct CountingSource {
+        sent: Arc<AtomicU64>
+    }
+
+    #[async_trait]
+    impl Source for CountingSource {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
+            tokio::task::yield_now().await;
+            let sequence_number = self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok((Some(Objects::MockV1(MockV1::new(uuid::uuid(), time::now_nanos(), sequence_number, uuid::uuid()))), false))
+        }
+
+        fn get_name(&self) -> &str {
+            "CountingSource"
+        }
+    }
+
+    struct ForwardingPU {
+        stopped: Arc<AtomicBool>
+    }
+
+    #[async_trait]
+    impl ProcessingUnit for ForwardingPU {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            Ok(())
+        }
+
+        async fn execute(&mut self, obj: Objects) -> Result<(Outputs, bool), HolocoreError> {
+            Ok((Outputs::from(obj), false))
+        }
+
+        async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+            self.stopped.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &str {
+            "ForwardingPU"
+        }
+    }
+
+    struct CountingSink {
+        received: Arc<AtomicU64>,
+        flushed: Arc<AtomicU64>
+    }
+
+    #[async_trait]
+    impl Sink for CountingSink {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            Ok(())
+        }
+
+        async fn send(&mut self, _obj: Objects) -> Result<(), HolocoreError> {
+            self.received.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn shutdown(&mut self) -> Result<(), HolocoreError> {
+            self.flushed.store(self.received.load(Ordering::SeqCst), Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &str {
+            "CountingSink"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_on_shutdown() {
+        let sent = Arc::new(AtomicU64::new(0));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let received = Arc::new(AtomicU64::new(0));
+        let flushed = Arc::new(AtomicU64::new(0));
+
+        let (source_tx, pu_rx) = edge(&Downstream::Name(String::from("ForwardingPU")), 16);
+        let (pu_tx, sink_rx) = edge(&Downstream::Name(String::from("CountingSink")), 16);
+        let (source_ctrl_tx, source_ctrl_rx) = unbounded::<Controls>();
+        let (_pu_ctrl_tx, pu_ctrl_rx) = unbounded::<Controls>();
+        let (_sink_ctrl_tx, sink_ctrl_rx) = unbounded::<Controls>();
+
+        let source = Arc::new(Mutex::new(CountingSource { sent: sent.clone() }));
+        let pu = Arc::new(Mutex::new(ForwardingPU { stopped: stopped.clone() }));
+        let sink = Arc::new(Mutex::new(CountingSink { received: received.clone(), flushed: flushed.clone() }));
+
+        let tasks = vec![
+            tokio::spawn(run_source(source, vec![source_tx], source_ctrl_rx, Supervisor::new("CountingSource", &RestartPolicy::Never), Arc::default(), SourceContext::new(false, Arc::new(WallClock)))),
+            tokio::spawn(run_pu(pu, vec![pu_tx], Inputs::new(vec![pu_rx], MergePolicy::Arrival, Timers::default()), pu_ctrl_rx, Supervisor::new("ForwardingPU", &RestartPolicy::Never), Arc::default())),
+            tokio::spawn(run_sink(sink, Inputs::new(vec![sink_rx], MergePolicy::Arrival, Timers::default()), sink_ctrl_rx, Supervisor::new("CountingSink", &RestartPolicy::Never), Arc::default(), SinkContext::new(Arc::default(), Arc::new(WallClock))))
+        ];
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        source_ctrl_tx.send(Controls::Shutdown).unwrap();
+        futures::future::join_all(tasks).await;
+
+        // Nothing emitted by the source is lost in the queues
+        assert!(sent.load(Ordering::SeqCst) > 0);
+        assert_eq!(received.load(Ordering::SeqCst), sent.load(Ordering::SeqCst));
+        assert_eq!(flushed.load(Ordering::SeqCst), sent.load(Ordering::SeqCst));
+        assert!(stopped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_undrained() {
+        let received = Arc::new(AtomicU64::new(0));
+        let flushed = Arc::new(AtomicU64::new(0));
+
+        // The upstream never stops, so the inputs of the sink are never drained
+        let (upstream_tx, sink_rx) = edge(&Downstream::Name(String::from("CountingSink")), 16);
+        let (sink_ctrl_tx, sink_ctrl_rx) = unbounded::<Controls>();
+        let sink = Arc::new(Mutex::new(CountingSink { received: received.clone(), flushed: flushed.clone() }));
+        let task = tokio::spawn(run_sink(sink, Inputs::new(vec![sink_rx], MergePolicy::Arrival, Timers::default()), sink_ctrl_rx, Supervisor::new("CountingSink", &RestartPolicy::Never), Arc::default(), SinkContext::new(Arc::default(), Arc::new(WallClock))));
+
+        upstream_tx.send(Objects::MockV1(MockV1::new(uuid::uuid(), time::now_nanos(), 1, uuid::uuid()))).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        sink_ctrl_tx.send(Controls::Shutdown).unwrap();
+        task.await.unwrap();
+        assert_eq!(flushed.load(Ordering::SeqCst), 1);
+    }
+
+    // Fails the first recv after every init, until failures runs out
+    struct FlakySource {
+        inits: u64,
+        failures: u64,
+        sent: u64,
+        failed: bool
+    }
+
+    #[async_trait]
+    impl Source for FlakySource {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            self.inits += 1;
+            self.failed = false;
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Result<(Option<Objects>, bool), HolocoreError> {
+            if self.failures > 0 && !self.failed {
+                self.failures -= 1;
+                self.failed = true;
+                return Err(HolocoreError::Network(String::from("connection dropped")));
+            }
+
+            if self.sent == 3 {
+                return Ok((None, true));
+            }
+
+            self.sent += 1;
+            Ok((Some(Objects::MockV1(MockV1::new(uuid::uuid(), time::now_nanos(), self.sent, uuid::uuid()))), false))
+        }
+
+        fn get_name(&self) -> &str {
+            "FlakySource"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restart_on_failure() {
+        let received = Arc::new(AtomicU64::new(0));
+        let flushed = Arc::new(AtomicU64::new(0));
+
+        let (source_tx, sink_rx) = edge(&Downstream::Name(String::from("CountingSink")), 16);
+        let (_source_ctrl_tx, source_ctrl_rx) = unbounded::<Controls>();
+        let (_sink_ctrl_tx, sink_ctrl_rx) = unbounded::<Controls>();
+
+        let policy = RestartPolicy::OnFailure { max_retries: 2, backoff: 1, max_backoff: 10 };
+        let supervisor = Supervisor::new("FlakySource", &policy);
+        let status = supervisor.get_status();
+
+        let source = Arc::new(Mutex::new(FlakySource { inits: 1, failures: 2, sent: 0, failed: false }));
+        let sink = Arc::new(Mutex::new(CountingSink { received: received.clone(), flushed: flushed.clone() }));
+
+        let tasks = vec![
+            tokio::spawn(run_source(source.clone(), vec![source_tx], source_ctrl_rx, supervisor, Arc::default(), SourceContext::new(false, Arc::new(WallClock)))),
+            tokio::spawn(run_sink(sink, Inputs::new(vec![sink_rx], MergePolicy::Arrival, Timers::default()), sink_ctrl_rx, Supervisor::new("CountingSink", &RestartPolicy::Never), Arc::default(), SinkContext::new(Arc::default(), Arc::new(WallClock))))
+        ];
+        futures::future::join_all(tasks).await;
+
+        // Two failures, each followed by a new init, then the source completes
+        let report = status.report();
+        assert_eq!(report.restarts, 2);
+        assert_eq!(report.last_error, Some(String::from("connection dropped")));
+        assert_eq!(source.lock().await.inits, 3);
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_connect_during_backoff() {
+        let received = Arc::new(AtomicU64::new(0));
+        let flushed = Arc::new(AtomicU64::new(0));
+
+        let (source_ctrl_tx, source_ctrl_rx) = unbounded::<Controls>();
+        let (_sink_ctrl_tx, sink_ctrl_rx) = unbounded::<Controls>();
+
+        // Fails straight away, then waits 200 ms before its restart
+        let policy = RestartPolicy::OnFailure { max_retries: 1, backoff: 200, max_backoff: 200 };
+        let source = Arc::new(Mutex::new(FlakySource { inits: 1, failures: 1, sent: 0, failed: false }));
+        let source_task = tokio::spawn(run_source(source, vec![], source_ctrl_rx, Supervisor::new("FlakySource", &policy), Arc::default(), SourceContext::new(false, Arc::new(WallClock))));
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let (source_tx, sink_rx) = edge(&Downstream::Name(String::from("CountingSink")), 16);
+        source_ctrl_tx.send(Controls::Connect(source_tx)).unwrap();
+
+        let sink = Arc::new(Mutex::new(CountingSink { received: received.clone(), flushed: flushed.clone() }));
+        let sink_task = tokio::spawn(run_sink(sink, Inputs::new(vec![sink_rx], MergePolicy::Arrival, Timers::default()), sink_ctrl_rx, Supervisor::new("CountingSink", &RestartPolicy::Never), Arc::default(), SinkContext::new(Arc::default(), Arc::new(WallClock))));
+        source_task.await.unwrap();
+        sink_task.await.unwrap();
+
+        // The sink connected while the source was waiting gets everything sent after the restart
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+        assert_eq!(flushed.load(Ordering::SeqCst), 3);
+    }
+
+    // Forwards every object to the default port and two copies of it to the orders port
+    struct SplittingPU {}
+
+    #[async_trait]
+    impl ProcessingUnit for SplittingPU {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            Ok(())
+        }
+
+        async fn execute(&mut self, obj: Objects) -> Result<(Outputs, bool), HolocoreError> {
+            let mut outputs = Outputs::new();
+            outputs.push_to("orders", obj.clone());
+            outputs.push_to("orders", obj.clone());
+            outputs.push(obj);
+            Ok((outputs, false))
+        }
+
+        fn get_name(&self) -> &str {
+            "SplittingPU"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pu_ports() {
+        let (input, pu_rx) = edge(&Downstream::Name(String::from("SplittingPU")), 16);
+        let (market, market_rx) = edge(&Downstream::Name(String::from("Recorder")), 16);
+        let (orders, orders_rx) = edge(&Downstream::Edge(EdgeConfig { name: String::from("OrderSink"), port: Some(String::from("orders")), ..Default::default() }), 16);
+        let (_pu_ctrl_tx, pu_ctrl_rx) = unbounded::<Controls>();
+
+        for idx in 0..3 {
+            input.send(Objects::MockV1(MockV1::new(uuid::uuid(), time::now_nanos(), idx, uuid::uuid()))).await.unwrap();
+        }
+        drop(input);
+
+        let pu = Arc::new(Mutex::new(SplittingPU {}));
+        let metrics = Arc::new(ComponentMetrics::default());
+        run_pu(pu, vec![market, orders], Inputs::new(vec![pu_rx], MergePolicy::Arrival, Timers::default()), pu_ctrl_rx, Supervisor::new("SplittingPU", &RestartPolicy::Never), metrics.clone()).await;
+
+        assert_eq!(market_rx.drain().count(), 3);
+        assert_eq!(orders_rx.drain().count(), 6);
+        assert_eq!(metrics.get_objs_in(), 3);
+        assert_eq!(metrics.get_objs_out(), 9);
+    }
+
+    // Cannot decode the first object and finds its settings broken on the third
+    struct PickyPU {}
+
+    #[async_trait]
+    impl ProcessingUnit for PickyPU {
+        async fn init(&mut self) -> Result<(), HolocoreError> {
+            Ok(())
+        }
+
+        async fn execute(&mut self, obj: Objects) -> Result<(Outputs, bool), HolocoreError> {
+            match obj.get_sequence_number() {
+                1 => Err(HolocoreError::Malformed(String::from("cannot decode object"))),
+                3 => Err(HolocoreError::Config(String::from("missing symbol"))),
+                _ => Ok((Outputs::from(obj), false))
+            }
+        }
+
+        fn get_name(&self) -> &str {
+            "PickyPU"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_classification() {
+        let received = Arc::new(AtomicU64::new(0));
+        let flushed = Arc::new(AtomicU64::new(0));
+
+        let (source_tx, pu_rx) = edge(&Downstream::Name(String::from("PickyPU")), 16);
+        let (pu_tx, sink_rx) = edge(&Downstream::Name(String::from("CountingSink")), 16);
+        let (_source_ctrl_tx, source_ctrl_rx) = unbounded::<Controls>();
+        let (_pu_ctrl_tx, pu_ctrl_rx) = unbounded::<Controls>();
+        let (_sink_ctrl_tx, sink_ctrl_rx) = unbounded::<Controls>();
+        let (stop_tx, stop_rx) = unbounded::<String>();
+
+        // Restarting cannot fix a config error, whatever the policy
+        let mut supervisor = Supervisor::new("PickyPU", &RestartPolicy::Always { backoff: 1, max_backoff: 10 });
+        supervisor.set_stop(stop_tx);
+        let status = supervisor.get_status();
+
+        let source = Arc::new(Mutex::new(FlakySource { inits: 1, failures: 0, sent: 0, failed: false }));
+        let pu = Arc::new(Mutex::new(PickyPU {}));
+        let sink = Arc::new(Mutex::new(CountingSink { received: received.clone(), flushed }));
+
+        let tasks = vec![
+            tokio::spawn(run_source(source, vec![source_tx], source_ctrl_rx, Supervisor::new("FlakySource", &RestartPolicy::Never), Arc::default(), SourceContext::new(false, Arc::new(WallClock)))),
+            tokio::spawn(run_pu(pu, vec![pu_tx], Inputs::new(vec![pu_rx], MergePolicy::Arrival, Timers::default()), pu_ctrl_rx, supervisor, Arc::default())),
+            tokio::spawn(run_sink(sink, Inputs::new(vec![sink_rx], MergePolicy::Arrival, Timers::default()), sink_ctrl_rx, Supervisor::new("CountingSink", &RestartPolicy::Never), Arc::default(), SinkContext::new(Arc::default(), Arc::new(WallClock))))
+        ];
+        futures::future::join_all(tasks).await;
+
+        // The first object is skipped, the second goes through and the third stops the pipeline
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+        assert_eq!(status.report().restarts, 0);
+        assert_eq!(status.report().last_error, Some(String::from("missing symbol")));
+        assert_eq!(stop_rx.try_recv().ok(), Some(String::from("Task PickyPU failed - missing symbol")));
+    }
+
+    #[tokio::test]
+    async fn test_latency_tracing() {
+        let received = Arc::new(AtomicU64::new(0));
+        let flushed = Arc::new(AtomicU64::new(0));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let traces = Arc::new(Traces::default());
+
+        let (source_tx, pu_rx) = edge(&Downstream::Name(String::from("ForwardingPU")), 16);
+        let (pu_tx, sink_rx) = edge(&Downstream::Name(String::from("CountingSink")), 16);
+        let (_source_ctrl_tx, source_ctrl_rx) = unbounded::<Controls>();
+        let (_pu_ctrl_tx, pu_ctrl_rx) = unbounded::<Controls>();
+        let (_sink_ctrl_tx, sink_ctrl_rx) = unbounded::<Controls>();
+
+        let source = Arc::new(Mutex::new(FlakySource { inits: 1, failures: 0, sent: 0, failed: false }));
+        let pu = Arc::new(Mutex::new(ForwardingPU { stopped }));
+        let sink = Arc::new(Mutex::new(CountingSink { received, flushed }));
+
+        let tasks = vec![
+            tokio::spawn(run_source(source, vec![source_tx], source_ctrl_rx, Supervisor::new("FlakySource", &RestartPolicy::Never), Arc::default(), SourceContext::new(true, Arc::new(WallClock)))),
+            tokio::spawn(run_pu(pu, vec![pu_tx], Inputs::new(vec![pu_rx], MergePolicy::Arrival, Timers::default()), pu_ctrl_rx, Supervisor::new("ForwardingPU", &RestartPolicy::Never), Arc::default())),
+            tokio::spawn(run_sink(sink, Inputs::new(vec![sink_rx], MergePolicy::Arrival, Timers::default()), sink_ctrl_rx, Supervisor::new("CountingSink", &RestartPolicy::Never), Arc::default(), SinkContext::new(traces.clone(), Arc::new(WallClock))))
+        ];
+        futures::future::join_all(tasks).await;
+
+        // Queueing and processing time of every component, for each of the 3 objects
+        let percentiles = traces.percentiles();
+        let mut hops = percentiles.keys().map(String::as_str).collect::<Vec<_>>();
+        hops.sort();
+        assert_eq!(hops, vec!["CountingSink", "FlakySource -> ForwardingPU", "ForwardingPU", "ForwardingPU -> CountingSink"]);
+        assert!(percentiles.values().all(|percentiles| percentiles.get_count() == 3));
+    }
+
+    #[tokio::test]
+    async fn test_runtime_edges() {
+        let (input, pu_rx) = edge(&Downstream::Name(String::from("ForwardingPU")), 16);
+        let (to_first, first_rx) = edge(&Downstream::Name(String::from("First")), 16);
+        let (to_second, second_rx) = edge(&Downstream::Name(String::from("Second")), 16);
+        let (pu_ctrl_tx, pu_ctrl_rx) = unbounded::<Controls>();
+
+        let pu = Arc::new(Mutex::new(ForwardingPU { stopped: Arc::new(AtomicBool::new(false)) }));
+        let task = tokio::spawn(run_pu(pu, vec![to_first], Inputs::new(vec![pu_rx], MergePolicy::Arrival, Timers::default()), pu_ctrl_rx, Supervisor::new("ForwardingPU", &RestartPolicy::Never), Arc::default()));
+
+        let send = |idx| input.send(Objects::MockV1(MockV1::new(uuid::uuid(), time::now_nanos(), idx, uuid::uuid())));
+        let settle = || tokio::time::sleep(std::time::Duration::from_millis(20));
+
+        send(0).await.unwrap();
+        settle().await;
+        pu_ctrl_tx.send(Controls::Connect(to_second)).unwrap();
+        settle().await;
+        send(1).await.unwrap();
+        settle().await;
+        pu_ctrl_tx.send(Controls::Disconnect(String::from("First"))).unwrap();
+        settle().await;
+        send(2).await.unwrap();
+        drop(input);
+        task.await.unwrap();
+
+        assert_eq!(first_rx.drain().map(|obj| obj.get_sequence_number()).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(second_rx.drain().map(|obj| obj.get_sequence_number()).collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::config::timer::{ Schedule, TimerConfig };
+use crate::error::HolocoreError;
+use crate::objects::{ Objects, timer::TimerV1 };
+use crate::utils::{ clock::{ Clock, WallClock }, uuid::{ IdGenerator, RandomIdGenerator } };
+
+use chrono::{ DateTime, Utc };
+use futures::future;
+use std::str::FromStr;
+use std::sync::Arc;
+
+enum Trigger {
+    // In nanoseconds
+    Interval(i64),
+    Cron(Box<cron::Schedule>)
+}
+
+impl Trigger {
+    // Deadline by the clock, None once a cron schedule has no occurrence left
+    fn next(&self, previous: i64, now: i64) -> Option<i64> {
+        match self {
+            // Ticks missed while the component was busy are skipped
+            Trigger::Interval(period) => Some(if previous.saturating_add(*period) > now { previous.saturating_add(*period) } else { now.saturating_add(*period) }),
+            Trigger::Cron(schedule) => schedule.after(&DateTime::<Utc>::from_timestamp_nanos(now)).next().and_then(|next| next.timestamp_nanos_opt())
+        }
+    }
+}
+
+struct Timer {
+    name: String,
+    trigger: Trigger,
+    // None until the timers are scheduled
+    deadline: Option<i64>
+}
+
+// Timers of a PU or a sink, fired as TimerV1 objects. They follow the clock, so that under a
+// replay clock they fire as the recording goes by rather than in wall time.
+pub struct Timers {
+    timers: Vec<Timer>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+    sequence_number: u64,
+    scheduled: bool
+}
+
+impl Timers {
+    // ids are those of the component, so that its timers follow its seed
+    pub fn new(cfgs: &[TimerConfig], clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Result<Self, HolocoreError> {
+        let mut timers = vec![];
+        for cfg in cfgs {
+            let trigger = match &cfg.schedule {
+                Schedule::Interval(0) => return Err(HolocoreError::Config(format!("Timer {} has an interval of 0 ms", cfg.name))),
+                Schedule::Interval(period) => Trigger::Interval(i64::try_from(*period).unwrap_or(i64::MAX).saturating_mul(1_000_000)),
+                Schedule::Cron(expression) => match cron::Schedule::from_str(expression) {
+                    Ok(schedule) => Trigger::Cron(Box::new(schedule)),
+                    Err(e) => return Err(HolocoreError::Config(format!("Timer {} has an invalid cron expression {} - {}", cfg.name, expression, e)))
+                }
+            };
+
+            timers.push(Timer { name: String::clone(&cfg.name), trigger, deadline: None });
+        }
+
+        Ok(Self {
+            timers,
+            clock,
+            ids,
+            sequence_number: 0,
+            scheduled: false
+        })
+    }
+
+    // Deadlines are taken from the first reading of the clock. A replay clock reads 0 until the
+    // sources emit, so the timers start with the recording rather than in 1970.
+    async fn schedule(&mut self) {
+        self.clock.sleep_until(1).await;
+        let now = self.clock.now_nanos();
+        self.timers.retain_mut(|timer| {
+            timer.deadline = timer.trigger.next(now, now);
+            if timer.deadline.is_none() {
+                log::warn!("Timer {} will never fire", timer.name);
+            }
+            timer.deadline.is_some()
+        });
+        self.scheduled = true;
+    }
+
+    // Waits for the next timer to fire, forever if there is none.
+    // Cancelling it loses nothing, a timer is only rescheduled once it fired.
+    pub async fn next(&mut self) -> Objects {
+        if self.timers.is_empty() {
+            return future::pending().await;
+        }
+
+        if !self.scheduled {
+            self.schedule().await;
+        }
+
+        let next = self.timers.iter()
+                              .enumerate()
+                              .filter_map(|(idx, timer)| timer.deadline.map(|deadline| (idx, deadline)))
+                              .min_by_key(|(_, deadline)| *deadline);
+        let Some((idx, deadline)) = next else {
+            return future::pending().await;
+        };
+
+        self.clock.sleep_until(deadline).await;
+        self.sequence_number += 1;
+        let timer = &mut self.timers[idx];
+        // Stamped with its deadline, which does not depend on how late the task woke up
+        let obj = TimerV1::new(self.ids.next_id(), deadline, self.sequence_number, self.ids.next_id(), &timer.name);
+        match timer.trigger.next(deadline, self.clock.now_nanos()) {
+            Some(deadline) => timer.deadline = Some(deadline),
+            None => {
+                self.timers.remove(idx);
+            }
+        }
+
+        Objects::TimerV1(obj)
+    }
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Self {
+            timers: vec![],
+            clock: Arc::new(WallClock),
+            ids: Arc::new(RandomIdGenerator),
+            sequence_number: 0,
+            scheduled: false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::timer::{ Schedule, TimerConfig };
+    use crate::objects::Objects;
+    use crate::scheduler::Timers;
+    use crate::utils::{ clock::{ Clock, ReplayClock, WallClock }, uuid::{ RandomIdGenerator, id_generator } };
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_intervals() {
+        let cfgs = vec![
+            TimerConfig { name: String::from("fast"), schedule: Schedule::Interval(100) },
+            TimerConfig { name: String::from("slow"), schedule: Schedule::Interval(250) }
+        ];
+        let mut timers = Timers::new(&cfgs, Arc::new(WallClock), Arc::new(RandomIdGenerator)).unwrap();
+
+        let mut names = vec![];
+        for _ in 0..4 {
+            match timers.next().await {
+                Objects::TimerV1(obj) => names.push(String::from(obj.get_name())),
+                obj => panic!("Unexpected {}", obj.get_type_name())
+            }
+        }
+        assert_eq!(names, vec!["fast", "fast", "slow", "fast"]);
+
+        let invalid = vec![TimerConfig { name: String::from("roll"), schedule: Schedule::Cron(String::from("every midnight")) }];
+        assert!(Timers::new(&invalid, Arc::new(WallClock), Arc::new(RandomIdGenerator)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_cron() {
+        let cfgs = vec![TimerConfig { name: String::from("new_year"), schedule: Schedule::Cron(String::from("0 0 0 1 1 *")) }];
+
+        let mut ids = vec![];
+        for _ in 0..2 {
+            // Created before the replay starts, as by the processor
+            let clock = Arc::new(ReplayClock::default());
+            let mut timers = Timers::new(&cfgs, clock.clone(), id_generator(Some(42), "MockPU")).unwrap();
+            let next = timers.next();
+            tokio::pin!(next);
+
+            // Scheduled from the first object, 10ms before 2030, not from 1970
+            assert!(tokio::time::timeout(Duration::ZERO, &mut next).await.is_err());
+            clock.advance(1_893_455_999_990_000_000);
+            assert!(tokio::time::timeout(Duration::ZERO, &mut next).await.is_err());
+
+            // Fires as soon as the recording goes past new year
+            clock.advance(1_893_456_000_500_000_000);
+            let fired = tokio::time::timeout(Duration::ZERO, &mut next).await.unwrap();
+            assert_eq!(fired.get_timestamp(), 1_893_456_000_000_000_000);
+            ids.push(fired.get_id());
+        }
+        assert_eq!(ids[0], ids[1]);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::components::control::Controls;
+use crate::config::restart::RestartPolicy;
+
+use flume::{ Receiver, RecvError, Sender };
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{ Arc, Mutex, atomic::{ AtomicU64, Ordering } };
+use std::time::Duration;
+
+// Why a component task stopped
+pub enum Exit {
+    // The component reported it has nothing more to do
+    Completed,
+    // recv, execute, send or init returned an error worth a restart
+    Failed(String),
+    // The component returned an error no restart can fix, the whole pipeline stops
+    Fatal(String),
+    // Shutdown requested, inputs drained or outputs gone
+    Stopped
+}
+
+// Restart counters of a component, shared with the control API
+#[derive(Default)]
+pub struct SupervisorStatus {
+    restarts: AtomicU64,
+    last_error: Mutex<Option<String>>
+}
+
+#[derive(Serialize)]
+pub struct SupervisorReport {
+    pub restarts: u64,
+    pub last_error: Option<String>
+}
+
+impl SupervisorStatus {
+    pub fn report(&self) -> SupervisorReport {
+        SupervisorReport {
+            restarts: self.restarts.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().map(|e| e.clone()).unwrap_or(None)
+        }
+    }
+}
+
+pub struct Supervisor {
+    name: String,
+    policy: RestartPolicy,
+    retries: u32,
+    status: Arc<SupervisorStatus>,
+    // Tells the processor to stop the pipeline
+    stop: Option<Sender<String>>,
+    // Control requests received during a backoff, handed over once the component runs again
+    deferred: VecDeque<Controls>
+}
+
+impl Supervisor {
+    pub fn new(name: &str, policy: &RestartPolicy) -> Self {
+        Self {
+            name: String::from(name),
+            policy: RestartPolicy::clone(policy),
+            retries: 0,
+            status: Arc::new(SupervisorStatus::default()),
+            stop: None,
+            deferred: VecDeque::new()
+        }
+    }
+
+    // Called after every object handled, so that only consecutive failures count towards max_retries
+    pub fn healthy(&mut self) {
+        self.retries = 0;
+    }
+
+    // Waits for the backoff and returns true if the component has to be restarted.
+    // A shutdown received while waiting cancels the restart, any other request is deferred.
+    pub async fn restart(&mut self, exit: &Exit, ctrl_rx: &Receiver<Controls>) -> bool {
+        if let Exit::Failed(e) | Exit::Fatal(e) = exit {
+            if let Ok(mut last_error) = self.status.last_error.lock() {
+                *last_error = Some(String::from(e));
+            }
+        }
+
+        if let Exit::Fatal(e) = exit {
+            match &self.stop {
+                Some(stop) => {
+                    // Fails only if the processor is already stopping
+                    let _ = stop.send(format!("Task {} failed - {}", self.name, e));
+                },
+                None => log::error!("Task {} failed and cannot stop the pipeline - {}", self.name, e)
+            }
+            return false;
+        }
+
+        let (backoff, max_backoff) = match (&self.policy, exit) {
+            (_, Exit::Stopped) | (_, Exit::Fatal(_)) | (RestartPolicy::Never, _) | (RestartPolicy::OnFailure { .. }, Exit::Completed) => return false,
+            (RestartPolicy::Always { backoff, max_backoff }, _) => (*backoff, *max_backoff),
+            (RestartPolicy::OnFailure { max_retries, backoff, max_backoff }, Exit::Failed(_)) => {
+                if self.retries >= *max_retries {
+                    log::error!("Task {} failed {} times in a row, giving up", self.name, self.retries + 1);
+                    return false;
+                }
+
+                (*backoff, *max_backoff)
+            }
+        };
+
+        let delay = backoff.saturating_mul(1 << self.retries.min(32)).min(max_backoff);
+        log::warn!("Task {} restarting in {} ms", self.name, delay);
+
+        let sleep = tokio::time::sleep(Duration::from_millis(delay));
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => break,
+                ctrl = ctrl_rx.recv_async() => match ctrl {
+                    Ok(Controls::Shutdown) | Err(_) => return false,
+                    Ok(ctrl) => self.deferred.push_back(ctrl)
+                }
+            }
+        }
+
+        self.retries += 1;
+        self.status.restarts.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    // Next control request of the component, those deferred during a backoff first
+    pub async fn next_control(&mut self, ctrl_rx: &Receiver<Controls>) -> Result<Controls, RecvError> {
+        match self.deferred.pop_front() {
+            Some(ctrl) => Ok(ctrl),
+            None => ctrl_rx.recv_async().await
+        }
+    }
+
+    // Getters
+    pub fn get_status(&self) -> Arc<SupervisorStatus> {
+        self.status.clone()
+    }
+
+    // Setters
+    pub fn set_stop(&mut self, stop: Sender<String>) {
+        self.stop = Some(stop);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::objects::hop::Hop;
+
+use serde::Serialize;
+use std::collections::{ HashMap, VecDeque };
+use std::sync::Mutex;
+use uuid::Uuid;
+
+// Completed traces kept for the control API
+const DEFAULT_CAPACITY: usize = 1024;
+
+// Time between two consecutive hops. It is spent inside a component when both hops are
+// stamped by it, and queueing between two components otherwise.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Segment {
+    hop: String,
+    nanos: u64
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Percentiles {
+    count: usize,
+    p50: u64,
+    p90: u64,
+    p99: u64,
+    max: u64
+}
+
+pub fn segments(hops: &[Hop]) -> Vec<Segment> {
+    hops.windows(2)
+        .map(|pair| {
+            let (from, to) = (&pair[0], &pair[1]);
+            let hop = if from.get_component() == to.get_component() {
+                String::from(from.get_component())
+            } else {
+                format!("{} -> {}", from.get_component(), to.get_component())
+            };
+
+            Segment { hop, nanos: to.get_timestamp().saturating_sub(from.get_timestamp()) }
+        })
+        .collect()
+}
+
+// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], rank: f64) -> u64 {
+    let idx = (rank * sorted.len() as f64).ceil() as usize;
+    sorted[idx.clamp(1, sorted.len()) - 1]
+}
+
+impl Percentiles {
+    // Getters
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+}
+
+// Traces of the objects which reached a sink, oldest first
+pub struct Traces {
+    capacity: usize,
+    traces: Mutex<VecDeque<(Uuid, Vec<Segment>)>>
+}
+
+impl Default for Traces {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl Traces {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            traces: Mutex::new(VecDeque::with_capacity(capacity))
+        }
+    }
+
+    pub fn record(&self, correlation_id: Uuid, hops: &[Hop]) {
+        if let Ok(mut traces) = self.traces.lock() {
+            if traces.len() == self.capacity {
+                traces.pop_front();
+            }
+            traces.push_back((correlation_id, segments(hops)));
+        }
+    }
+
+    // An object can reach several sinks, each of them recording its own trace
+    pub fn get(&self, correlation_id: Uuid) -> Vec<Vec<Segment>> {
+        match self.traces.lock() {
+            Ok(traces) => traces.iter()
+                                .filter(|(id, _)| *id == correlation_id)
+                                .map(|(_, segments)| segments.clone())
+                                .collect(),
+            Err(_) => vec![]
+        }
+    }
+
+    // Percentiles of every hop over the kept traces
+    pub fn percentiles(&self) -> HashMap<String, Percentiles> {
+        let mut nanos = HashMap::<String, Vec<u64>>::new();
+        if let Ok(traces) = self.traces.lock() {
+            for segment in traces.iter().flat_map(|(_, segments)| segments) {
+                nanos.entry(String::clone(&segment.hop)).or_default().push(segment.nanos);
+            }
+        }
+
+        nanos.into_iter()
+             .map(|(hop, mut values)| {
+                 values.sort_unstable();
+                 let percentiles = Percentiles {
+                     count: values.len(),
+                     p50: percentile(&values, 0.5),
+                     p90: percentile(&values, 0.9),
+                     p99: percentile(&values, 0.99),
+                     max: values[values.len() - 1]
+                 };
+                 (hop, percentiles)
+             })
+             .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::hop::Hop;
+    use crate::trace::{ Traces, percentile };
+    use crate::utils::uuid;
+
+    #[test]
+    fn test_percentile() {
+        let values = (1..=100).collect::<Vec<u64>>();
+        assert_eq!(percentile(&values, 0.5), 50);
+        assert_eq!(percentile(&values, 0.99), 99);
+        assert_eq!(percentile(&[7], 0.5), 7);
+    }
+
+    #[test]
+    fn test_traces() {
+        let traces = Traces::new(2);
+        let hops = [ Hop::new("Source"), Hop::new("PU"), Hop::new("PU"), Hop::new("Sink") ];
+        let first = uuid::uuid();
+        traces.record(first, &hops);
+        traces.record(uuid::uuid(), &hops);
+
+        let recorded = traces.get(first);
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].iter().map(|segment| segment.hop.as_str()).collect::<Vec<_>>(), vec!["Source -> PU", "PU", "PU -> Sink"]);
+
+        // Only the last two traces are kept
+        traces.record(uuid::uuid(), &hops);
+        assert!(traces.get(first).is_empty());
+        assert_eq!(traces.percentiles()["PU"].count, 2);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::HolocoreError;
+use crate::objects::{ Objects, schema };
+
+// A record is the type id and the version of the object, the payload length, all big-endian,
+// then the object encoded with bincode. Fixed-point fields keep their bits.
+pub const HEADER_SIZE: usize = 8;
+
+// Guards against reading garbage as a huge length
+pub const MAX_RECORD_SIZE: usize = 16 * 1024 * 1024;
+
+pub struct Header {
+    pub type_id: u16,
+    pub version: u16,
+    pub len: usize
+}
+
+impl Header {
+    pub fn parse(bytes: &[u8; HEADER_SIZE]) -> Result<Self, HolocoreError> {
+        let header = Self {
+            type_id: u16::from_be_bytes([bytes[0], bytes[1]]),
+            version: u16::from_be_bytes([bytes[2], bytes[3]]),
+            len: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize
+        };
+
+        if header.len > MAX_RECORD_SIZE {
+            return Err(HolocoreError::Malformed(format!("Record of type {} and {} bytes exceeds the record size", header.type_id, header.len)));
+        }
+
+        Ok(header)
+    }
+}
+
+fn malformed(e: bincode::Error) -> HolocoreError {
+    HolocoreError::Malformed(format!("Cannot encode object - {}", e))
+}
+
+pub fn encode(obj: &Objects) -> Result<Vec<u8>, HolocoreError> {
+    let payload = match obj {
+        Objects::MockV1(obj) => bincode::serialize(obj),
+        Objects::KlineV1(obj) => bincode::serialize(obj),
+        Objects::KlineV2(obj) => bincode::serialize(obj),
+        Objects::NewOrderV1(obj) => bincode::serialize(obj),
+        Objects::NewOrderV2(obj) => bincode::serialize(obj),
+        Objects::OrderbookV1(obj) => bincode::serialize(obj),
+        Objects::OrderbookV2(obj) => bincode::serialize(obj),
+        Objects::OrderbookUpdateV1(obj) => bincode::serialize(obj),
+        Objects::OrderbookUpdateV2(obj) => bincode::serialize(obj),
+        Objects::TimerV1(obj) => bincode::serialize(obj),
+        Objects::TradeV1(obj) => bincode::serialize(obj),
+        Objects::TradeV2(obj) => bincode::serialize(obj),
+        Objects::AggTradeV1(obj) => bincode::serialize(obj),
+        Objects::BookTickerV1(obj) => bincode::serialize(obj),
+        Objects::TickerV1(obj) => bincode::serialize(obj)
+    }.map_err(malformed)?;
+    if payload.len() > MAX_RECORD_SIZE {
+        return Err(HolocoreError::Malformed(format!("Object of {} bytes exceeds the record size", payload.len())));
+    }
+
+    let type_id = u16::try_from(obj.get_type()).map_err(|_| HolocoreError::Malformed(format!("Type {} does not fit a record", obj.get_type())))?;
+    let version = u16::try_from(obj.get_version()).map_err(|_| HolocoreError::Malformed(format!("Version {} does not fit a record", obj.get_version())))?;
+
+    let mut record = Vec::with_capacity(HEADER_SIZE + payload.len());
+    record.extend_from_slice(&type_id.to_be_bytes());
+    record.extend_from_slice(&version.to_be_bytes());
+    record.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    record.extend_from_slice(&payload);
+    Ok(record)
+}
+
+// Decodes the payload of a record with the schema of the type id and version of its header
+pub fn decode(header: &Header, payload: &[u8]) -> Result<Objects, HolocoreError> {
+    match schema::find(u64::from(header.type_id), u64::from(header.version)) {
+        Some(schema) => (schema.decode)(payload).map_err(|e| HolocoreError::Malformed(format!("Cannot decode {} - {}", schema.name, e))),
+        None => Err(HolocoreError::Malformed(format!("Unknown object type {} version {}", header.type_id, header.version)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::sources::binance::model::{ BinanceAsks, BinanceBids, BinanceOrderBookEvent };
+    use crate::objects::{ Objects, orderbook::OrderbookV2 };
+    use crate::utils::binary::{ HEADER_SIZE, Header, decode, encode };
+    use crate::utils::uuid;
+
+    #[test]
+    fn test_records() {
+        let event = BinanceOrderBookEvent {
+            last_update_id: 7,
+            bids: vec![ BinanceBids { price: String::from("100.25"), qty: String::from("1.5") } ],
+            asks: vec![ BinanceAsks { price: String::from("100.5"), qty: String::from("0.125") } ]
+        };
+        let mut obj = Objects::OrderbookV2(OrderbookV2::from_binance(uuid::uuid(), 42, 3, uuid::uuid(), "BinanceSpotSource", "Binance", "Spot", "BTCUSDT", event));
+        obj.start_trace("BinanceSpotSource");
+
+        let record = encode(&obj).unwrap();
+        let header = Header::parse(record[..HEADER_SIZE].try_into().unwrap()).unwrap();
+        assert_eq!((header.type_id, header.version, header.len), (19, 2, record.len() - HEADER_SIZE));
+        assert!(record.len() < serde_json::to_vec(&obj).unwrap().len());
+
+        let decoded = decode(&header, &record[HEADER_SIZE..]).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), serde_json::to_string(&obj).unwrap());
+
+        let unknown = Header { type_id: 19, version: 9, len: header.len };
+        assert!(decode(&unknown, &record[HEADER_SIZE..]).is_err());
+        assert!(Header::parse(&[0, 19, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::utils::time;
+
+use async_trait::async_trait;
+use serde::{ Deserialize, Serialize };
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicI64, Ordering };
+use std::time::Duration;
+use tokio::sync::Notify;
+
+// Time given to the components, in nanoseconds since the epoch
+#[async_trait]
+pub trait Clock: Send + Sync {
+    fn now_nanos(&self) -> i64;
+
+    // Called with the timestamp of every object emitted by a source
+    fn advance(&self, _timestamp: i64) {}
+
+    // Returns once the clock reads timestamp or later
+    async fn sleep_until(&self, timestamp: i64) {
+        let delay = timestamp.saturating_sub(self.now_nanos()).max(0);
+        tokio::time::sleep(Duration::from_nanos(delay as u64)).await;
+    }
+}
+
+#[derive(Default)]
+pub struct WallClock;
+
+#[async_trait]
+impl Clock for WallClock {
+    fn now_nanos(&self) -> i64 {
+        time::now_nanos()
+    }
+}
+
+// Follows the timestamps of the objects read by the sources, so that a replay does not
+// depend on when or how fast it runs
+#[derive(Default)]
+pub struct ReplayClock {
+    now: AtomicI64,
+    advanced: Notify
+}
+
+#[async_trait]
+impl Clock for ReplayClock {
+    fn now_nanos(&self) -> i64 {
+        self.now.load(Ordering::Acquire)
+    }
+
+    fn advance(&self, timestamp: i64) {
+        if self.now.fetch_max(timestamp, Ordering::AcqRel) < timestamp {
+            self.advanced.notify_waiters();
+        }
+    }
+
+    // Waits for the sources to bring the clock there, however long it takes in wall time
+    async fn sleep_until(&self, timestamp: i64) {
+        loop {
+            let advanced = self.advanced.notified();
+            tokio::pin!(advanced);
+            // Registered before reading the clock, so that no advance is missed in between
+            advanced.as_mut().enable();
+            if self.now_nanos() >= timestamp {
+                return;
+            }
+
+            advanced.await;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClockKind {
+    #[default]
+    Wall,
+    Replay
+}
+
+pub fn clock(kind: ClockKind) -> Arc<dyn Clock> {
+    match kind {
+        ClockKind::Wall => Arc::new(WallClock),
+        ClockKind::Replay => Arc::new(ReplayClock::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::clock::{ Clock, ReplayClock };
+
+    #[test]
+    fn test_replay_clock() {
+        let clock = ReplayClock::default();
+        clock.advance(20);
+        clock.advance(10);
+        assert_eq!(clock.now_nanos(), 20);
+    }
+}
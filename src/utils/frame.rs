@@ -0,0 +1,68 @@
+use crate::error::HolocoreError;
+use crate::objects::Objects;
+
+// A frame is the sequence number, the payload length, both big-endian, then the serialized object
+pub const HEADER_SIZE: usize = 12;
+
+// Guards against reading garbage as a huge length
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+pub fn encode(sequence: u64, obj: &Objects) -> Result<Vec<u8>, HolocoreError> {
+    let payload = serde_json::to_vec(obj).map_err(|e| HolocoreError::Malformed(format!("Cannot serialize object - {}", e)))?;
+    if payload.len() > MAX_FRAME_SIZE {
+        return Err(HolocoreError::Malformed(format!("Object of {} bytes exceeds the frame size", payload.len())));
+    }
+
+    let mut frame = Vec::with_capacity(HEADER_SIZE + payload.len());
+    frame.extend_from_slice(&sequence.to_be_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+// Takes the first frame out of buf, if it has been fully read. A frame that cannot be deserialized
+// is dropped, while a length out of bounds means the stream is out of sync.
+pub fn decode(buf: &mut Vec<u8>) -> Result<Option<(u64, Objects)>, HolocoreError> {
+    if buf.len() < HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let sequence = u64::from_be_bytes(buf[..8].try_into().unwrap());
+    let len = u32::from_be_bytes(buf[8..HEADER_SIZE].try_into().unwrap()) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(HolocoreError::Io(format!("Frame {} of {} bytes exceeds the frame size", sequence, len)));
+    }
+    if buf.len() < HEADER_SIZE + len {
+        return Ok(None);
+    }
+
+    let result = serde_json::from_slice(&buf[HEADER_SIZE..HEADER_SIZE + len]);
+    buf.drain(..HEADER_SIZE + len);
+    match result {
+        Ok(obj) => Ok(Some((sequence, obj))),
+        Err(e) => Err(HolocoreError::Malformed(format!("Cannot deserialize frame {} - {}", sequence, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::{ Objects, mock::MockV1 };
+    use crate::utils::frame::{ decode, encode };
+    use crate::utils::uuid;
+
+    #[test]
+    fn test_frames() {
+        let obj = Objects::MockV1(MockV1::new(uuid::uuid(), 1, 7, uuid::uuid()));
+        let mut buf = encode(41, &obj).unwrap();
+        buf.extend(encode(42, &obj).unwrap());
+
+        // A partial frame is left in the buffer until the rest arrives
+        let mut partial = buf[..buf.len() - 1].to_vec();
+        assert_eq!(decode(&mut partial).unwrap().map(|(sequence, _)| sequence), Some(41));
+        assert!(decode(&mut partial).unwrap().is_none());
+
+        assert!(matches!(decode(&mut buf).unwrap(), Some((41, Objects::MockV1(_)))));
+        assert!(matches!(decode(&mut buf).unwrap(), Some((42, Objects::MockV1(_)))));
+        assert!(buf.is_empty());
+    }
+}
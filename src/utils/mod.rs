@@ -1,4 +1,9 @@
+pub mod binary;
+pub mod clock;
+pub mod frame;
 pub mod http;
 pub mod redis;
+pub mod shm;
+pub mod threads;
 pub mod time;
 pub mod uuid;
@@ -1,6 +1,8 @@
+use crate::error::HolocoreError;
+
 use redis::Commands;
 
-pub fn get(url: &str, key: &str) -> Result<String, String> {
+pub fn get(url: &str, key: &str) -> Result<String, HolocoreError> {
     match redis::Client::open(format!("redis://{}/", url)) {
         Ok(client) => match client.get_connection() {
             Ok(mut connection) => {
@@ -9,20 +11,20 @@ pub fn get(url: &str, key: &str) -> Result<String, String> {
                     Err(e) => {
                         let err_msg = format!("Cannot get value from key {} - {}", key, e);
                         log::error!("{}", &err_msg);
-                        Err(err_msg)
+                        Err(HolocoreError::Network(err_msg))
                     }
                 }
             },
             Err(e) => {
                 let err_msg = format!("Cannot connect to redis - {}", e);
                 log::error!("{}", &err_msg);
-                Err(err_msg)
+                Err(HolocoreError::Network(err_msg))
             }
         },
         Err(e) => {
             let err_msg = format!("Cannot open client with {} - {}", url, e);
             log::error!("{}", &err_msg);
-            Err(err_msg)
+            Err(HolocoreError::Config(err_msg))
         }
     }
 }
@@ -0,0 +1,237 @@
+use crate::error::HolocoreError;
+use crate::utils::time::now_nanos;
+
+use memmap2::{ Mmap, MmapMut, MmapOptions };
+use std::fs::{ File, OpenOptions };
+use std::ptr;
+use std::sync::atomic::{ AtomicU64, Ordering, fence };
+
+// Single-producer, multi-consumer ring buffer over a memory-mapped file.
+//
+// The header holds the layout, an epoch changed by every writer, and the number of published
+// messages. Message n goes to slot n % capacity, whose own sequence is set to n + 1 once it is
+// written, so that a reader can tell a slot that was overwritten while it was copying it.
+const MAGIC: u64 = 0x484f_4c4f_5249_4e47;
+const HEADER_SIZE: usize = 128;
+const SLOT_HEADER_SIZE: usize = 16;
+const WRITING: u64 = u64::MAX;
+
+const MAGIC_OFFSET: usize = 0;
+const EPOCH_OFFSET: usize = 8;
+const CAPACITY_OFFSET: usize = 16;
+const SLOT_SIZE_OFFSET: usize = 24;
+// On its own cache line, as it is the only header field written per message
+const WRITE_SEQ_OFFSET: usize = 64;
+
+fn atomic(base: *const u8, offset: usize) -> &'static AtomicU64 {
+    unsafe { &*(base.add(offset) as *const AtomicU64) }
+}
+
+fn mapped_size(capacity: u64, slot_size: u64) -> u64 {
+    HEADER_SIZE as u64 + capacity * slot_size
+}
+
+pub struct ShmWriter {
+    mmap: MmapMut,
+    capacity: u64,
+    slot_size: u64,
+    sequence: u64
+}
+
+// The mapping is only written through this struct, which has a single owner
+unsafe impl Send for ShmWriter {}
+
+impl ShmWriter {
+    pub fn create(path: &str, capacity: u64, slot_size: u64) -> Result<Self, HolocoreError> {
+        if capacity == 0 || slot_size <= SLOT_HEADER_SIZE as u64 || !slot_size.is_multiple_of(8) {
+            return Err(HolocoreError::Config(format!("Invalid ring layout for {} - capacity {}, slot size {}", path, capacity, slot_size)));
+        }
+
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)
+                                     .map_err(|e| HolocoreError::Io(format!("Cannot open {} - {}", path, e)))?;
+        file.set_len(mapped_size(capacity, slot_size)).map_err(|e| HolocoreError::Io(format!("Cannot resize {} - {}", path, e)))?;
+        let mmap = unsafe { MmapOptions::new().map_mut(&file) }.map_err(|e| HolocoreError::Io(format!("Cannot map {} - {}", path, e)))?;
+
+        // Readers of a previous writer notice the new epoch and skip to the live messages
+        let base = mmap.as_ptr();
+        atomic(base, MAGIC_OFFSET).store(0, Ordering::Release);
+        atomic(base, CAPACITY_OFFSET).store(capacity, Ordering::Relaxed);
+        atomic(base, SLOT_SIZE_OFFSET).store(slot_size, Ordering::Relaxed);
+        atomic(base, WRITE_SEQ_OFFSET).store(0, Ordering::Relaxed);
+        for slot in 0..capacity {
+            atomic(base, HEADER_SIZE + (slot * slot_size) as usize).store(0, Ordering::Relaxed);
+        }
+        atomic(base, EPOCH_OFFSET).store(now_nanos() as u64, Ordering::Relaxed);
+        atomic(base, MAGIC_OFFSET).store(MAGIC, Ordering::Release);
+
+        Ok(Self { mmap, capacity, slot_size, sequence: 0 })
+    }
+
+    pub fn push(&mut self, payload: &[u8]) -> Result<(), HolocoreError> {
+        if payload.len() > self.slot_size as usize - SLOT_HEADER_SIZE {
+            return Err(HolocoreError::Malformed(format!("Message of {} bytes exceeds the slot size {}", payload.len(), self.slot_size)));
+        }
+
+        let base = self.mmap.as_mut_ptr();
+        let slot = HEADER_SIZE + ((self.sequence % self.capacity) * self.slot_size) as usize;
+        let slot_seq = atomic(base, slot);
+        slot_seq.store(WRITING, Ordering::Relaxed);
+        fence(Ordering::Release);
+        unsafe {
+            ptr::copy_nonoverlapping((payload.len() as u32).to_le_bytes().as_ptr(), base.add(slot + 8), 4);
+            ptr::copy_nonoverlapping(payload.as_ptr(), base.add(slot + SLOT_HEADER_SIZE), payload.len());
+        }
+
+        self.sequence += 1;
+        slot_seq.store(self.sequence, Ordering::Release);
+        atomic(base, WRITE_SEQ_OFFSET).store(self.sequence, Ordering::Release);
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ShmRead {
+    Empty,
+    Message(Vec<u8>),
+    // The writer lapped the reader, which skipped that many messages
+    Overrun(u64),
+    // A new writer took over the file, the reader resumed from its live messages
+    Restarted
+}
+
+pub struct ShmReader {
+    mmap: Mmap,
+    epoch: u64,
+    capacity: u64,
+    slot_size: u64,
+    // Next message to read
+    cursor: u64
+}
+
+// The mapping is read-only and every reader has its own cursor
+unsafe impl Send for ShmReader {}
+
+impl ShmReader {
+    // Starts from the messages published after the call
+    pub fn open(path: &str) -> Result<Self, HolocoreError> {
+        let file = File::open(path).map_err(|e| HolocoreError::Io(format!("Cannot open {} - {}", path, e)))?;
+        let len = file.metadata().map_err(|e| HolocoreError::Io(format!("Cannot stat {} - {}", path, e)))?.len();
+        if len < HEADER_SIZE as u64 {
+            return Err(HolocoreError::Io(format!("{} is not a ring buffer", path)));
+        }
+
+        let mmap = unsafe { MmapOptions::new().map(&file) }.map_err(|e| HolocoreError::Io(format!("Cannot map {} - {}", path, e)))?;
+        let base = mmap.as_ptr();
+        if atomic(base, MAGIC_OFFSET).load(Ordering::Acquire) != MAGIC {
+            return Err(HolocoreError::Io(format!("{} is not a ring buffer or is being initialized", path)));
+        }
+
+        let capacity = atomic(base, CAPACITY_OFFSET).load(Ordering::Relaxed);
+        let slot_size = atomic(base, SLOT_SIZE_OFFSET).load(Ordering::Relaxed);
+        if len < mapped_size(capacity, slot_size) {
+            return Err(HolocoreError::Io(format!("{} is smaller than its ring layout", path)));
+        }
+
+        Ok(Self {
+            epoch: atomic(base, EPOCH_OFFSET).load(Ordering::Relaxed),
+            cursor: atomic(base, WRITE_SEQ_OFFSET).load(Ordering::Acquire),
+            mmap,
+            capacity,
+            slot_size
+        })
+    }
+
+    pub fn read(&mut self) -> Result<ShmRead, HolocoreError> {
+        let base = self.mmap.as_ptr();
+        if atomic(base, MAGIC_OFFSET).load(Ordering::Acquire) != MAGIC {
+            return Ok(ShmRead::Empty);
+        }
+
+        if atomic(base, EPOCH_OFFSET).load(Ordering::Relaxed) != self.epoch {
+            // The mapping only covers the layout it was opened with
+            if atomic(base, CAPACITY_OFFSET).load(Ordering::Relaxed) != self.capacity ||
+               atomic(base, SLOT_SIZE_OFFSET).load(Ordering::Relaxed) != self.slot_size {
+                return Err(HolocoreError::Io(String::from("ring layout changed, the file has to be opened again")));
+            }
+
+            self.epoch = atomic(base, EPOCH_OFFSET).load(Ordering::Relaxed);
+            self.cursor = atomic(base, WRITE_SEQ_OFFSET).load(Ordering::Acquire);
+            return Ok(ShmRead::Restarted);
+        }
+
+        let written = atomic(base, WRITE_SEQ_OFFSET).load(Ordering::Acquire);
+        if self.cursor >= written {
+            return Ok(ShmRead::Empty);
+        }
+        // The slot of the oldest message may already be rewritten with the next one
+        if written - self.cursor >= self.capacity {
+            return Ok(self.overrun(written));
+        }
+
+        let slot = HEADER_SIZE + ((self.cursor % self.capacity) * self.slot_size) as usize;
+        let slot_seq = atomic(base, slot);
+        if slot_seq.load(Ordering::Acquire) != self.cursor + 1 {
+            return Ok(self.overrun(atomic(base, WRITE_SEQ_OFFSET).load(Ordering::Acquire)));
+        }
+
+        let mut len = [0u8; 4];
+        unsafe { ptr::copy_nonoverlapping(base.add(slot + 8), len.as_mut_ptr(), 4) };
+        let len = (u32::from_le_bytes(len) as usize).min(self.slot_size as usize - SLOT_HEADER_SIZE);
+        let mut payload = vec![0u8; len];
+        unsafe { ptr::copy_nonoverlapping(base.add(slot + SLOT_HEADER_SIZE), payload.as_mut_ptr(), len) };
+
+        // The copy is only valid if the writer did not come back to the slot meanwhile
+        fence(Ordering::Acquire);
+        if slot_seq.load(Ordering::Relaxed) != self.cursor + 1 {
+            return Ok(self.overrun(atomic(base, WRITE_SEQ_OFFSET).load(Ordering::Acquire)));
+        }
+
+        self.cursor += 1;
+        Ok(ShmRead::Message(payload))
+    }
+
+    // Skips to the oldest message that cannot be overwritten before it is read
+    fn overrun(&mut self, written: u64) -> ShmRead {
+        let oldest = (written + 1).saturating_sub(self.capacity).max(self.cursor + 1);
+        let skipped = oldest - self.cursor;
+        self.cursor = oldest;
+        ShmRead::Overrun(skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::shm::{ ShmRead, ShmReader, ShmWriter };
+
+    #[test]
+    fn test_ring() {
+        let path = std::env::temp_dir().join("holocore_test_ring");
+        let path = path.to_str().unwrap();
+        let mut writer = ShmWriter::create(path, 4, 64).unwrap();
+        let mut reader = ShmReader::open(path).unwrap();
+        assert_eq!(reader.read().unwrap(), ShmRead::Empty);
+        assert!(writer.push(&[0u8; 49]).is_err());
+
+        writer.push(b"first").unwrap();
+        writer.push(b"second").unwrap();
+        assert_eq!(reader.read().unwrap(), ShmRead::Message(b"first".to_vec()));
+        assert_eq!(reader.read().unwrap(), ShmRead::Message(b"second".to_vec()));
+        assert_eq!(reader.read().unwrap(), ShmRead::Empty);
+
+        // Messages 3 to 10 overrun a ring of 4 slots, the reader resumes from message 8
+        for idx in 3..=10u8 {
+            writer.push(&[idx]).unwrap();
+        }
+        assert_eq!(reader.read().unwrap(), ShmRead::Overrun(5));
+        assert_eq!(reader.read().unwrap(), ShmRead::Message(vec![8]));
+
+        // A new writer starts another epoch
+        let mut writer = ShmWriter::create(path, 4, 64).unwrap();
+        writer.push(b"lost").unwrap();
+        assert_eq!(reader.read().unwrap(), ShmRead::Restarted);
+        writer.push(b"live").unwrap();
+        assert_eq!(reader.read().unwrap(), ShmRead::Message(b"live".to_vec()));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
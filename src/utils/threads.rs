@@ -0,0 +1,70 @@
+use crate::error::HolocoreError;
+
+use std::future::Future;
+use std::panic::{ self, AssertUnwindSafe };
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+// Runs task on a current-thread runtime of its own OS thread, pinned to core if set.
+// The handle completes along with the thread, and fails with a panic if the task panics,
+// but aborting it does not stop the thread.
+pub fn spawn_dedicated(name: &str, core: Option<usize>, stack_size: usize, task: impl Future<Output = ()> + Send + 'static) -> Result<JoinHandle<()>, HolocoreError> {
+    let (done_tx, done_rx) = oneshot::channel::<std::thread::Result<()>>();
+    let thread_name = String::from(name);
+    std::thread::Builder::new()
+        .name(String::from(name))
+        .stack_size(stack_size)
+        .spawn(move || {
+            if let Some(core) = core {
+                match core_affinity::set_for_current(core_affinity::CoreId { id: core }) {
+                    true => log::info!("Thread {} pinned to core {}", thread_name, core),
+                    false => log::error!("Cannot pin thread {} to core {}", thread_name, core)
+                }
+            }
+
+            let result = panic::catch_unwind(AssertUnwindSafe(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .expect("Cannot build runtime");
+                runtime.block_on(task);
+            }));
+            // Fails only if the processor has stopped waiting for the task
+            let _ = done_tx.send(result);
+        })
+        .map_err(|e| HolocoreError::Internal(format!("Cannot spawn thread {} - {}", name, e)))?;
+
+    // Panics again on the shared runtime, so that awaiting the handle reports the panic
+    let name = String::from(name);
+    Ok(tokio::spawn(async move {
+        match done_rx.await {
+            Ok(Ok(())) => (),
+            Ok(Err(payload)) => panic::resume_unwind(payload),
+            Err(_) => panic!("Thread {} stopped before its task completed", name)
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::threads::spawn_dedicated;
+
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn test_dedicated_thread() {
+        let (name_tx, name_rx) = oneshot::channel();
+        let handle = spawn_dedicated("Dedicated", None, 2 * 1024 * 1024, async move {
+            let _ = name_tx.send(std::thread::current().name().map(String::from));
+        }).unwrap();
+
+        handle.await.unwrap();
+        assert_eq!(name_rx.await.unwrap().as_deref(), Some("Dedicated"));
+
+        // A panic of the task fails the handle
+        let handle = spawn_dedicated("Panicking", None, 2 * 1024 * 1024, async move {
+            panic!("Task failed");
+        }).unwrap();
+        assert!(handle.await.unwrap_err().is_panic());
+    }
+}
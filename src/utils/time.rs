@@ -1,4 +1,6 @@
 use chrono::{NaiveDate, NaiveTime, NaiveDateTime, Utc};
+use std::sync::OnceLock;
+use std::time::Instant;
 
 pub fn now() -> (i64, u32) {
     let now = Utc::now();
@@ -20,6 +22,13 @@ pub fn now_nanos() -> i64 {
     now.timestamp_nanos()
 }
 
+// Nanoseconds since the first call in this process. Unlike now_nanos it never goes backwards,
+// but it cannot be compared across processes
+pub fn monotonic_nanos() -> u64 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
 pub fn datetime(year: i32, month: u32, day: u32, hour: u32, minutes: u32, seconds: u32) -> String {
     let date = NaiveDate::from_ymd(year, month, day);
     let time = NaiveTime::from_hms(hour, minutes, seconds);
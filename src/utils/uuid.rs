@@ -1,5 +1,83 @@
-use uuid::Uuid;
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicU64, Ordering };
+use uuid::{ Builder, Uuid };
 
 pub fn uuid() -> Uuid {
     Uuid::new_v4()
 }
+
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> Uuid;
+}
+
+#[derive(Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn next_id(&self) -> Uuid {
+        uuid()
+    }
+}
+
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// v4 UUIDs drawn from a splitmix64 sequence, the same for the same seed
+pub struct SeededIdGenerator {
+    state: AtomicU64
+}
+
+impl SeededIdGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed)
+        }
+    }
+}
+
+impl IdGenerator for SeededIdGenerator {
+    fn next_id(&self) -> Uuid {
+        let state = self.state.fetch_add(GAMMA.wrapping_mul(2), Ordering::Relaxed);
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&mix(state.wrapping_add(GAMMA)).to_be_bytes());
+        bytes[8..].copy_from_slice(&mix(state.wrapping_add(GAMMA.wrapping_mul(2))).to_be_bytes());
+        Builder::from_random_bytes(bytes).into_uuid()
+    }
+}
+
+// FNV-1a, which unlike the std hashers is stable across builds
+fn stable_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+// Every component gets a sequence of its own, so that its IDs do not depend on how the
+// components are scheduled
+pub fn id_generator(seed: Option<u64>, component: &str) -> Arc<dyn IdGenerator> {
+    match seed {
+        Some(seed) => Arc::new(SeededIdGenerator::new(seed ^ stable_hash(component))),
+        None => Arc::new(RandomIdGenerator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::uuid::id_generator;
+
+    #[test]
+    fn test_seeded_ids() {
+        let first = id_generator(Some(42), "FileSource");
+        let second = id_generator(Some(42), "FileSource");
+        let other = id_generator(Some(42), "MockSource");
+
+        let id = first.next_id();
+        assert_eq!(id.get_version_num(), 4);
+        assert_eq!(id, second.next_id());
+        assert_ne!(id, other.next_id());
+        assert_ne!(first.next_id(), id);
+    }
+}